@@ -1,4 +1,5 @@
 use std::borrow::Cow;
+use std::io::ErrorKind;
 use std::path::Path;
 
 use clap::{app_from_crate, crate_authors, crate_description, crate_name, crate_version, Arg};
@@ -11,8 +12,12 @@ struct OsFileResolver;
 impl KirlFileResolver for OsFileResolver {
     type ResolveError = std::io::Error;
 
-    fn resolve_file_by_path(&mut self, path: impl AsRef<Path>) -> Result<Cow<str>, Self::ResolveError> {
-        std::fs::read_to_string(path).map(Cow::from)
+    fn resolve_file_by_path(&mut self, path: impl AsRef<Path>) -> Result<Option<Cow<str>>, Self::ResolveError> {
+        match std::fs::read_to_string(path) {
+            Ok(file) => Ok(Some(Cow::from(file))),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e),
+        }
     }
 }
 
@@ -20,10 +25,7 @@ fn main() -> Result<(), String> {
     let matches = app_from_crate!().arg(Arg::with_name("entrypoint").help("kirl program file for entrypoint").takes_value(true).required(true)).get_matches();
     let entrypoint = matches.value_of("entrypoint").unwrap();
     match compile(&mut OsFileResolver, &entrypoint) {
-        Ok(executable) => {
-            exec(&executable);
-            Ok(())
-        }
+        Ok(executable) => exec(&executable).map(|_| ()).map_err(|err| format!("{}", err)),
         Err(err) => Err(format!("{}", err)),
     }
 }
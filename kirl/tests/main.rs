@@ -301,3 +301,869 @@ create_test! {
 value: 42
 ";
 }
+
+create_test! {
+    name: test_9;
+    map: {
+        9: {
+            main
+        }
+    };
+    entry: "9/main.kirl";
+    out: "42
+18
+21
+15
+";
+}
+
+create_failue_test! {
+    name: test_9_capture_assign;
+    map: {
+        9: {
+            capture_assign
+        }
+    };
+    entry: "9/capture_assign.kirl";
+    out: "variable \\\"c\\\" is captured by a closure and cannot be assigned in it.";
+}
+
+create_test! {
+    name: test_10;
+    map: {
+        10: {
+            main
+        }
+    };
+    entry: "10/main.kirl";
+    out: "42
+10
+3
+";
+}
+
+create_failue_test! {
+    name: test_10_mismatch;
+    map: {
+        10: {
+            mismatch
+        }
+    };
+    entry: "10/mismatch.kirl";
+    out: "has multiple candidates double:(Number)->Number.";
+}
+
+create_test! {
+    name: test_11;
+    map: {
+        11: {
+            main
+        }
+    };
+    entry: "11/main.kirl";
+    out: "1
+text
+5
+";
+}
+
+create_failue_test! {
+    name: test_11_error;
+    map: {
+        11: {
+            error
+        }
+    };
+    entry: "11/error.kirl";
+    out: "Error: \"A match is not exhaustive. Values of type (Number, Number) are not covered.\"
+";
+}
+
+create_test! {
+    name: test_11_global;
+    map: {
+        11: {
+            global
+        }
+    };
+    entry: "11/global.kirl";
+    out: "global
+7
+";
+}
+
+create_test! {
+    name: test_11_nested;
+    map: {
+        11: {
+            nested
+        }
+    };
+    entry: "11/nested.kirl";
+    out: "1
+text
+";
+}
+
+create_failue_test! {
+    name: test_11_unreachable_arm;
+    map: {
+        11: {
+            unreachable_arm
+        }
+    };
+    entry: "11/unreachable_arm.kirl";
+    out: "A match arm with pattern #{number: $Infer} never matches a value of type !.";
+}
+
+create_test! {
+    name: test_12;
+    map: {
+        12: {
+            main,
+            shapes
+        }
+    };
+    entry: "12/main.kirl";
+    out: "3
+point
+anonymous
+1
+five
+3
+";
+}
+
+create_failue_test! {
+    name: test_12_error;
+    map: {
+        12: {
+            error
+        }
+    };
+    entry: "12/error.kirl";
+    out: "Error: \"A struct Point is not found.\"
+";
+}
+
+create_failue_test! {
+    name: test_12_unknown_type;
+    map: {
+        12: {
+            unknown_type
+        }
+    };
+    entry: "12/unknown_type.kirl";
+    out: "Error: \"A struct Radius is not found.\"
+";
+}
+
+create_failue_test! {
+    name: test_12_mismatch;
+    map: {
+        12: {
+            mismatch,
+            shapes
+        }
+    };
+    entry: "12/mismatch.kirl";
+    out: "Error: \"Expected type Number but found shapes::Vector.\"
+";
+}
+
+create_test! {
+    name: test_12_generic_pattern;
+    map: {
+        12: {
+            generic_pattern
+        }
+    };
+    entry: "12/generic_pattern.kirl";
+    out: "pair
+2
+";
+}
+
+create_failue_test! {
+    name: test_12_bare_generics;
+    map: {
+        12: {
+            bare_generics
+        }
+    };
+    entry: "12/bare_generics.kirl";
+    out: "Error: \"A struct type bare_generics::Pair requires 2 generic arguments.\"
+";
+}
+
+create_test! {
+    name: test_13;
+    map: {
+        13: {
+            main,
+            counter
+        }
+    };
+    entry: "13/main.kirl";
+    out: "counter initialized
+15
+2
+";
+}
+
+create_failue_test! {
+    name: test_13_uninitialized;
+    map: {
+        13: {
+            uninitialized
+        }
+    };
+    entry: "13/uninitialized.kirl";
+    out: "is read before it is initialized.";
+}
+
+create_failue_test! {
+    name: test_14_error;
+    map: {
+        14: {
+            error
+        }
+    };
+    entry: "14/error.kirl";
+    out: "Error: \"A built-in function failed: Index 5 is out of range for a list of length 2.";
+}
+
+create_failue_test! {
+    name: test_15_backtrace;
+    map: {
+        15: {
+            backtrace
+        }
+    };
+    entry: "15/backtrace.kirl";
+    out: "15/backtrace.kirl:3:53)\\n    at <top level> (";
+}
+
+create_test! {
+    name: test_16;
+    map: {
+        16: {
+            main
+        }
+    };
+    entry: "16/main.kirl";
+    out: "matched
+not matched
+error
+";
+}
+
+create_failue_test! {
+    name: test_16_infallible_try;
+    map: {
+        16: {
+            infallible_try
+        }
+    };
+    entry: "16/infallible_try.kirl";
+    out: "has type Number which never contains an error #{error: String}.";
+}
+
+create_test! {
+    name: test_17;
+    map: {
+        17: {
+            main
+        }
+    };
+    entry: "17/main.kirl";
+    out: "55
+7
+true
+";
+}
+
+create_failue_test! {
+    name: test_17_no_prelude;
+    map: {
+        17: {
+            no_prelude
+        }
+    };
+    entry: "17/no_prelude.kirl";
+    out: "Error: \"A named reference \\\"_add\\\" at";
+}
+
+create_test! {
+    name: test_18;
+    map: {
+        18: {
+            main,
+            vector
+        }
+    };
+    entry: "18/main.kirl";
+    out: "7
+10
+true
+true
+11
+";
+}
+
+create_failue_test! {
+    name: test_18_ambiguous;
+    map: {
+        18: {
+            ambiguous,
+            vector
+        }
+    };
+    entry: "18/ambiguous.kirl";
+    out: "Error: \"A named reference \\\"_add\\\", or \\\"vector::_add\\\" at Line:5,position:";
+}
+
+create_test! {
+    name: test_19;
+    map: {
+        19: {
+            main
+        }
+    };
+    entry: "19/main.kirl";
+    out: "a
+false
+c
+true
+e
+f
+g
+true
+";
+}
+
+create_failue_test! {
+    name: test_19_non_bool_operand;
+    map: {
+        19: {
+            non_bool_operand
+        }
+    };
+    entry: "19/non_bool_operand.kirl";
+    out: "Error: \"Expected type Bool but found String.\"";
+}
+
+create_test! {
+    name: test_20;
+    map: {
+        20: {
+            main
+        }
+    };
+    entry: "20/main.kirl";
+    out: "12
+nothing
+7
+0
+";
+}
+
+create_failue_test! {
+    name: test_20_while_value;
+    map: {
+        20: {
+            while_value
+        }
+    };
+    entry: "20/while_value.kirl";
+    out: "Error: \"Expected type () but found ";
+}
+
+create_test! {
+    name: test_21;
+    map: {
+        21: {
+            main
+        }
+    };
+    entry: "21/main.kirl";
+    out: "12
+10
+0
+1
+";
+}
+
+create_failue_test! {
+    name: test_21_non_exhaustive;
+    map: {
+        21: {
+            non_exhaustive
+        }
+    };
+    entry: "21/non_exhaustive.kirl";
+    out: "Shape::Empty are not covered.";
+}
+
+create_test! {
+    name: test_22;
+    map: {
+        22: {
+            main,
+            shapes
+        }
+    };
+    entry: "22/main.kirl";
+    out: "3
+7
+11
+";
+}
+
+create_failue_test! {
+    name: test_22_recursive;
+    map: {
+        22: {
+            recursive
+        }
+    };
+    entry: "22/recursive.kirl";
+    out: "type alias \\\"A\\\" refers to itself without being wrapped in a tuple, array, struct or function.";
+}
+
+create_failue_test! {
+    name: test_22_cross_file_cycle;
+    map: {
+        22: {
+            cycle_a,
+            cycle_b
+        }
+    };
+    entry: "22/cycle_a.kirl";
+    out: "type alias \\\"A\\\" refers to itself without being wrapped in a tuple, array, struct or function.";
+}
+
+create_failue_test! {
+    name: test_22_alias_name;
+    map: {
+        22: {
+            alias_name
+        }
+    };
+    entry: "22/alias_name.kirl";
+    out: "Expected type String but found alias_name::Pair::<Number>.";
+}
+
+create_test! {
+    name: test_23;
+    map: {
+        23: {
+            main
+        }
+    };
+    entry: "23/main.kirl";
+    out: "6
+0
+7
+";
+}
+
+create_test! {
+    name: test_24;
+    map: {
+        24: {
+            main
+        }
+    };
+    entry: "24/main.kirl";
+    out: "1
+a
+3
+c
+";
+}
+
+create_failue_test! {
+    name: test_24_unsatisfied;
+    map: {
+        24: {
+            unsatisfied
+        }
+    };
+    entry: "24/unsatisfied.kirl";
+    out: "A type Bool does not satisfy the bound Comparable of first at";
+}
+
+create_failue_test! {
+    name: test_24_turbofish;
+    map: {
+        24: {
+            turbofish
+        }
+    };
+    entry: "24/turbofish.kirl";
+    out: "A type Bool does not satisfy the bound Comparable of first at";
+}
+
+create_failue_test! {
+    name: test_24_unbounded;
+    map: {
+        24: {
+            unbounded
+        }
+    };
+    entry: "24/unbounded.kirl";
+    out: "A type $T0 does not satisfy the bound Comparable of max at";
+}
+
+create_test! {
+    name: test_25;
+    map: {
+        25: {
+            main
+        }
+    };
+    entry: "25/main.kirl";
+    out: "2
+banana
+5
+42
+4
+";
+}
+
+create_test! {
+    name: test_25_nested;
+    map: {
+        25: {
+            nested
+        }
+    };
+    entry: "25/nested.kirl";
+    out: "7
+1
+2
+";
+}
+
+create_failue_test! {
+    name: test_25_growing;
+    map: {
+        25: {
+            growing
+        }
+    };
+    entry: "25/growing.kirl";
+    out: "is instantiated more than 64 levels deep.";
+}
+
+create_failue_test! {
+    name: test_25_uninferable;
+    map: {
+        25: {
+            uninferable
+        }
+    };
+    entry: "25/uninferable.kirl";
+    out: "Type arguments of `ignore` in";
+}
+
+create_test! {
+    name: test_26;
+    map: {
+        26: {
+            main
+        }
+    };
+    entry: "26/main.kirl";
+    out: "3
+15
+1
+7
+";
+}
+
+create_failue_test! {
+    name: test_26_invalid_target;
+    map: {
+        26: {
+            invalid_target
+        }
+    };
+    entry: "26/invalid_target.kirl";
+    out: "invalid assignment target at Line:4,position:0";
+}
+
+create_test! {
+    name: test_27;
+    map: {
+        27: {
+            main
+        }
+    };
+    entry: "27/main.kirl";
+    out: "value = 6, name = kirl
+point: (1, 2), flag: true
+{escaped}
+";
+}
+
+create_test! {
+    name: test_27_holes;
+    map: {
+        27: {
+            holes
+        }
+    };
+    entry: "27/holes.kirl";
+    out: "x = 1, }
+";
+}
+
+create_failue_test! {
+    name: test_27_unsupported_hole;
+    map: {
+        27: {
+            unsupported_hole
+        }
+    };
+    entry: "27/unsupported_hole.kirl";
+    out: "UnsupportedFormatStringHole";
+}
+
+create_test! {
+    name: test_28;
+    map: {
+        28: {
+            main
+        }
+    };
+    entry: "28/main.kirl";
+    out: "10
+1
+4
+7
+10
+true
+false
+5
+3
+4
+0
+20
+30
+40
+";
+}
+
+create_test! {
+    name: test_29;
+    map: {
+        29: {
+            main
+        }
+    };
+    entry: "29/main.kirl";
+    out: "3
+2
+1
+2
+2
+1
+";
+}
+
+create_failue_test! {
+    name: test_29_invalid_next;
+    map: {
+        29: {
+            invalid_next
+        }
+    };
+    entry: "29/invalid_next.kirl";
+    out: "must return () | #{value: T} but returns Number.";
+}
+
+create_test! {
+    name: test_30;
+    map: {
+        30: {
+            main
+        }
+    };
+    entry: "30/main.kirl";
+    out: "1
+2
+3
+1
+2
+3
+done
+done
+kirl
+kirl
+1
+2
+3
+";
+}
+
+create_test! {
+    name: test_30_calls;
+    map: {
+        30: {
+            calls
+        }
+    };
+    entry: "30/calls.kirl";
+    out: "332833500
+0
+1
+4
+9
+";
+}
+
+create_failue_test! {
+    name: test_30_yield_outside;
+    map: {
+        30: {
+            yield_outside
+        }
+    };
+    entry: "30/yield_outside.kirl";
+    out: "A yield statement is only allowed in a generator function (fn*).";
+}
+
+create_failue_test! {
+    name: test_30_reentrant;
+    map: {
+        30: {
+            reentrant
+        }
+    };
+    entry: "30/reentrant.kirl";
+    out: "A generator is resumed while it is running.";
+}
+
+create_test! {
+    name: test_31;
+    map: {
+        31: {
+            main,
+            lib
+        }
+    };
+    entry: "31/main.kirl";
+    out: "5
+2
+3
+";
+}
+
+create_failue_test! {
+    name: test_31_private_function;
+    map: {
+        31: {
+            private_function,
+            lib
+        }
+    };
+    entry: "31/private_function.kirl";
+    out: "function `helper` in lib.kirl is private.";
+}
+
+create_failue_test! {
+    name: test_31_private_variable;
+    map: {
+        31: {
+            private_variable,
+            lib
+        }
+    };
+    entry: "31/private_variable.kirl";
+    out: "variable `hidden` in lib.kirl is private.";
+}
+
+create_failue_test! {
+    name: test_31_private_struct;
+    map: {
+        31: {
+            private_struct,
+            lib
+        }
+    };
+    entry: "31/private_struct.kirl";
+    out: "struct `Secret` in lib.kirl is private.";
+}
+
+create_failue_test! {
+    name: test_31_private_variant;
+    map: {
+        31: {
+            private_variant,
+            lib
+        }
+    };
+    entry: "31/private_variant.kirl";
+    out: "enum `Hidden` in lib.kirl is private.";
+}
+
+create_test! {
+    name: test_32;
+    map: {
+        32: {
+            main,
+            geometry: {
+                mod,
+                point,
+                metrics
+            },
+            text: {
+                lib
+            }
+        }
+    };
+    entry: "32/main.kirl";
+    out: "25
+(3, 4)
+hello from text/lib.kirl
+";
+}
+
+create_failue_test! {
+    name: test_33_module_typo;
+    map: {
+        33: {
+            module_typo
+        }
+    };
+    entry: "33/module_typo.kirl";
+    out: "Error: \"A module for import \\\"subdri::sub2\\\" at Line:2,position:0 - Line:2,position:20 in module_typo.kirl is not found. Tried subdri/sub2.kirl, subdri/sub2/mod.kirl, subdri/sub2/lib.kirl, subdri.kirl, subdri/mod.kirl, subdri/lib.kirl.\"";
+}
+
+create_failue_test! {
+    name: test_33_middle_typo;
+    map: {
+        33: {
+            middle_typo,
+            geometry: {
+                mod,
+                circle
+            }
+        }
+    };
+    entry: "33/middle_typo.kirl";
+    out: "Error: \"A module for import \\\"geometry::cirlce::area\\\" at Line:2,position:0 - Line:2,position:30 in middle_typo.kirl is not found. Tried geometry/cirlce/area.kirl, geometry/cirlce/area/mod.kirl, geometry/cirlce/area/lib.kirl, geometry/cirlce.kirl, geometry/cirlce/mod.kirl, geometry/cirlce/lib.kirl, geometry.kirl, geometry/mod.kirl.\"";
+}
+
+create_failue_test! {
+    name: test_33_std_typo;
+    map: {
+        33: {
+            std_typo
+        }
+    };
+    entry: "33/std_typo.kirl";
+    out: "Error: \"A module for import \\\"std::oi::println\\\" at Line:1,position:0 - Line:1,position:24 in std_typo.kirl is not found.\"";
+}
@@ -5,7 +5,7 @@ use std::any::{Any, TypeId};
 use std::borrow::Cow;
 use std::collections::HashMap;
 use std::error::Error;
-use std::fmt::Debug;
+use std::fmt::{Debug, Display};
 use std::marker::PhantomData;
 use std::sync::{Arc, RwLock};
 
@@ -15,6 +15,10 @@ pub trait KirlRustFunction: Send + Sync {
         Self: Sized;
     fn argument_count(&self) -> usize;
     fn call(&mut self, args: Vec<Arc<dyn KirlVMValueLock>>) -> Result<Arc<dyn KirlVMValueLock>, Box<dyn Error>>;
+    // 引数がジェネレータならVMが代わりにジェネレータの続きを実行する(Iteratorの_next用)
+    fn resumes_generator(&self) -> bool {
+        false
+    }
 }
 
 pub struct FunctionWrapper<Args, Result, F>(F, PhantomData<(Args, Result)>);
@@ -51,10 +55,7 @@ macro_rules! impl_fn {
             fn call(&mut self, args: Vec<Arc<dyn KirlVMValueLock>>) -> Result<Arc<dyn KirlVMValueLock>, Box<dyn Error>> {
                 #[allow(non_snake_case)]
                 if let Ok([$($t),*]) = <[_;count!($($t),*)] as std::convert::TryFrom<_>>::try_from(args) {
-                    self.0($({
-                        let value_ref = $t::try_from_kirl_value($t).unwrap_or_else(|value:Arc<dyn KirlVMValueLock>|panic!("expected type {:?} but found {:?}.", std::any::type_name::<$t>(), value.type_name()));
-                        Arc::try_unwrap(value_ref).map(|lock| lock.into_inner().expect("")).unwrap_or_else(|arc| arc.read().expect("").clone())
-                    }),*).map(|result|result.into_kirl_value()).map_err(|err|Box::new(err) as Box<dyn Error>)
+                    self.0($(take_argument::<$t>($t)?),*).map(|result|result.into_kirl_value()).map_err(|err|Box::new(err) as Box<dyn Error>)
                 } else { unreachable!() }
             }
         }
@@ -73,6 +74,36 @@ fn downcast<U: Sized + 'static>(value: Arc<dyn KirlVMValueLock>) -> Result<Arc<R
     }
 }
 
+// Rust関数に渡された引数を変換できなかったときのエラー
+#[derive(Debug)]
+pub enum KirlArgumentError {
+    UnexpectedType { expected: &'static str, actual: &'static str },
+    PoisonedValue,
+}
+
+impl Display for KirlArgumentError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            KirlArgumentError::UnexpectedType { expected, actual } => write!(f, "expected type {:?} but found {:?}.", expected, actual),
+            KirlArgumentError::PoisonedValue => write!(f, "an argument is poisoned."),
+        }
+    }
+}
+
+impl Error for KirlArgumentError {}
+
+pub fn cast_argument<T: InterchangeKirlVMValue>(value: Arc<dyn KirlVMValueLock>) -> Result<Arc<RwLock<T>>, KirlArgumentError> {
+    T::try_from_kirl_value(value).map_err(|value| KirlArgumentError::UnexpectedType { expected: std::any::type_name::<T>(), actual: value.type_name() })
+}
+
+// 他から参照されていなければ中身を取り出し、参照されていれば複製する
+pub fn take_argument<T: InterchangeKirlVMValue + Clone>(value: Arc<dyn KirlVMValueLock>) -> Result<T, KirlArgumentError> {
+    match Arc::try_unwrap(cast_argument::<T>(value)?) {
+        Ok(lock) => lock.into_inner().map_err(|_| KirlArgumentError::PoisonedValue),
+        Err(arc) => arc.read().map(|value| value.clone()).map_err(|_| KirlArgumentError::PoisonedValue),
+    }
+}
+
 impl_fn!();
 impl_fn!(A1);
 impl_fn!(A1, A2);
@@ -80,6 +111,20 @@ impl_fn!(A1, A2, A3);
 impl_fn!(A1, A2, A3, A4);
 impl_fn!(A1, A2, A3, A4, A5);
 
+// Kirl側で#{error: String}として捕捉できるエラー値
+pub fn kirl_error_value(message: impl Display) -> Arc<dyn KirlVMValueLock> {
+    let mut value = HashMap::new();
+    value.insert("error".to_string(), message.to_string().into_kirl_value());
+    value.into_kirl_value()
+}
+
+pub fn into_kirl_result<T: InterchangeKirlVMValue, E: Display>(result: Result<T, E>) -> Arc<dyn KirlVMValueLock> {
+    match result {
+        Ok(value) => value.into_kirl_value(),
+        Err(error) => kirl_error_value(error),
+    }
+}
+
 #[macro_export]
 macro_rules! get_type {
     (!)=>{ kirl_common::typing::LIRType::Unreachable };
@@ -155,7 +200,7 @@ impl<V: KirlVMValue> KirlVMValueLock for RwLock<V> {
     }
 
     fn type_name(&self) -> &'static str {
-        self.read().unwrap().type_name()
+        self.read().map_or(std::any::type_name::<V>(), |value| value.type_name())
     }
 }
 
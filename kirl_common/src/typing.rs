@@ -14,6 +14,9 @@ pub enum HIRType {
     Function { arguments: Vec<HIRType>, result: Box<HIRType> },
     AnonymousStruct(BTreeMap<String, HIRType>),
     Or(Vec<HIRType>),
+    // 再帰的な型 body中のRecursiveReference(path)は、それを囲む最も内側の同じpathのRecursiveを表す
+    Recursive { path: Vec<String>, body: Box<HIRType> },
+    RecursiveReference(Vec<String>),
 }
 
 impl HIRType {
@@ -64,24 +67,94 @@ impl HIRType {
                 }
                 types
             }),
+            HIRType::Recursive { path, body } => HIRType::Recursive { path: path.clone(), body: Box::new(body.apply_generics_type_argument(type_arguments)?) },
+            HIRType::RecursiveReference(path) => HIRType::RecursiveReference(path.clone()),
         }
         .into()
     }
 
+    // pathを参照しているRecursiveReferenceをtyに置き換える(内側で同じpathのRecursiveに束縛されているものは除く)
+    pub fn replace_recursive_reference(&self, path: &[String], ty: &HIRType) -> HIRType {
+        match self {
+            HIRType::RecursiveReference(reference) if reference == path => ty.clone(),
+            HIRType::Recursive { path: inner_path, .. } if inner_path == path => self.clone(),
+            HIRType::Infer | HIRType::Unreachable | HIRType::GenericsTypeArgument(_) | HIRType::RecursiveReference(_) => self.clone(),
+            HIRType::Named { path: named_path, generics_arguments } => HIRType::Named {
+                path: named_path.clone(),
+                generics_arguments: generics_arguments.iter().map(|item| item.replace_recursive_reference(path, ty)).collect(),
+            },
+            HIRType::Tuple(items) => HIRType::Tuple(items.iter().map(|item| item.replace_recursive_reference(path, ty)).collect()),
+            HIRType::Array(item) => HIRType::Array(Box::new(item.replace_recursive_reference(path, ty))),
+            HIRType::Function { arguments, result } => HIRType::Function {
+                arguments: arguments.iter().map(|item| item.replace_recursive_reference(path, ty)).collect(),
+                result: Box::new(result.replace_recursive_reference(path, ty)),
+            },
+            HIRType::AnonymousStruct(members) => HIRType::AnonymousStruct(members.iter().map(|(k, item)| (k.clone(), item.replace_recursive_reference(path, ty))).collect()),
+            HIRType::Or(items) => HIRType::Or(items.iter().map(|item| item.replace_recursive_reference(path, ty)).collect()),
+            HIRType::Recursive { path: inner_path, body } => HIRType::Recursive { path: inner_path.clone(), body: Box::new(body.replace_recursive_reference(path, ty)) },
+        }
+    }
+
+    // 再帰的な型を1段だけ展開する
+    pub fn unfold_recursive(&self) -> Cow<HIRType> {
+        match self {
+            HIRType::Recursive { path, body } => Cow::Owned(body.replace_recursive_reference(path, self)),
+            ty => Cow::Borrowed(ty),
+        }
+    }
+
+    pub fn contains_recursive_reference(&self, path: &[String]) -> bool {
+        match self {
+            HIRType::RecursiveReference(reference) => reference == path,
+            HIRType::Recursive { path: inner_path, .. } if inner_path == path => false,
+            HIRType::Infer | HIRType::Unreachable | HIRType::GenericsTypeArgument(_) => false,
+            HIRType::Named { generics_arguments: items, .. } | HIRType::Tuple(items) | HIRType::Or(items) => items.iter().any(|item| item.contains_recursive_reference(path)),
+            HIRType::Array(item) => item.contains_recursive_reference(path),
+            HIRType::Function { arguments, result } => arguments.iter().any(|item| item.contains_recursive_reference(path)) || result.contains_recursive_reference(path),
+            HIRType::AnonymousStruct(members) => members.values().any(|item| item.contains_recursive_reference(path)),
+            HIRType::Recursive { body, .. } => body.contains_recursive_reference(path),
+        }
+    }
+
+    // 展開しても構造が変わらない位置(Orの要素)に自分自身があると展開が終わらない
+    pub fn is_guarded_recursion(&self, path: &[String]) -> bool {
+        match self {
+            HIRType::RecursiveReference(reference) => reference != path,
+            HIRType::Or(items) => items.iter().all(|item| item.is_guarded_recursion(path)),
+            HIRType::Recursive { path: inner_path, body } => inner_path == path || body.is_guarded_recursion(path),
+            _ => true,
+        }
+    }
+
     pub fn is_a(&self, rhs: &Self) -> bool {
+        self.is_a_inner(rhs, &mut Vec::new())
+    }
+
+    // 再帰的な型の比較は、比較中の組を仮定として真とみなすことで打ち切る
+    fn is_a_inner(&self, rhs: &Self, assumptions: &mut Vec<(HIRType, HIRType)>) -> bool {
         match (self, rhs) {
             (HIRType::Infer, _) => true,
             (_, HIRType::Infer) => true,
             (HIRType::Unreachable, _) => true,
             (_, HIRType::Unreachable) => false,
+            (ty1, ty2) if ty1 == ty2 => true,
+            (ty1 @ HIRType::Recursive { .. }, ty2) | (ty1, ty2 @ HIRType::Recursive { .. }) => {
+                if assumptions.iter().any(|(assumed1, assumed2)| assumed1 == ty1 && assumed2 == ty2) {
+                    return true;
+                }
+                assumptions.push((ty1.clone(), ty2.clone()));
+                let result = ty1.unfold_recursive().is_a_inner(&ty2.unfold_recursive(), assumptions);
+                assumptions.pop();
+                result
+            }
             (HIRType::GenericsTypeArgument(i), HIRType::GenericsTypeArgument(j)) => j == i,
-            (HIRType::Named { path: path1, generics_arguments: arg1 }, HIRType::Named { path: path2, generics_arguments: arg2 }) => path1 == path2 && arg1.len() == arg2.len() && arg1.iter().zip(arg2).all(|(ty1, ty2)| ty1.is_a(ty2)),
-            (HIRType::Tuple(items1), HIRType::Tuple(items2)) => items1.len() >= items2.len() && items1.iter().zip(items2).all(|(ty1, ty2)| ty1.is_a(ty2)),
-            (HIRType::Array(t1), HIRType::Array(t2)) => t1.is_a(t2),
-            (HIRType::Function { arguments: arg1, result: res1 }, HIRType::Function { arguments: arg2, result: res2 }) => arg1.len() == arg2.len() && arg2.iter().zip(arg1).all(|(ty1, ty2)| ty1.is_a(ty2)) && res1.is_a(res2),
-            (HIRType::AnonymousStruct(members1), HIRType::AnonymousStruct(members2)) => members2.iter().all(|(k, v2)| members1.get(k).map_or(false, |v1| v1.is_a(v2))),
-            (HIRType::Or(items1), ty2) => items1.iter().all(|ty1| ty1.is_a(ty2)),
-            (ty1, HIRType::Or(items2)) => items2.iter().any(|ty2| ty1.is_a(ty2)),
+            (HIRType::Named { path: path1, generics_arguments: arg1 }, HIRType::Named { path: path2, generics_arguments: arg2 }) => path1 == path2 && arg1.len() == arg2.len() && arg1.iter().zip(arg2).all(|(ty1, ty2)| ty1.is_a_inner(ty2, assumptions)),
+            (HIRType::Tuple(items1), HIRType::Tuple(items2)) => items1.len() >= items2.len() && items1.iter().zip(items2).all(|(ty1, ty2)| ty1.is_a_inner(ty2, assumptions)),
+            (HIRType::Array(t1), HIRType::Array(t2)) => t1.is_a_inner(t2, assumptions),
+            (HIRType::Function { arguments: arg1, result: res1 }, HIRType::Function { arguments: arg2, result: res2 }) => arg1.len() == arg2.len() && arg2.iter().zip(arg1).all(|(ty1, ty2)| ty1.is_a_inner(ty2, assumptions)) && res1.is_a_inner(res2, assumptions),
+            (HIRType::AnonymousStruct(members1), HIRType::AnonymousStruct(members2)) => members2.iter().all(|(k, v2)| members1.get(k).map_or(false, |v1| v1.is_a_inner(v2, assumptions))),
+            (HIRType::Or(items1), ty2) => items1.iter().all(|ty1| ty1.is_a_inner(ty2, assumptions)),
+            (ty1, HIRType::Or(items2)) => items2.iter().any(|ty2| ty1.is_a_inner(ty2, assumptions)),
             _ => false,
         }
     }
@@ -95,6 +168,7 @@ impl HIRType {
                 result.normalize();
             }
             HIRType::AnonymousStruct(members) => members.values_mut().for_each(HIRType::normalize),
+            HIRType::Recursive { body, .. } => body.normalize(),
             HIRType::Or(items) => {
                 items.iter_mut().for_each(HIRType::normalize);
                 *items = items.drain(..).flat_map(|ty| if let HIRType::Or(items) = ty { items } else { vec![ty] }).collect();
@@ -127,6 +201,7 @@ impl HIRType {
                 }
                 Some(Cow::Owned(HIRType::Or(result)))
             }
+            HIRType::Recursive { .. } => self.unfold_recursive().member_type(member_name).map(|ty| Cow::Owned(ty.into_owned())),
             _ => None,
         }
     }
@@ -142,6 +217,7 @@ impl HIRType {
                 }
                 Some(Cow::Owned(HIRType::Or(result)))
             }
+            HIRType::Recursive { .. } => self.unfold_recursive().tuple_item_type(n).map(|ty| Cow::Owned(ty.into_owned())),
             _ => None,
         }
     }
@@ -151,6 +227,7 @@ impl HIRType {
             HIRType::Infer => true,
             HIRType::AnonymousStruct(members) => members.contains_key(member_name),
             HIRType::Or(items) => items.iter().all(|ty| ty.has_member(member_name)),
+            HIRType::Recursive { .. } => self.unfold_recursive().has_member(member_name),
             _ => false,
         }
     }
@@ -160,6 +237,7 @@ impl HIRType {
             HIRType::Infer => true,
             HIRType::Tuple(items) => n < items.len(),
             HIRType::Or(items) => items.iter().all(|ty| ty.has_tuple_item(n)),
+            HIRType::Recursive { .. } => self.unfold_recursive().has_tuple_item(n),
             _ => false,
         }
     }
@@ -169,6 +247,8 @@ impl HIRType {
             (this, rhs) if this == rhs => this.clone(),
             (HIRType::Infer, rhs) => rhs.clone(),
             (this, HIRType::Infer) => this.clone(),
+            // 右辺の再帰的な型は展開しない(左辺の構造に沿ってしか展開が進まないので停止する)
+            (this @ HIRType::Recursive { .. }, rhs) => this.unfold_recursive().intersect_to(rhs),
             (HIRType::Tuple(items1), HIRType::Tuple(items2)) if items1.len() <= items2.len() => {
                 let mut types = Vec::with_capacity(items1.len());
                 for (ty1, ty2) in items1.iter().zip(items2) {
@@ -177,6 +257,15 @@ impl HIRType {
                 HIRType::Tuple(types)
             }
             (HIRType::Array(t1), HIRType::Array(t2)) => HIRType::Array(Box::new(t1.intersect_to(t2))),
+            (HIRType::Named { path: path1, generics_arguments: arg1 }, ty2 @ HIRType::Named { path: path2, generics_arguments: arg2 }) if path1 == path2 => {
+                if arg1.is_empty() {
+                    ty2.clone()
+                } else if arg1.len() == arg2.len() {
+                    HIRType::Named { path: path1.clone(), generics_arguments: arg1.iter().zip(arg2).map(|(ty1, ty2)| ty1.intersect_to(ty2)).collect() }
+                } else {
+                    self.clone()
+                }
+            }
             (ty1 @ HIRType::AnonymousStruct(members1), HIRType::AnonymousStruct(members2)) => {
                 let mut result_members = BTreeMap::new();
                 for (key, value) in members1 {
@@ -219,6 +308,14 @@ impl HIRType {
                     .collect();
                 HIRType::Array(Box::new(HIRType::Or(types).into_normalized()))
             }
+            (ty1 @ HIRType::Named { .. }, HIRType::Or(types)) => {
+                let types = types.iter().filter(|ty| ty.is_a(ty1)).map(|ty| ty1.intersect_to(ty)).collect::<Vec<_>>();
+                if types.is_empty() {
+                    ty1.clone()
+                } else {
+                    HIRType::Or(types).into_normalized()
+                }
+            }
             (ty1 @ HIRType::AnonymousStruct(members1), HIRType::Or(types)) => {
                 let members = types
                     .iter()
@@ -240,15 +337,29 @@ impl HIRType {
     }
 
     pub fn possibility_assignable_to(&self, target: &HIRType) -> bool {
+        self.possibility_assignable_to_inner(target, &mut Vec::new())
+    }
+
+    fn possibility_assignable_to_inner(&self, target: &HIRType, assumptions: &mut Vec<(HIRType, HIRType)>) -> bool {
         match (self, target) {
             (ty1, ty2) if ty1 == ty2 => true,
             (HIRType::Infer, _) | (_, HIRType::Infer) => true,
             (HIRType::GenericsTypeArgument(_), _) | (_, HIRType::GenericsTypeArgument(_)) => true,
-            (HIRType::Tuple(items1), HIRType::Tuple(items2)) => items1.iter().zip(items2).all(|(ty1, ty2)| ty1.possibility_assignable_to(ty2)),
-            (HIRType::Array(ty1), HIRType::Array(ty2)) => ty1.possibility_assignable_to(ty2),
-            (HIRType::AnonymousStruct(members1), HIRType::AnonymousStruct(members2)) => !members2.iter().any(|(key, ty2)| members1.get(key).map(|ty1| !ty1.possibility_assignable_to(ty2)).unwrap_or_default()),
-            (HIRType::Or(types), ty2) => types.iter().any(|ty1| ty1.possibility_assignable_to(ty2)),
-            (ty1, HIRType::Or(types)) => types.iter().any(|ty2| ty1.possibility_assignable_to(ty2)),
+            (ty1 @ HIRType::Recursive { .. }, ty2) | (ty1, ty2 @ HIRType::Recursive { .. }) => {
+                if assumptions.iter().any(|(assumed1, assumed2)| assumed1 == ty1 && assumed2 == ty2) {
+                    return true;
+                }
+                assumptions.push((ty1.clone(), ty2.clone()));
+                let result = ty1.unfold_recursive().possibility_assignable_to_inner(&ty2.unfold_recursive(), assumptions);
+                assumptions.pop();
+                result
+            }
+            (HIRType::Tuple(items1), HIRType::Tuple(items2)) => items1.iter().zip(items2).all(|(ty1, ty2)| ty1.possibility_assignable_to_inner(ty2, assumptions)),
+            (HIRType::Array(ty1), HIRType::Array(ty2)) => ty1.possibility_assignable_to_inner(ty2, assumptions),
+            (HIRType::Named { path: path1, generics_arguments: arg1 }, HIRType::Named { path: path2, generics_arguments: arg2 }) => path1 == path2 && (arg1.is_empty() || arg2.is_empty() || arg1.iter().zip(arg2).all(|(ty1, ty2)| ty1.possibility_assignable_to_inner(ty2, assumptions))),
+            (HIRType::AnonymousStruct(members1), HIRType::AnonymousStruct(members2)) => !members2.iter().any(|(key, ty2)| members1.get(key).map(|ty1| !ty1.possibility_assignable_to_inner(ty2, assumptions)).unwrap_or_default()),
+            (HIRType::Or(types), ty2) => types.iter().any(|ty1| ty1.possibility_assignable_to_inner(ty2, assumptions)),
+            (ty1, HIRType::Or(types)) => types.iter().any(|ty2| ty1.possibility_assignable_to_inner(ty2, assumptions)),
             _ => false,
         }
     }
@@ -256,7 +367,7 @@ impl HIRType {
     pub fn infer_temporary(&self) -> HIRType {
         match self {
             HIRType::Infer => HIRType::Tuple(Vec::new()),
-            ty @ (HIRType::Unreachable | HIRType::GenericsTypeArgument(_) | HIRType::Named { .. }) => ty.clone(),
+            ty @ (HIRType::Unreachable | HIRType::GenericsTypeArgument(_) | HIRType::Named { .. } | HIRType::Recursive { .. } | HIRType::RecursiveReference(_)) => ty.clone(),
             HIRType::Tuple(items) => HIRType::Tuple(items.iter().map(HIRType::infer_temporary).collect()),
             HIRType::Array(item) => HIRType::Array(Box::new(item.infer_temporary())),
             HIRType::Function { arguments, result } => HIRType::Function {
@@ -298,6 +409,7 @@ impl ToString for HIRType {
                 let items = items.iter().map(ToString::to_string).reduce(|a, b| format!("{} | {}", a, b)).unwrap_or_default();
                 format!("({})", items)
             }
+            HIRType::Recursive { path, .. } | HIRType::RecursiveReference(path) => path.join("::"),
         }
     }
 }
@@ -311,6 +423,8 @@ pub enum LIRType {
     Function { arguments: Vec<LIRType>, result: Box<LIRType> },
     AnonymousStruct(BTreeMap<String, LIRType>),
     Or(Vec<LIRType>),
+    Recursive { path: Vec<String>, body: Box<LIRType> },
+    RecursiveReference(Vec<String>),
 }
 
 impl From<LIRType> for HIRType {
@@ -329,22 +443,71 @@ impl From<LIRType> for HIRType {
             },
             LIRType::AnonymousStruct(items) => HIRType::AnonymousStruct(items.into_iter().map(|(k, v)| (k, v.into())).collect()),
             LIRType::Or(items) => HIRType::Or(items.into_iter().map(Into::into).collect()),
+            LIRType::Recursive { path, body } => HIRType::Recursive { path, body: Box::new((*body).into()) },
+            LIRType::RecursiveReference(path) => HIRType::RecursiveReference(path),
         }
     }
 }
 
 impl LIRType {
+    fn replace_recursive_reference(&self, path: &[String], ty: &LIRType) -> LIRType {
+        match self {
+            LIRType::RecursiveReference(reference) if reference == path => ty.clone(),
+            LIRType::Recursive { path: inner_path, .. } if inner_path == path => self.clone(),
+            LIRType::Unreachable | LIRType::RecursiveReference(_) => self.clone(),
+            LIRType::Named { path: named_path, generics_arguments } => LIRType::Named {
+                path: named_path.clone(),
+                generics_arguments: generics_arguments.iter().map(|item| item.replace_recursive_reference(path, ty)).collect(),
+            },
+            LIRType::Tuple(items) => LIRType::Tuple(items.iter().map(|item| item.replace_recursive_reference(path, ty)).collect()),
+            LIRType::Array(item) => LIRType::Array(Box::new(item.replace_recursive_reference(path, ty))),
+            LIRType::Function { arguments, result } => LIRType::Function {
+                arguments: arguments.iter().map(|item| item.replace_recursive_reference(path, ty)).collect(),
+                result: Box::new(result.replace_recursive_reference(path, ty)),
+            },
+            LIRType::AnonymousStruct(members) => LIRType::AnonymousStruct(members.iter().map(|(k, item)| (k.clone(), item.replace_recursive_reference(path, ty))).collect()),
+            LIRType::Or(items) => LIRType::Or(items.iter().map(|item| item.replace_recursive_reference(path, ty)).collect()),
+            LIRType::Recursive { path: inner_path, body } => LIRType::Recursive { path: inner_path.clone(), body: Box::new(body.replace_recursive_reference(path, ty)) },
+        }
+    }
+
+    pub fn unfold_recursive(&self) -> Cow<LIRType> {
+        match self {
+            LIRType::Recursive { path, body } => Cow::Owned(body.replace_recursive_reference(path, self)),
+            ty => Cow::Borrowed(ty),
+        }
+    }
+
     pub fn is_a(&self, rhs: &Self) -> bool {
+        self.is_a_inner(rhs, false, &mut Vec::new())
+    }
+
+    // 実行時の型タグは構造体の型引数を消してあるので、型引数の無い名前付きの型はどの型引数のものとも一致させる
+    pub fn is_a_ignoring_generics_arguments(&self, rhs: &Self) -> bool {
+        self.is_a_inner(rhs, true, &mut Vec::new())
+    }
+
+    fn is_a_inner(&self, rhs: &Self, ignore_generics_arguments: bool, assumptions: &mut Vec<(LIRType, LIRType)>) -> bool {
         match (self, rhs) {
             (LIRType::Unreachable, _) => true,
             (_, LIRType::Unreachable) => false,
-            (LIRType::Named { path: path1, generics_arguments: arg1 }, LIRType::Named { path: path2, generics_arguments: arg2 }) => path1 == path2 && arg1.len() == arg2.len() && arg1.iter().zip(arg2).all(|(ty1, ty2)| ty1.is_a(ty2)),
-            (LIRType::Tuple(items1), LIRType::Tuple(items2)) => items1.len() == items2.len() && items1.iter().zip(items2).all(|(ty1, ty2)| ty1.is_a(ty2)),
-            (LIRType::Array(t1), LIRType::Array(t2)) => t1.is_a(t2),
-            (LIRType::Function { arguments: arg1, result: res1 }, LIRType::Function { arguments: arg2, result: res2 }) => arg1.len() == arg2.len() && arg2.iter().zip(arg1).all(|(ty1, ty2)| ty1.is_a(ty2)) && res1.is_a(res2),
-            (LIRType::AnonymousStruct(members1), LIRType::AnonymousStruct(members2)) => members2.iter().all(|(k, v2)| members1.get(k).map_or(false, |v1| v1.is_a(v2))),
-            (LIRType::Or(items1), ty2) => items1.iter().all(|ty1| ty1.is_a(ty2)),
-            (ty1, LIRType::Or(items2)) => items2.iter().any(|ty2| ty1.is_a(ty2)),
+            (ty1, ty2) if ty1 == ty2 => true,
+            (ty1 @ LIRType::Recursive { .. }, ty2) | (ty1, ty2 @ LIRType::Recursive { .. }) => {
+                if assumptions.iter().any(|(assumed1, assumed2)| assumed1 == ty1 && assumed2 == ty2) {
+                    return true;
+                }
+                assumptions.push((ty1.clone(), ty2.clone()));
+                let result = ty1.unfold_recursive().is_a_inner(&ty2.unfold_recursive(), ignore_generics_arguments, assumptions);
+                assumptions.pop();
+                result
+            }
+            (LIRType::Named { path: path1, generics_arguments: arg1 }, LIRType::Named { path: path2, generics_arguments: arg2 }) => path1 == path2 && (ignore_generics_arguments && (arg1.is_empty() || arg2.is_empty()) || arg1.len() == arg2.len() && arg1.iter().zip(arg2).all(|(ty1, ty2)| ty1.is_a_inner(ty2, ignore_generics_arguments, assumptions))),
+            (LIRType::Tuple(items1), LIRType::Tuple(items2)) => items1.len() == items2.len() && items1.iter().zip(items2).all(|(ty1, ty2)| ty1.is_a_inner(ty2, ignore_generics_arguments, assumptions)),
+            (LIRType::Array(t1), LIRType::Array(t2)) => t1.is_a_inner(t2, ignore_generics_arguments, assumptions),
+            (LIRType::Function { arguments: arg1, result: res1 }, LIRType::Function { arguments: arg2, result: res2 }) => arg1.len() == arg2.len() && arg2.iter().zip(arg1).all(|(ty1, ty2)| ty1.is_a_inner(ty2, ignore_generics_arguments, assumptions)) && res1.is_a_inner(res2, ignore_generics_arguments, assumptions),
+            (LIRType::AnonymousStruct(members1), LIRType::AnonymousStruct(members2)) => members2.iter().all(|(k, v2)| members1.get(k).map_or(false, |v1| v1.is_a_inner(v2, ignore_generics_arguments, assumptions))),
+            (LIRType::Or(items1), ty2) => items1.iter().all(|ty1| ty1.is_a_inner(ty2, ignore_generics_arguments, assumptions)),
+            (ty1, LIRType::Or(items2)) => items2.iter().any(|ty2| ty1.is_a_inner(ty2, ignore_generics_arguments, assumptions)),
             _ => false,
         }
     }
@@ -358,6 +521,7 @@ impl LIRType {
                 result.normalize();
             }
             LIRType::AnonymousStruct(members) => members.values_mut().for_each(LIRType::normalize),
+            LIRType::Recursive { body, .. } => body.normalize(),
             LIRType::Or(items) => {
                 items.iter_mut().for_each(LIRType::normalize);
                 *items = items.drain(..).flat_map(|ty| if let LIRType::Or(items) = ty { items } else { vec![ty] }).collect();
@@ -392,6 +556,7 @@ impl LIRType {
                 }
                 Some(Cow::Owned(LIRType::Or(result)))
             }
+            LIRType::Recursive { .. } => self.unfold_recursive().member_type(member_name).map(|ty| Cow::Owned(ty.into_owned())),
             _ => None,
         }
     }
@@ -400,6 +565,7 @@ impl LIRType {
         match self {
             LIRType::AnonymousStruct(members) => members.contains_key(member_name),
             LIRType::Or(items) => items.iter().all(|ty| ty.has_member(member_name)),
+            LIRType::Recursive { .. } => self.unfold_recursive().has_member(member_name),
             _ => false,
         }
     }
@@ -467,13 +633,15 @@ impl TryFrom<HIRType> for LIRType {
                 }
                 Ok(LIRType::Or(result_items))
             }
+            HIRType::Recursive { path, body } => Ok(LIRType::Recursive { path, body: Box::new((*body).try_into()?) }),
+            HIRType::RecursiveReference(path) => Ok(LIRType::RecursiveReference(path)),
         }
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::HIRType;
+    use super::{HIRType, LIRType};
     use std::borrow::Cow;
     use std::collections::BTreeMap;
 
@@ -486,6 +654,13 @@ mod tests {
 
         assert!(Named { path: vec!["Number".to_string()], generics_arguments: vec![] }.is_a(&Named { path: vec!["Number".to_string()], generics_arguments: vec![] }));
         assert!(!Named { path: vec!["Number".to_string()], generics_arguments: vec![] }.is_a(&Named { path: vec!["String".to_string()], generics_arguments: vec![] }));
+        assert!(!Named { path: vec!["Box".to_string()], generics_arguments: vec![Named { path: vec!["Number".to_string()], generics_arguments: vec![] }] }.is_a(&Named { path: vec!["Box".to_string()], generics_arguments: vec![] }));
+        assert!(!Named { path: vec!["Box".to_string()], generics_arguments: vec![] }.is_a(&Named { path: vec!["Box".to_string()], generics_arguments: vec![Named { path: vec!["Number".to_string()], generics_arguments: vec![] }] }));
+        let lir_box_number = LIRType::Named { path: vec!["Box".to_string()], generics_arguments: vec![LIRType::Named { path: vec!["Number".to_string()], generics_arguments: vec![] }] };
+        let lir_box = LIRType::Named { path: vec!["Box".to_string()], generics_arguments: vec![] };
+        assert!(!lir_box_number.is_a(&lir_box));
+        assert!(lir_box_number.is_a_ignoring_generics_arguments(&lir_box));
+        assert!(!lir_box_number.is_a_ignoring_generics_arguments(&LIRType::Named { path: vec!["Pair".to_string()], generics_arguments: vec![] }));
 
         assert!(Tuple(vec![]).is_a(&Tuple(vec![])));
         assert!(Tuple(vec![Tuple(vec![]), Tuple(vec![])]).is_a(&Tuple(vec![Tuple(vec![]), Tuple(vec![])])));
@@ -623,4 +798,33 @@ mod tests {
         assert!(AnonymousStruct(BTreeMap::from([("a".to_string(), tuple0.clone()), ("b".to_string(), tuple1.clone())])).possibility_assignable_to(&Or(vec![AnonymousStruct(BTreeMap::from([("a".to_string(), tuple0.clone()), ("b".to_string(), tuple0.clone())])), tuple0.clone()])));
         assert!(tuple2.possibility_assignable_to(&Or(vec![AnonymousStruct(BTreeMap::from([("a".to_string(), tuple0.clone()), ("b".to_string(), tuple0.clone())])), tuple0.clone()])));
     }
+
+    #[test]
+    fn test_type_recursive() {
+        use HIRType::*;
+        let number = Named { path: vec!["Number".to_string()], generics_arguments: vec![] };
+        let string = Named { path: vec!["String".to_string()], generics_arguments: vec![] };
+        let list = |name: &str| Recursive {
+            path: vec![name.to_string()],
+            body: Box::new(Or(vec![Tuple(vec![]), Tuple(vec![number.clone(), RecursiveReference(vec![name.to_string()])])])),
+        };
+        assert!(Tuple(vec![]).is_a(&list("List")));
+        assert!(Tuple(vec![number.clone(), Tuple(vec![number.clone(), Tuple(vec![])])]).is_a(&list("List")));
+        assert!(!string.is_a(&list("List")));
+        assert!(list("List").is_a(&list("List")));
+        assert!(list("List").is_a(&list("Other")));
+        assert!(list("List").is_a(&Or(vec![Tuple(vec![]), Tuple(vec![number.clone(), list("List")])])));
+        assert!(!list("List").is_a(&Tuple(vec![number.clone()])));
+        assert_eq!(list("List").unfold_recursive().into_owned(), Or(vec![Tuple(vec![]), Tuple(vec![number.clone(), list("List")])]));
+        assert!(list("List").possibility_assignable_to(&list("Other")));
+
+        let json = Recursive { path: vec!["Json".to_string()], body: Box::new(Or(vec![number.clone(), Array(Box::new(RecursiveReference(vec!["Json".to_string()])))])) };
+        assert!(Array(Box::new(Array(Box::new(number.clone())))).is_a(&json));
+        assert!(!Array(Box::new(string.clone())).is_a(&json));
+        assert_eq!(Array(Box::new(json.clone())).intersect_to(&json), Array(Box::new(json.clone())));
+
+        let lir_json = LIRType::try_from(json).unwrap();
+        assert!(LIRType::Array(Box::new(LIRType::Named { path: vec!["Number".to_string()], generics_arguments: vec![] })).is_a(&lir_json));
+        assert!(!LIRType::Array(Box::new(LIRType::Named { path: vec!["String".to_string()], generics_arguments: vec![] })).is_a(&lir_json));
+    }
 }
@@ -27,7 +27,7 @@ impl TypeWithGenerics {
             }
             fn expand_generics_inner(ty: HIRType, map: &HashMap<String, usize>) -> HIRType {
                 match ty {
-                    ty @ (HIRType::Infer | HIRType::Unreachable | HIRType::GenericsTypeArgument(_)) => ty,
+                    ty @ (HIRType::Infer | HIRType::Unreachable | HIRType::GenericsTypeArgument(_) | HIRType::RecursiveReference(_)) => ty,
                     HIRType::Named { path, generics_arguments } => {
                         if generics_arguments.is_empty() {
                             if let [name] = path.as_slice() {
@@ -49,6 +49,7 @@ impl TypeWithGenerics {
                     },
                     HIRType::AnonymousStruct(members) => HIRType::AnonymousStruct(members.into_iter().map(|(k, ty)| (k, expand_generics_inner(ty, map))).collect()),
                     HIRType::Or(items) => HIRType::Or(items.into_iter().map(|ty| expand_generics_inner(ty, map)).collect()),
+                    HIRType::Recursive { path, body } => HIRType::Recursive { path, body: Box::new(expand_generics_inner(*body, map)) },
                 }
             }
             expand_generics_inner(ty, &map)
@@ -263,6 +264,15 @@ fn quote_type(ty: &HIRType) -> proc_macro2::TokenStream {
             let items = items.iter().map(|ty| quote_type(ty));
             quote! { kirl_common::typing::HIRType::Or(vec![#(#items),*]) }
         }
+        HIRType::Recursive { path, body } => {
+            let path = path.iter().map(|s| syn::LitStr::new(s, Span::call_site()));
+            let body = quote_type(body);
+            quote! { kirl_common::typing::HIRType::Recursive { path: vec![#(#path.to_string()),*], body: Box::new(#body) } }
+        }
+        HIRType::RecursiveReference(path) => {
+            let path = path.iter().map(|s| syn::LitStr::new(s, Span::call_site()));
+            quote! { kirl_common::typing::HIRType::RecursiveReference(vec![#(#path.to_string()),*]) }
+        }
     }
 }
 
@@ -412,11 +422,8 @@ pub(crate) fn kirl_function_inner(args: TokenStream, input: TokenStream) -> Toke
             let name = Ident::new(&format!("param{}", i), Span::call_site());
             match ty {
                 ParamType::NonCast => quote! { #name },
-                ParamType::Cast(ty) => quote! { <#ty as kirl_common::interface::InterchangeKirlVMValue>::try_from_kirl_value(#name).unwrap_or_else(|value|panic!("expected type {:?} but found {:?}.", std::any::type_name::<#ty>(), value.type_name())) },
-                ParamType::Owned(ty) => quote! { {
-                    let value = <#ty as kirl_common::interface::InterchangeKirlVMValue>::try_from_kirl_value(#name).unwrap_or_else(|value|panic!("expected type {:?} but found {:?}.", std::any::type_name::<#ty>(), value.type_name()));
-                    std::sync::Arc::try_unwrap(value).map(|lock| lock.into_inner().expect("")).unwrap_or_else(|arc| arc.read().expect("").clone())
-                } },
+                ParamType::Cast(ty) => quote! { kirl_common::interface::cast_argument::<#ty>(#name)? },
+                ParamType::Owned(ty) => quote! { kirl_common::interface::take_argument::<#ty>(#name)? },
             }
         });
     let param_names = (0..argument_count).map(|i| Ident::new(&format!("param{}", i), Span::call_site()));
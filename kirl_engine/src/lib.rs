@@ -1,26 +1,31 @@
 use std::borrow::Cow;
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
 use std::error::Error;
 use std::fmt::{Debug, Display, Formatter};
-use std::ops::Deref;
+use std::iter;
+use std::mem;
+use std::ops::{Deref, Range};
 use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::slice;
 
 use kirl_common::typing::HIRType;
 use uuid::Uuid;
 
-use kirl_parser::kirl_parser::Function;
-use kirl_parser::{KirlParseError, KirlParser};
-use kirl_semantic_analyzer::name_resolver::{resolve_statements, statement_references, KirlNameResolver};
-use kirl_semantic_analyzer::syntax_tree_to_hir::{analysis_function, analysis_statements, AnalysisStatementError, SearchPaths};
-use kirl_semantic_analyzer::type_checker::{decision_type, used_functions, DecisionTypeError};
-use kirl_semantic_analyzer::{collect_top_level_item_with_imports, HIRStatement, KirlTopLevelItems, WithImport};
-use kirl_stdlib::get_stdlib;
-use kirl_vm::bytecode::KirlVMExecutable;
-use kirl_vm::lir::{hir_to_lir, LIRStatementList, LIRStatementListConvertError};
+use kirl_parser::kirl_parser::{Enum, Function, Statement, Struct, TypeAlias};
+use kirl_parser::{CharacterPosition, KirlParseError, KirlParser};
+use kirl_semantic_analyzer::name_resolver::{generics_bound_references, resolve_generics_bounds, resolve_statements, resolve_type, statement_references, type_references, KirlNameResolver, ResolvedItems};
+use kirl_semantic_analyzer::syntax_tree_to_hir::{analysis_enum, analysis_function, analysis_module_statements, analysis_struct, analysis_type_alias, import_paths, public_imports, uses_prelude, AnalysisStatementError, SearchPaths};
+use kirl_semantic_analyzer::type_checker::{check_generics_argument_count, decision_type, for_each_used_function, DecisionTypeError};
+use kirl_semantic_analyzer::{collect_top_level_item_with_imports, HIREnumVariant, HIRExpression, HIRGenericsBound, HIRStatement, HIRStructDefinition, KirlTopLevelItems, Variable, WithImport};
+use kirl_stdlib::{get_stdlib, KirlStdLib};
+use kirl_vm::bytecode::{KirlFunctionDebugInfo, KirlVMExecutable};
+use kirl_vm::lir::{hir_to_lir, LIRInstruction, LIRStatement, LIRStatementList, LIRStatementListConvertError};
 
 pub trait KirlFileResolver {
     type ResolveError: Error + Send + Sync + 'static;
-    fn resolve_file_by_path(&mut self, path: impl AsRef<Path>) -> Result<Cow<str>, Self::ResolveError>;
+    // ファイルが存在しなければOk(None)を返し、それ以外の読み込みの失敗はErrとする
+    fn resolve_file_by_path(&mut self, path: impl AsRef<Path>) -> Result<Option<Cow<str>>, Self::ResolveError>;
 }
 
 #[derive(Debug)]
@@ -30,6 +35,15 @@ pub enum KirlCompileError<F: Error + Send + Sync + 'static> {
     LIRConvertError(LIRStatementListConvertError),
     AnalysisError(AnalysisStatementError),
     FileResolveError(F),
+    // 他のファイルのpubでない要素を参照した
+    PrivateItem { kind: &'static str, name: String, file_path: PathBuf },
+    EntryPointNotFound(PathBuf),
+    // importしたパスに対応するモジュールが見つからない(ファイルのパスはエントリーポイントのディレクトリからの相対パス)
+    UnresolvedImport { path: Vec<String>, position: Range<CharacterPosition>, file_path: PathBuf, candidates: Vec<PathBuf> },
+    // 呼び出された型から型引数を決められない
+    UninferableGenericsArgument { name: String, file_path: PathBuf },
+    // 型引数を持つ関数の実体が、実体から呼ばれる実体として深く連なりすぎた(型引数が際限なく大きくなる再帰など)
+    TooDeepInstantiation { name: String, file_path: PathBuf },
 }
 
 impl<F: Error + Send + Sync + 'static> From<KirlParseError> for KirlCompileError<F> {
@@ -64,6 +78,21 @@ impl<F: Error + Send + Sync + 'static> Display for KirlCompileError<F> {
             KirlCompileError::LIRConvertError(e) => Display::fmt(e, f),
             KirlCompileError::AnalysisError(e) => Display::fmt(e, f),
             KirlCompileError::FileResolveError(e) => Display::fmt(e, f),
+            KirlCompileError::PrivateItem { kind, name, file_path } => write!(f, "{} `{}` in {} is private.", kind, name, file_path.display()),
+            KirlCompileError::EntryPointNotFound(file_path) => write!(f, "An entry point file {} is not found.", file_path.display()),
+            KirlCompileError::UnresolvedImport { path, position, file_path, candidates } => {
+                write!(f, "A module for import \"{}\" at {} - {} in {} is not found.", path.join("::"), position.start, position.end, file_path.display())?;
+                if let Some((first, rest)) = candidates.split_first() {
+                    write!(f, " Tried {}", first.display())?;
+                    for candidate in rest {
+                        write!(f, ", {}", candidate.display())?;
+                    }
+                    write!(f, ".")?;
+                }
+                Ok(())
+            }
+            KirlCompileError::UninferableGenericsArgument { name, file_path } => write!(f, "Type arguments of `{}` in {} cannot be inferred from the call. Specify them explicitly.", name, file_path.display()),
+            KirlCompileError::TooDeepInstantiation { name, file_path } => write!(f, "`{}` in {} is instantiated more than {} levels deep. Its type arguments may grow without end.", name, file_path.display(), MAX_INSTANTIATION_DEPTH),
         }
     }
 }
@@ -76,138 +105,1005 @@ impl<F: Error + Send + Sync + 'static> Error for KirlCompileError<F> {
             KirlCompileError::LIRConvertError(e) => Some(e),
             KirlCompileError::AnalysisError(e) => Some(e),
             KirlCompileError::FileResolveError(e) => Some(e),
+            KirlCompileError::PrivateItem { .. } | KirlCompileError::EntryPointNotFound(_) | KirlCompileError::UnresolvedImport { .. } | KirlCompileError::UninferableGenericsArgument { .. } | KirlCompileError::TooDeepInstantiation { .. } => None,
         }
     }
 }
 
+#[derive(Clone)]
 struct InFileResolver<'a> {
     function_types: &'a HashMap<(PathBuf, String), Vec<(Uuid, HIRType)>>,
+    global_variables: &'a HashMap<(PathBuf, String), (Uuid, HIRType)>,
+    struct_definitions: &'a HashMap<Vec<String>, HIRStructDefinition>,
+    type_aliases: &'a HashMap<Vec<String>, (usize, HIRType)>,
+    private_items: &'a PrivateItems,
+    module_index_files: &'a HashMap<PathBuf, PathBuf>,
+    reexports: &'a HashMap<(PathBuf, String), Vec<Vec<String>>>,
+    // 循環したpub importで無限に辿らないよう、辿ってきた再公開を覚えておく
+    reexport_chain: Vec<&'a (PathBuf, String)>,
+    base_directory: &'a Path,
     base_file_path: &'a Path,
 }
 
+// pubの付いていない要素は定義されたファイルからしか見えない
+#[derive(Default)]
+struct PrivateItems {
+    functions: HashSet<Uuid>,
+    structs: HashSet<Vec<String>>,
+    // 列挙型のヴァリアントは列挙型と同じ公開範囲になる
+    enums: HashSet<Vec<String>>,
+    type_aliases: HashSet<Vec<String>>,
+    global_variables: HashSet<(PathBuf, String)>,
+}
+
+fn module_file_path(base_file_path: &Path, module: &[String]) -> PathBuf {
+    let mut file_path = base_file_path.to_path_buf();
+    file_path.pop();
+    file_path.extend(module);
+    file_path.with_extension("kirl")
+}
+
+// utils.kirlが無ければ、utils/mod.kirlかutils/lib.kirlをモジュールutilsとして読み込む
+const MODULE_INDEX_FILE_NAMES: [&str; 2] = ["mod.kirl", "lib.kirl"];
+
+// 構造体は定義されたファイルのエントリーポイントのディレクトリからの相対パスと名前で一意に識別する
+fn struct_path(base_directory: &Path, file_path: &Path, name: &str) -> Vec<String> {
+    let file_path = file_path.strip_prefix(base_directory).unwrap_or(file_path);
+    file_path.with_extension("").iter().map(|component| component.to_string_lossy().into_owned()).chain(iter::once(name.to_string())).collect()
+}
+
+// 列挙型のヴァリアントは列挙型の中に定義された構造体として扱う
+fn enum_variant_path(base_directory: &Path, file_path: &Path, enum_name: &str, variant_name: &str) -> Vec<String> {
+    let mut path = struct_path(base_directory, file_path, enum_name);
+    path.push(variant_name.to_string());
+    path
+}
+
+// ヴァリアントのコンストラクタは"列挙型名::ヴァリアント名"という名前の関数として登録する
+// 値の無いヴァリアントは同じ名前のグローバル変数として登録する
+fn enum_variant_constructor_name(enum_name: &str, variant_name: &str) -> String {
+    format!("{}::{}", enum_name, variant_name)
+}
+
+fn uses_generics_argument(ty: &HIRType, index: usize) -> bool {
+    match ty {
+        HIRType::Infer | HIRType::Unreachable | HIRType::RecursiveReference(_) => false,
+        HIRType::GenericsTypeArgument(i) => *i == index,
+        HIRType::Named { generics_arguments: items, .. } | HIRType::Tuple(items) | HIRType::Or(items) => items.iter().any(|ty| uses_generics_argument(ty, index)),
+        HIRType::Array(item) => uses_generics_argument(item, index),
+        HIRType::Function { arguments, result } => arguments.iter().any(|ty| uses_generics_argument(ty, index)) || uses_generics_argument(result, index),
+        HIRType::AnonymousStruct(members) => members.values().any(|ty| uses_generics_argument(ty, index)),
+        HIRType::Recursive { body, .. } => uses_generics_argument(body, index),
+    }
+}
+
+impl<'a> InFileResolver<'a> {
+    fn split_path(&self, full_path: &[String]) -> (PathBuf, String) {
+        match full_path {
+            [] => unreachable!(),
+            [name] => (self.base_file_path.to_path_buf(), name.clone()),
+            [path @ .., name] => {
+                let file_path = module_file_path(self.base_file_path, path);
+                (self.module_index_files.get(&file_path).cloned().unwrap_or(file_path), name.clone())
+            }
+        }
+    }
+
+    // pub importで再公開された名前を、再公開したファイルからのパスに置き換えて返す
+    fn reexported(&self, full_path: &[String]) -> Vec<(InFileResolver<'a>, Vec<String>)> {
+        let mut result = Vec::new();
+        for i in 1..full_path.len() {
+            let (key, targets) = match self.reexports.get_key_value(&self.split_path(&full_path[..=i])) {
+                Some(reexport) => reexport,
+                None => continue,
+            };
+            if self.reexport_chain.contains(&key) {
+                continue;
+            }
+            let mut reexport_chain = self.reexport_chain.clone();
+            reexport_chain.push(key);
+            let resolver = InFileResolver { reexport_chain, base_file_path: &key.0, ..self.clone() };
+            result.extend(targets.iter().map(|target| (resolver.clone(), target.iter().chain(&full_path[i + 1..]).cloned().collect())));
+        }
+        result
+    }
+}
+
 impl<'a> KirlNameResolver for InFileResolver<'a> {
     fn resolve(&mut self, full_path: &[String]) -> Vec<(Uuid, HIRType)> {
-        let InFileResolver { function_types, base_file_path } = self;
-        let key = match full_path {
-            [] => unreachable!(),
-            [name] => {
-                let file_path = base_file_path.to_path_buf();
-                (file_path, name.clone())
+        let key = self.split_path(full_path);
+        let in_same_file = key.0 == self.base_file_path;
+        let functions = self.function_types.get(&key).into_iter().flatten().filter(|(id, _)| in_same_file || !self.private_items.functions.contains(id)).map(|(id, ty)| (*id, ty.clone()));
+        let global_variable = self.global_variables.get(&key).filter(|_| in_same_file || !self.private_items.global_variables.contains(&key)).map(|(id, ty)| (*id, ty.clone()));
+        let variant_key = match full_path {
+            [enum_path @ .., variant_name] if !enum_path.is_empty() => {
+                let (file_path, enum_name) = self.split_path(enum_path);
+                let visible = file_path == self.base_file_path || !self.private_items.enums.contains(&struct_path(self.base_directory, &file_path, &enum_name));
+                Some((file_path, enum_variant_constructor_name(&enum_name, variant_name))).filter(|_| visible)
             }
-            [path @ .., name] => {
-                let mut file_path = base_file_path.to_path_buf();
-                file_path.pop();
-                file_path.extend(path);
-                let file_path = file_path.with_extension("kirl");
-                (file_path, name.clone())
+            _ => None,
+        };
+        let variant_constructors = variant_key.iter().flat_map(|key| self.function_types.get(key).into_iter().flatten().chain(self.global_variables.get(key))).map(|(id, ty)| (*id, ty.clone()));
+        let reexported = self.reexported(full_path).into_iter().flat_map(|(mut resolver, path)| resolver.resolve(&path));
+        functions.chain(global_variable).chain(variant_constructors).chain(reexported).collect()
+    }
+
+    fn resolve_type(&mut self, full_path: &[String]) -> Option<Vec<String>> {
+        let (file_path, name) = self.split_path(full_path);
+        let path = struct_path(self.base_directory, &file_path, &name);
+        if self.struct_definitions.contains_key(&path) {
+            if file_path != self.base_file_path && self.private_items.structs.contains(&path) {
+                return None;
+            }
+            return Some(path);
+        }
+        let variant_path = match full_path {
+            [enum_path @ .., variant_name] if !enum_path.is_empty() => {
+                let (file_path, enum_name) = self.split_path(enum_path);
+                let path = enum_variant_path(self.base_directory, &file_path, &enum_name, variant_name);
+                if file_path != self.base_file_path && self.private_items.enums.contains(&struct_path(self.base_directory, &file_path, &enum_name)) {
+                    return None;
+                }
+                if self.struct_definitions.contains_key(&path) {
+                    Some(path)
+                } else {
+                    None
+                }
             }
+            _ => None,
         };
-        function_types.get(&key).into_iter().flatten().map(|(id, ty)| (*id, ty.clone())).collect()
+        variant_path.or_else(|| self.reexported(full_path).into_iter().find_map(|(mut resolver, path)| resolver.resolve_type(&path)))
+    }
+
+    fn resolve_type_alias(&mut self, full_path: &[String]) -> Option<(usize, HIRType)> {
+        let (file_path, name) = self.split_path(full_path);
+        let path = struct_path(self.base_directory, &file_path, &name);
+        if file_path != self.base_file_path && (self.private_items.enums.contains(&path) || self.private_items.type_aliases.contains(&path)) {
+            return None;
+        }
+        self.type_aliases.get(&path).cloned().or_else(|| self.reexported(full_path).into_iter().find_map(|(mut resolver, path)| resolver.resolve_type_alias(&path)))
+    }
+}
+
+// preludeを使わないファイルの名前解決に使う
+struct WithoutPrelude<R>(R);
+
+impl<R: KirlNameResolver> KirlNameResolver for WithoutPrelude<R> {
+    fn resolve(&mut self, full_path: &[String]) -> Vec<(Uuid, HIRType)> {
+        self.0.resolve(full_path)
+    }
+
+    fn resolve_type(&mut self, full_path: &[String]) -> Option<Vec<String>> {
+        self.0.resolve_type(full_path)
+    }
+
+    fn resolve_type_alias(&mut self, full_path: &[String]) -> Option<(usize, HIRType)> {
+        self.0.resolve_type_alias(full_path)
+    }
+}
+
+struct GenericFunction {
+    name: String,
+    file_path: PathBuf,
+    generics_argument_count: usize,
+    function_type: HIRType,
+    body: Vec<HIRStatement<SearchPaths>>,
+}
+
+#[derive(Default)]
+struct LoadedItems {
+    // エントリーポイントのあるディレクトリ(構造体の名前はここからの相対パスで作る)
+    base_directory: PathBuf,
+    parsed_files: HashSet<PathBuf>,
+    no_prelude_files: HashSet<PathBuf>,
+    function_types: HashMap<(PathBuf, String), Vec<(Uuid, HIRType)>>,
+    parsed_functions: HashMap<Uuid, Vec<HIRStatement<SearchPaths>>>,
+    // 型引数の制約は、関数を定義したファイルで名前解決してから使う
+    pending_generics_bounds: Vec<(Uuid, PathBuf, Vec<HIRGenericsBound<SearchPaths>>)>,
+    generics_bounds: HashMap<Uuid, Vec<HIRGenericsBound<ResolvedItems>>>,
+    // 型引数を持つ関数は、呼び出された型引数ごとに本体を複製して別の関数として解決する
+    generic_functions: HashMap<Uuid, GenericFunction>,
+    function_instances: BTreeMap<(Uuid, Vec<HIRType>), Uuid>,
+    // 実体から、それを呼び出して作られた実体の連なりの深さへ
+    instance_depths: HashMap<Uuid, usize>,
+    // 型引数のまま制約を検査済みの関数
+    checked_generic_functions: HashSet<Uuid>,
+    pending_function_instances: Vec<(Uuid, String, Vec<HIRStatement<SearchPaths>>, HIRType, PathBuf, Vec<HIRType>)>,
+    struct_definitions: HashMap<Vec<String>, HIRStructDefinition>,
+    // 型の別名や列挙型の名前から、型引数の数と展開後の型へ
+    type_aliases: HashMap<Vec<String>, (usize, HIRType)>,
+    // 定義待ちの型の別名のパスから、定義したファイルと名前、型引数の数と型へ
+    pending_type_aliases: BTreeMap<Vec<String>, (PathBuf, String, usize, HIRType)>,
+    // 定義中の型の別名と型引数の数(ファイルをまたいで参照し合う別名も再帰的な型にするので全体で共有する)
+    defining_type_aliases: Vec<(Vec<String>, usize)>,
+    // 定義は終わったが、定義中の別名への参照が残っているかもしれない型の別名
+    finished_type_aliases: Vec<Vec<String>>,
+    global_variables: HashMap<(PathBuf, String), (Uuid, HIRType)>,
+    private_items: PrivateItems,
+    // モジュールのパスから、代わりに読み込んだmod.kirlやlib.kirlのパスへ
+    module_index_files: HashMap<PathBuf, PathBuf>,
+    // pub importで再公開した名前から、再公開したファイルから見たパスへ
+    reexports: HashMap<(PathBuf, String), Vec<Vec<String>>>,
+    // importの検査と読み込みで同じファイルを二度読まないように、読んだ内容(存在しなければNone)を覚えておく
+    file_sources: HashMap<PathBuf, Option<Rc<str>>>,
+    // importの検査に使う、ファイルで宣言または再公開されている名前
+    declared_names: HashMap<PathBuf, DeclaredNames>,
+    // 読み込んだが未解決のモジュールのトップレベルの文
+    pending_module_initializers: VecDeque<(PathBuf, Vec<HIRStatement<SearchPaths>>, BTreeMap<String, usize>)>,
+    // 依存されるものから順に並ぶ
+    module_initializers: Vec<Uuid>,
+    loaded_functions: HashMap<Uuid, (KirlFunctionDebugInfo, LIRStatementList)>,
+}
+
+impl LoadedItems {
+    fn resolver<'a>(&'a self, base_file_path: &'a Path) -> InFileResolver<'a> {
+        InFileResolver {
+            function_types: &self.function_types,
+            global_variables: &self.global_variables,
+            struct_definitions: &self.struct_definitions,
+            type_aliases: &self.type_aliases,
+            private_items: &self.private_items,
+            module_index_files: &self.module_index_files,
+            reexports: &self.reexports,
+            reexport_chain: Vec::new(),
+            base_directory: &self.base_directory,
+            base_file_path,
+        }
+    }
+
+    // 名前が見つからなかった原因が他のファイルのpubでない要素なら、その種類と名前と定義されたファイルを返す
+    fn find_private_reference(&self, base_file_path: &Path, error: &DecisionTypeError) -> Option<(&'static str, String, PathBuf)> {
+        let resolver = self.resolver(base_file_path);
+        match error {
+            DecisionTypeError::NamedReferenceIsNotUnique { found, .. } if found.candidates().is_empty() => found.search_paths().iter().find_map(|path| {
+                let key = resolver.split_path(path);
+                if key.0 == base_file_path {
+                    return None;
+                }
+                if self.function_types.get(&key).into_iter().flatten().any(|(id, _)| self.private_items.functions.contains(id)) {
+                    let (file_path, name) = key;
+                    Some(("function", name, relative_file_path(base_file_path, file_path)))
+                } else if self.private_items.global_variables.contains(&key) {
+                    let (file_path, name) = key;
+                    Some(("variable", name, relative_file_path(base_file_path, file_path)))
+                } else {
+                    self.find_private_enum(base_file_path, path)
+                }
+            }),
+            DecisionTypeError::UnknownStruct { path } => self.find_private_type(base_file_path, path),
+            _ => None,
+        }
+    }
+
+    // 名前解決や型検査のエラーの原因が他のファイルのpubでない要素なら、それを示すエラーにする
+    fn compile_error<F: Error + Send + Sync + 'static>(&self, base_file_path: &Path, error: DecisionTypeError) -> KirlCompileError<F> {
+        match self.find_private_reference(base_file_path, &error) {
+            Some((kind, name, file_path)) => KirlCompileError::PrivateItem { kind, name, file_path },
+            None => error.map_types(|ty| self.fold_type_aliases(ty)).into(),
+        }
+    }
+
+    // 型の別名は名前解決で展開されるので、エラーに出す型のうち別名を展開したものと一致する部分は別名の名前で表示する
+    // 名前を付け替えただけの別名や再帰的な型(元から名前で表示される)は対象にしない
+    fn fold_type_aliases(&self, ty: HIRType) -> HIRType {
+        let mut aliases = self
+            .type_aliases
+            .iter()
+            .filter(|(_, (_, body))| !matches!(body, HIRType::Named { .. } | HIRType::GenericsTypeArgument(_) | HIRType::Recursive { .. } | HIRType::RecursiveReference(_)))
+            .map(|(path, (generics_argument_count, body))| (path, *generics_argument_count, body.clone().into_normalized()))
+            .collect::<Vec<_>>();
+        aliases.sort_by(|(path1, ..), (path2, ..)| path1.cmp(path2));
+        fold_type_aliases(ty, &aliases)
+    }
+
+    fn find_private_type(&self, base_file_path: &Path, path: &[String]) -> Option<(&'static str, String, PathBuf)> {
+        let (file_path, name) = self.resolver(base_file_path).split_path(path);
+        if file_path == base_file_path {
+            return None;
+        }
+        let item_path = struct_path(&self.base_directory, &file_path, &name);
+        let kind = if self.private_items.structs.contains(&item_path) {
+            "struct"
+        } else if self.private_items.enums.contains(&item_path) {
+            "enum"
+        } else if self.private_items.type_aliases.contains(&item_path) {
+            "type"
+        } else {
+            return self.find_private_enum(base_file_path, path);
+        };
+        Some((kind, name, relative_file_path(base_file_path, file_path)))
+    }
+
+    // Enum::Variantの形で、pubでない列挙型のヴァリアントを参照している
+    fn find_private_enum(&self, base_file_path: &Path, path: &[String]) -> Option<(&'static str, String, PathBuf)> {
+        let enum_path = match path {
+            [enum_path @ .., _] if !enum_path.is_empty() => enum_path,
+            _ => return None,
+        };
+        let (file_path, enum_name) = self.resolver(base_file_path).split_path(enum_path);
+        if file_path != base_file_path && self.private_items.enums.contains(&struct_path(&self.base_directory, &file_path, &enum_name)) {
+            Some(("enum", enum_name, relative_file_path(base_file_path, file_path)))
+        } else {
+            None
+        }
+    }
+}
+
+const TOP_LEVEL_NAME: &str = "<top level>";
+
+fn top_level_type() -> HIRType {
+    HIRType::Function {
+        arguments: Vec::new(),
+        result: Box::new(HIRType::Or(vec![HIRType::Tuple(Vec::new()), HIRType::Named { path: vec!["Number".to_string()], generics_arguments: Vec::new() }])),
     }
 }
 
 fn resolve<F: KirlFileResolver>(
     parser: &KirlParser,
+    id: Uuid,
+    name: &str,
     hir: Vec<HIRStatement<SearchPaths>>,
     hir_type: HIRType,
     hir_path: &impl AsRef<Path>,
+    generics_arguments: &[HIRType],
+    top_level_variables: &BTreeMap<String, usize>,
     file_resolver: &mut F,
-    parsed_files: &mut HashSet<PathBuf>,
     mut static_resolver: &mut impl KirlNameResolver,
-    function_types: &mut HashMap<(PathBuf, String), Vec<(Uuid, HIRType)>>,
-    parsed_functions: &mut HashMap<Uuid, Vec<HIRStatement<SearchPaths>>>,
-    loaded_functions: &mut HashMap<Uuid, LIRStatementList>,
-) -> Result<Vec<HIRStatement<(Uuid, HIRType)>>, KirlCompileError<F::ResolveError>> {
-    let current_references = statement_references(&hir);
-    for reference_path in current_references {
-        match reference_path {
-            [] => unreachable!(),
-            [_] => {}
-            [reference_file_path @ .., _] => {
-                let mut file_path = hir_path.as_ref().to_path_buf();
-                file_path.pop();
-                file_path.extend(reference_file_path);
-                let file_path = file_path.with_extension("kirl");
-                if !parsed_files.contains(&file_path) {
-                    let file = match file_resolver.resolve_file_by_path(&file_path) {
-                        Ok(file) => file,
-                        Err(_) => continue,
-                    };
-                    let KirlTopLevelItems { functions, .. } = collect_top_level_item_with_imports(parser.parse(&file)?);
-                    for function in functions {
-                        add_parsed_function(function_types, parsed_functions, &file_path, function)?;
-                    }
+    loaded_items: &mut LoadedItems,
+) -> Result<(), KirlCompileError<F::ResolveError>> {
+    load_referenced_files(parser, hir_path.as_ref(), statement_references(&hir), file_resolver, loaded_items)?;
+    resolve_module_initializers(parser, file_resolver, static_resolver, loaded_items)?;
+    while let Some((function_id, function_path, bounds)) = loaded_items.pending_generics_bounds.pop() {
+        load_referenced_files(parser, &function_path, generics_bound_references(&bounds), file_resolver, loaded_items)?;
+        resolve_module_initializers(parser, file_resolver, static_resolver, loaded_items)?;
+        let bounds = if loaded_items.no_prelude_files.contains(&function_path) {
+            resolve_generics_bounds(bounds, &mut WithoutPrelude((loaded_items.resolver(&function_path), &mut static_resolver)))
+        } else {
+            resolve_generics_bounds(bounds, &mut (loaded_items.resolver(&function_path), &mut static_resolver))
+        }
+        .map_err(|e| loaded_items.compile_error(&function_path, e))?;
+        loaded_items.generics_bounds.insert(function_id, bounds);
+    }
+    let mut loaded = if loaded_items.no_prelude_files.contains(hir_path.as_ref()) {
+        resolve_statements(hir, &mut WithoutPrelude((loaded_items.resolver(hir_path.as_ref()), &mut static_resolver)))
+    } else {
+        resolve_statements(hir, &mut (loaded_items.resolver(hir_path.as_ref()), &mut static_resolver))
+    }
+    .map_err(|e| loaded_items.compile_error(hir_path.as_ref(), e))?;
+    // 型引数を持つ関数の実体は、定義したファイルで名前解決してから型引数を埋める
+    if !generics_arguments.is_empty() {
+        // 型引数のまま渡した先の制約は、実体では具体的な型になって検査できないので、最初の実体を作るときに宣言された制約と照らし合わせる
+        let generic_id = loaded_items.function_instances.iter().find_map(|((generic_id, _), instance_id)| (*instance_id == id).then_some(*generic_id)).expect("実体は必ずfunction_instancesに登録されている");
+        if loaded_items.checked_generic_functions.insert(generic_id) {
+            if let Some(HIRType::Function { arguments, result }) = loaded_items.generic_functions.get(&generic_id).map(|function| &function.function_type) {
+                let own_bounds = loaded_items.generics_bounds.get(&generic_id).map(Vec::as_slice).unwrap_or_default();
+                // 制約以外の誤りは実体の型検査で報告する
+                if let Err(e @ DecisionTypeError::UnsatisfiedGenericsBound { .. }) = decision_type(loaded.clone(), arguments.clone(), result.deref().clone(), &loaded_items.struct_definitions, &loaded_items.generics_bounds, own_bounds) {
+                    return Err(loaded_items.compile_error(hir_path.as_ref(), e));
                 }
             }
         }
+        loaded = loaded.into_iter().map(|statement| statement.apply_generics_type_argument(generics_arguments)).collect();
     }
-    let loaded = resolve_statements(hir, &mut (InFileResolver { function_types, base_file_path: hir_path.as_ref() }, &mut static_resolver));
     let (argument_types, return_type) = if let HIRType::Function { arguments, result } = &hir_type { (arguments.clone(), result.deref().clone()) } else { unreachable!() };
-    let loaded = decision_type(loaded, argument_types, return_type)?;
-    let used_function_id = used_functions(&loaded);
-    let next_resolve = function_types
+    let argument_count = argument_types.len();
+    let (mut loaded, types) = decision_type(loaded, argument_types, return_type, &loaded_items.struct_definitions, &loaded_items.generics_bounds, &[]).map_err(|e| loaded_items.compile_error(hir_path.as_ref(), e))?;
+    let mut global_ids = HashMap::new();
+    for (name, variable_id) in top_level_variables {
+        let global_id = *global_ids.entry(*variable_id).or_insert_with(Uuid::new_v4);
+        loaded_items.global_variables.insert((hir_path.as_ref().to_path_buf(), name.clone()), (global_id, types[*variable_id].clone()));
+    }
+    let mut used_function_id = HashSet::new();
+    let depth = loaded_items.instance_depths.get(&id).copied().unwrap_or_default();
+    let mut instantiate_error = None;
+    for_each_used_function(&mut loaded, &mut |id, explicit_generics_arguments, ty| {
+        match instantiate_function(*id, explicit_generics_arguments, ty, depth + 1, loaded_items) {
+            Ok(Some(instance_id)) => *id = instance_id,
+            Ok(None) => {}
+            Err(e) => {
+                instantiate_error.get_or_insert(e);
+            }
+        }
+        used_function_id.insert(*id);
+    });
+    if let Some(e) = instantiate_error {
+        return Err(e);
+    }
+    let mut next_resolve = loaded_items
+        .function_types
         .iter()
-        .flat_map(|((path, _), value)| {
+        .flat_map(|((path, name), value)| {
             let mut result = Vec::new();
             for (id, ty) in value {
                 if used_function_id.contains(id) {
-                    if let Some(body) = parsed_functions.remove(id) {
-                        result.push((*id, body, ty.clone(), path.to_path_buf()));
+                    if let Some(body) = loaded_items.parsed_functions.remove(id) {
+                        result.push((*id, name.clone(), body, ty.clone(), path.to_path_buf(), Vec::new()));
                     }
                 }
             }
             result
         })
         .collect::<Vec<_>>();
-    for (id, statements, ty, path) in next_resolve {
-        let argument_count = if let HIRType::Function { arguments, .. } = &ty { arguments.len() } else { unreachable!() };
-        let statements = resolve(parser, statements, ty, &path, file_resolver, parsed_files, static_resolver, function_types, parsed_functions, loaded_functions)?;
-        loaded_functions.insert(id, hir_to_lir(statements, argument_count)?);
+    next_resolve.append(&mut loaded_items.pending_function_instances);
+    let global_variables = loaded_items.global_variables.values().map(|(id, _)| *id).collect::<HashSet<_>>();
+    let debug_info = KirlFunctionDebugInfo { name: name.to_string(), file_path: hir_path.as_ref().to_path_buf() };
+    loaded_items.loaded_functions.insert(id, (debug_info, hir_to_lir(loaded, argument_count, &global_variables, &global_ids)?));
+    for (id, name, statements, ty, path, generics_arguments) in next_resolve {
+        resolve(parser, id, &name, statements, ty, &path, &generics_arguments, &BTreeMap::new(), file_resolver, static_resolver, loaded_items)?;
+    }
+    Ok(())
+}
+
+const MAX_INSTANTIATION_DEPTH: usize = 64;
+
+// 型引数を持つ関数の参照なら、明示された型引数か呼び出された型から型引数を求めてその型引数での実体のIDを返す
+fn instantiate_function<F: Error + Send + Sync + 'static>(id: Uuid, explicit_generics_arguments: &[HIRType], ty: &HIRType, depth: usize, loaded_items: &mut LoadedItems) -> Result<Option<Uuid>, KirlCompileError<F>> {
+    let GenericFunction { name, file_path, generics_argument_count, function_type, body } = match loaded_items.generic_functions.get(&id) {
+        Some(function) => function,
+        None => return Ok(None),
+    };
+    let mut generics_arguments = if explicit_generics_arguments.len() == *generics_argument_count { explicit_generics_arguments.to_vec() } else { vec![HIRType::Infer; *generics_argument_count] };
+    bind_generics_arguments(function_type, ty, &mut generics_arguments);
+    if generics_arguments.contains(&HIRType::Infer) {
+        return Err(KirlCompileError::UninferableGenericsArgument { name: name.clone(), file_path: file_path.clone() });
+    }
+    if let Some(instance_id) = loaded_items.function_instances.get(&(id, generics_arguments.clone())) {
+        return Ok(Some(*instance_id));
+    }
+    if depth > MAX_INSTANTIATION_DEPTH {
+        return Err(KirlCompileError::TooDeepInstantiation { name: name.clone(), file_path: file_path.clone() });
+    }
+    let instance_id = Uuid::new_v4();
+    let instance_name = format!("{}::<{}>", name, generics_arguments.iter().map(ToString::to_string).collect::<Vec<_>>().join(", "));
+    let instance_type = function_type.apply_generics_type_argument(&generics_arguments).expect("型引数は関数の定義と同じ数だけある");
+    loaded_items.pending_function_instances.push((instance_id, instance_name, body.clone(), instance_type, file_path.clone(), generics_arguments.clone()));
+    loaded_items.function_instances.insert((id, generics_arguments), instance_id);
+    loaded_items.instance_depths.insert(instance_id, depth);
+    Ok(Some(instance_id))
+}
+
+// 型引数を含む型と、それに型引数を適用した型を突き合わせて型引数を求める
+fn bind_generics_arguments(generic: &HIRType, concrete: &HIRType, generics_arguments: &mut [HIRType]) {
+    match (generic, concrete) {
+        (HIRType::GenericsTypeArgument(i), concrete) => {
+            if let Some(argument @ HIRType::Infer) = generics_arguments.get_mut(*i) {
+                *argument = concrete.clone();
+            }
+        }
+        (HIRType::Or(items1), concrete) => {
+            let concrete_items = if let HIRType::Or(items) = concrete { items.as_slice() } else { slice::from_ref(concrete) };
+            // 型引数を含まない要素と同じ型を除き、残りは形の同じ要素どうしで突き合わせる
+            let (generic_items, fixed_items): (Vec<_>, Vec<_>) = items1.iter().partition(|item| contains_generics_type_argument(item));
+            let mut rest = concrete_items.iter().filter(|item| !fixed_items.contains(item)).collect::<Vec<_>>();
+            let mut bare_arguments = Vec::new();
+            for generic_item in generic_items {
+                if let HIRType::GenericsTypeArgument(_) = generic_item {
+                    bare_arguments.push(generic_item);
+                } else if let Some(index) = rest.iter().position(|item| same_shape(generic_item, item)) {
+                    bind_generics_arguments(generic_item, rest.remove(index), generics_arguments);
+                }
+            }
+            // 型引数そのものの要素が一つだけなら、残った要素をすべてそれに当てはめる
+            if let ([argument], [_, ..]) = (bare_arguments.as_slice(), rest.as_slice()) {
+                let concrete = if let [item] = rest.as_slice() { (*item).clone() } else { HIRType::Or(rest.into_iter().cloned().collect()) };
+                bind_generics_arguments(argument, &concrete, generics_arguments);
+            }
+        }
+        (HIRType::Named { generics_arguments: items1, .. }, HIRType::Named { generics_arguments: items2, .. }) | (HIRType::Tuple(items1), HIRType::Tuple(items2)) => {
+            for (ty1, ty2) in items1.iter().zip(items2) {
+                bind_generics_arguments(ty1, ty2, generics_arguments);
+            }
+        }
+        (HIRType::Array(item1), HIRType::Array(item2)) => bind_generics_arguments(item1, item2, generics_arguments),
+        (HIRType::Function { arguments: arguments1, result: result1 }, HIRType::Function { arguments: arguments2, result: result2 }) => {
+            for (ty1, ty2) in arguments1.iter().zip(arguments2) {
+                bind_generics_arguments(ty1, ty2, generics_arguments);
+            }
+            bind_generics_arguments(result1, result2, generics_arguments);
+        }
+        (HIRType::AnonymousStruct(members1), HIRType::AnonymousStruct(members2)) => {
+            for (member, ty1) in members1 {
+                if let Some(ty2) = members2.get(member) {
+                    bind_generics_arguments(ty1, ty2, generics_arguments);
+                }
+            }
+        }
+        (HIRType::Recursive { body: body1, .. }, HIRType::Recursive { body: body2, .. }) => bind_generics_arguments(body1, body2, generics_arguments),
+        _ => {}
     }
-    Ok(loaded)
 }
 
-fn add_parsed_function<F: Error + Send + Sync + 'static>(function_types: &mut HashMap<(PathBuf, String), Vec<(Uuid, HIRType)>>, parsed_functions: &mut HashMap<Uuid, Vec<HIRStatement<SearchPaths>>>, file_path: &Path, function: WithImport<Function>) -> Result<(), KirlCompileError<F>> {
-    let function_name = function.item.name.clone();
-    let (function_body, argument, result) = analysis_function(function)?;
-    let id = Uuid::new_v4();
-    function_types.entry((file_path.to_path_buf(), function_name)).or_default().push((id, HIRType::Function { arguments: argument, result: Box::new(result) }));
-    parsed_functions.insert(id, function_body);
+fn contains_generics_type_argument(ty: &HIRType) -> bool {
+    match ty {
+        HIRType::GenericsTypeArgument(_) => true,
+        HIRType::Infer | HIRType::Unreachable | HIRType::RecursiveReference(_) => false,
+        HIRType::Named { generics_arguments: items, .. } | HIRType::Tuple(items) | HIRType::Or(items) => items.iter().any(contains_generics_type_argument),
+        HIRType::Array(item) | HIRType::Recursive { body: item, .. } => contains_generics_type_argument(item),
+        HIRType::Function { arguments, result } => arguments.iter().any(contains_generics_type_argument) || contains_generics_type_argument(result),
+        HIRType::AnonymousStruct(members) => members.values().any(contains_generics_type_argument),
+    }
+}
+
+// 型引数の中身を見ずに、同じ種類の型として突き合わせられるか
+fn same_shape(generic: &HIRType, concrete: &HIRType) -> bool {
+    match (generic, concrete) {
+        (HIRType::Named { path: path1, .. }, HIRType::Named { path: path2, .. }) | (HIRType::Recursive { path: path1, .. }, HIRType::Recursive { path: path2, .. }) => path1 == path2,
+        (HIRType::Tuple(items1), HIRType::Tuple(items2)) => items1.len() == items2.len(),
+        (HIRType::Function { arguments: arguments1, .. }, HIRType::Function { arguments: arguments2, .. }) => arguments1.len() == arguments2.len(),
+        (HIRType::AnonymousStruct(members1), HIRType::AnonymousStruct(members2)) => members1.keys().eq(members2.keys()),
+        (HIRType::Array(_), HIRType::Array(_)) => true,
+        _ => false,
+    }
+}
+
+fn load_referenced_files<F: KirlFileResolver>(parser: &KirlParser, base_file_path: &Path, references: Vec<&[String]>, file_resolver: &mut F, loaded_items: &mut LoadedItems) -> Result<(), KirlCompileError<F::ResolveError>> {
+    for reference_path in references {
+        match reference_path {
+            [] => unreachable!(),
+            [_] => {}
+            [reference_file_path @ .., _] => {
+                let file_path = module_file_path(base_file_path, reference_file_path);
+                load_file(parser, &file_path, file_resolver, loaded_items)?;
+                // module::Enum::Variantの場合はmoduleを読み込む
+                if let [enum_file_path @ .., _] = reference_file_path {
+                    if !enum_file_path.is_empty() {
+                        load_file(parser, &module_file_path(base_file_path, enum_file_path), file_resolver, loaded_items)?;
+                    }
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+fn resolve_module_initializers<F: KirlFileResolver>(parser: &KirlParser, file_resolver: &mut F, static_resolver: &mut impl KirlNameResolver, loaded_items: &mut LoadedItems) -> Result<(), KirlCompileError<F::ResolveError>> {
+    while let Some((path, statements, variables)) = loaded_items.pending_module_initializers.pop_front() {
+        let initializer_id = Uuid::new_v4();
+        resolve(parser, initializer_id, TOP_LEVEL_NAME, statements, top_level_type(), &path, &[], &variables, file_resolver, static_resolver, loaded_items)?;
+        loaded_items.module_initializers.push(initializer_id);
+    }
+    Ok(())
+}
+
+fn load_file<F: KirlFileResolver>(parser: &KirlParser, file_path: &Path, file_resolver: &mut F, loaded_items: &mut LoadedItems) -> Result<(), KirlCompileError<F::ResolveError>> {
+    if loaded_items.parsed_files.contains(file_path) || loaded_items.module_index_files.contains_key(file_path) {
+        return Ok(());
+    }
+    let syntax_tree = match read_file(file_path, file_resolver, loaded_items)? {
+        Some(file) => parser.parse(&file)?,
+        None => return load_module_index(parser, file_path, file_resolver, loaded_items),
+    };
+    let KirlTopLevelItems { statements, structs, enums, type_aliases, functions } = collect_top_level_item_with_imports(syntax_tree);
+    if !uses_prelude(&statements) {
+        loaded_items.no_prelude_files.insert(file_path.to_path_buf());
+    }
+    validate_imports(parser, file_path, &statements, &enums, file_resolver, loaded_items)?;
+    load_items(parser, file_path, structs, enums, type_aliases, functions, file_resolver, loaded_items)?;
+    let reexports = public_imports(&statements);
+    let (statements, variables, public_variables) = analysis_module_statements(statements)?;
+    register_private_global_variables(file_path, &variables, &public_variables, loaded_items);
+    loaded_items.pending_module_initializers.push_back((file_path.to_path_buf(), statements, variables));
+    register_reexports(parser, file_path, reexports, file_resolver, loaded_items)
+}
+
+fn load_module_index<F: KirlFileResolver>(parser: &KirlParser, file_path: &Path, file_resolver: &mut F, loaded_items: &mut LoadedItems) -> Result<(), KirlCompileError<F::ResolveError>> {
+    let module_directory = file_path.with_extension("");
+    for index_file_name in MODULE_INDEX_FILE_NAMES {
+        let index_file_path = module_directory.join(index_file_name);
+        if read_file(&index_file_path, file_resolver, loaded_items)?.is_some() {
+            loaded_items.module_index_files.insert(file_path.to_path_buf(), index_file_path.clone());
+            return load_file(parser, &index_file_path, file_resolver, loaded_items);
+        }
+    }
+    Ok(())
+}
+
+fn read_file<F: KirlFileResolver>(file_path: &Path, file_resolver: &mut F, loaded_items: &mut LoadedItems) -> Result<Option<Rc<str>>, KirlCompileError<F::ResolveError>> {
+    if let Some(source) = loaded_items.file_sources.get(file_path) {
+        return Ok(source.clone());
+    }
+    let source = file_resolver.resolve_file_by_path(file_path).map_err(KirlCompileError::FileResolveError)?.map(|source| Rc::from(source.as_ref()));
+    loaded_items.file_sources.insert(file_path.to_path_buf(), source.clone());
+    Ok(source)
+}
+
+#[derive(Default)]
+struct DeclaredNames {
+    items: HashSet<String>,
+    // 列挙型の名前とヴァリアントの名前
+    enum_variants: HashSet<(String, String)>,
+}
+
+// ファイルでitem(列挙型のヴァリアントならEnum::Variant)が宣言または再公開されているか
+fn declares<F: KirlFileResolver>(parser: &KirlParser, file_path: &Path, item: &[String], file_resolver: &mut F, loaded_items: &mut LoadedItems) -> Result<bool, KirlCompileError<F::ResolveError>> {
+    if !loaded_items.declared_names.contains_key(file_path) {
+        let syntax_tree = match read_file(file_path, file_resolver, loaded_items)? {
+            Some(file) => parser.parse(&file)?,
+            None => return Ok(false),
+        };
+        let KirlTopLevelItems { statements, structs, enums, type_aliases, functions } = collect_top_level_item_with_imports(syntax_tree);
+        let mut names = DeclaredNames::default();
+        names.items.extend(structs.into_iter().map(|item| item.item.name));
+        names.items.extend(type_aliases.into_iter().map(|item| item.item.name));
+        names.items.extend(functions.into_iter().map(|item| item.item.name));
+        for item in enums {
+            names.enum_variants.extend(item.item.variants.into_iter().map(|(variant_name, _)| (item.item.name.clone(), variant_name)));
+            names.items.insert(item.item.name);
+        }
+        names.items.extend(public_imports(&statements).into_iter().filter_map(|mut path| path.pop()));
+        let (_, variables, _) = analysis_module_statements(statements)?;
+        names.items.extend(variables.into_keys());
+        loaded_items.declared_names.insert(file_path.to_path_buf(), names);
+    }
+    let names = &loaded_items.declared_names[file_path];
+    Ok(match item {
+        [name] => names.items.contains(name),
+        [enum_name, variant_name] => names.enum_variants.contains(&(enum_name.clone(), variant_name.clone())),
+        _ => false,
+    })
+}
+
+// 名前解決より前に、importしたパスに対応するモジュールと、そのモジュールで宣言された名前が存在するか確かめる
+fn validate_imports<F: KirlFileResolver>(parser: &KirlParser, file_path: &Path, statements: &[Statement], enums: &[WithImport<Enum>], file_resolver: &mut F, loaded_items: &mut LoadedItems) -> Result<(), KirlCompileError<F::ResolveError>> {
+    let base_directory = loaded_items.base_directory.clone();
+    let from_base_directory = |path: &Path| path.strip_prefix(&base_directory).unwrap_or(path).to_path_buf();
+    'imports: for (position, path) in import_paths(statements) {
+        match path.as_slice() {
+            [root, rest @ ..] if root == "std" => {
+                if *rest == ["no_prelude"] || get_stdlib().contains_path(rest) {
+                    continue;
+                }
+                return Err(KirlCompileError::UnresolvedImport { path, position, file_path: from_base_directory(file_path), candidates: Vec::new() });
+            }
+            // 同じファイルの列挙型のヴァリアント
+            [name, _] if enums.iter().any(|e| e.item.name == *name) => continue,
+            _ => {}
+        }
+        // import a::b::c; ならa/b/cがモジュールであるか、a/bでcが宣言されているか、aで列挙型bのヴァリアントcが宣言されていればよい
+        let mut candidates = Vec::new();
+        for i in (path.len().saturating_sub(2).max(1)..=path.len()).rev() {
+            let (module, item) = path.split_at(i);
+            let module_path = module_file_path(file_path, module);
+            let module_directory = module_path.with_extension("");
+            for candidate in iter::once(module_path).chain(MODULE_INDEX_FILE_NAMES.iter().map(|name| module_directory.join(name))) {
+                candidates.push(from_base_directory(&candidate));
+                if read_file(&candidate, file_resolver, loaded_items)?.is_some() {
+                    if item.is_empty() || declares(parser, &candidate, item, file_resolver, loaded_items)? {
+                        continue 'imports;
+                    }
+                    // 最初に見つかったファイルがモジュールとして読み込まれる
+                    break;
+                }
+            }
+        }
+        return Err(KirlCompileError::UnresolvedImport { path, position, file_path: from_base_directory(file_path), candidates });
+    }
+    Ok(())
+}
+
+fn register_reexports<F: KirlFileResolver>(parser: &KirlParser, file_path: &Path, reexports: Vec<Vec<String>>, file_resolver: &mut F, loaded_items: &mut LoadedItems) -> Result<(), KirlCompileError<F::ResolveError>> {
+    for path in &reexports {
+        let name = path.last().unwrap().clone();
+        loaded_items.reexports.entry((file_path.to_path_buf(), name)).or_default().push(path.clone());
+    }
+    load_referenced_files(parser, file_path, reexports.iter().map(Vec::as_slice).collect(), file_resolver, loaded_items)?;
+    // モジュールそのものを再公開している場合
+    for path in &reexports {
+        load_file(parser, &module_file_path(file_path, path), file_resolver, loaded_items)?;
+    }
+    Ok(())
+}
+
+fn load_items<F: KirlFileResolver>(parser: &KirlParser, file_path: &Path, structs: Vec<WithImport<Struct>>, enums: Vec<WithImport<Enum>>, type_aliases: Vec<WithImport<TypeAlias>>, functions: Vec<WithImport<Function>>, file_resolver: &mut F, loaded_items: &mut LoadedItems) -> Result<(), KirlCompileError<F::ResolveError>> {
+    loaded_items.parsed_files.insert(file_path.to_path_buf());
+    for item in &structs {
+        if !item.item.public {
+            loaded_items.private_items.structs.insert(struct_path(&loaded_items.base_directory, file_path, &item.item.name));
+        }
+    }
+    for item in &enums {
+        if !item.item.public {
+            loaded_items.private_items.enums.insert(struct_path(&loaded_items.base_directory, file_path, &item.item.name));
+        }
+    }
+    for item in &type_aliases {
+        if !item.item.public {
+            loaded_items.private_items.type_aliases.insert(struct_path(&loaded_items.base_directory, file_path, &item.item.name));
+        }
+    }
+    let mut structs = structs.into_iter().map(|item| Ok((struct_path(&loaded_items.base_directory, file_path, &item.item.name), analysis_struct(item)?))).collect::<Result<Vec<_>, AnalysisStatementError>>()?;
+    // ヴァリアントはそれぞれ構造体として登録し、列挙型はヴァリアントの型のOrの別名とする
+    let mut variant_constructors = Vec::new();
+    let mut unit_variants = Vec::new();
+    for item in enums {
+        let enum_name = item.item.name.clone();
+        let definition = analysis_enum(item)?;
+        let generics_argument_count = definition.generics_argument_count;
+        let generics_arguments = (0..generics_argument_count).map(HIRType::GenericsTypeArgument).collect::<Vec<_>>();
+        let mut variant_types = Vec::with_capacity(definition.variants.len());
+        for (variant_name, variant) in definition.variants {
+            let path = enum_variant_path(&loaded_items.base_directory, file_path, &enum_name, &variant_name);
+            variant_types.push(HIRType::Named { path: path.clone(), generics_arguments: generics_arguments.clone() });
+            let members = match variant {
+                HIREnumVariant::Tuple(items) if items.is_empty() => {
+                    unit_variants.push((enum_name.clone(), variant_name.clone()));
+                    BTreeMap::new()
+                }
+                HIREnumVariant::Tuple(items) => {
+                    variant_constructors.push((enum_variant_constructor_name(&enum_name, &variant_name), (enum_name.clone(), variant_name.clone()), path.clone(), generics_argument_count, items.len()));
+                    items.into_iter().enumerate().map(|(i, ty)| (i.to_string(), ty)).collect()
+                }
+                HIREnumVariant::Struct(members) => members,
+            };
+            structs.push((path, HIRStructDefinition { generics_argument_count, members }));
+        }
+        loaded_items.type_aliases.insert(struct_path(&loaded_items.base_directory, file_path, &enum_name), (generics_argument_count, HIRType::Or(variant_types)));
+    }
+    // 構造体同士で参照できるように先に名前を登録しておく
+    for (path, definition) in &structs {
+        loaded_items.struct_definitions.insert(path.clone(), definition.clone());
+    }
+    let mut type_alias_paths = Vec::with_capacity(type_aliases.len());
+    for item in type_aliases {
+        let name = item.item.name.clone();
+        let (generics_argument_count, ty) = analysis_type_alias(item)?;
+        let path = struct_path(&loaded_items.base_directory, file_path, &name);
+        loaded_items.pending_type_aliases.insert(path.clone(), (file_path.to_path_buf(), name, generics_argument_count, ty));
+        type_alias_paths.push(path);
+    }
+    for path in type_alias_paths {
+        define_type_alias(parser, &path, file_resolver, loaded_items)?;
+    }
+    for (path, definition) in &mut structs {
+        for ty in definition.members.values_mut() {
+            canonicalize_type(parser, file_path, ty, file_resolver, loaded_items)?;
+        }
+        loaded_items.struct_definitions.insert(path.clone(), definition.clone());
+    }
+    // タプル形式のヴァリアントは引数を順にメンバにして構築する関数を持つ
+    for (name, (enum_name, variant_name), path, generics_argument_count, argument_count) in variant_constructors {
+        let arguments = (0..argument_count).map(|i| loaded_items.struct_definitions[&path].members[&i.to_string()].clone()).collect::<Vec<_>>();
+        let generics_arguments = (0..generics_argument_count).map(|i| if arguments.iter().any(|ty| uses_generics_argument(ty, i)) { HIRType::GenericsTypeArgument(i) } else { HIRType::Infer }).collect();
+        let result = HIRType::Named { path, generics_arguments };
+        // 本体は通常の関数と同じく名前解決されるので、定義したファイルから見た名前で構造体を指す
+        let body = vec![
+            HIRStatement::Binding {
+                position: Default::default(),
+                variable_id: argument_count,
+                variable_type: HIRType::Infer,
+                expression: HIRExpression::ConstructNamedStruct {
+                    struct_type: HIRType::Named { path: vec![enum_name, variant_name], generics_arguments: Vec::new() },
+                    members: (0..argument_count).map(|i| (i.to_string(), Variable::Unnamed(i))).collect(),
+                },
+            },
+            HIRStatement::Return(Default::default(), Variable::Unnamed(argument_count)),
+        ];
+        let id = Uuid::new_v4();
+        let function_type = HIRType::Function { arguments, result: Box::new(result) };
+        loaded_items.function_types.entry((file_path.to_path_buf(), name.clone())).or_default().push((id, function_type.clone()));
+        register_function_body(id, name, file_path, generics_argument_count, function_type, body, loaded_items);
+    }
+    // 値の無いヴァリアントは、ファイルの初期化より前に一度だけ構築してグローバル変数に入れておく
+    if !unit_variants.is_empty() {
+        let mut statements = Vec::with_capacity(unit_variants.len());
+        let mut variables = BTreeMap::new();
+        for (variable_id, (enum_name, variant_name)) in unit_variants.into_iter().enumerate() {
+            variables.insert(enum_variant_constructor_name(&enum_name, &variant_name), variable_id);
+            statements.push(HIRStatement::Binding {
+                position: Default::default(),
+                variable_id,
+                variable_type: HIRType::Infer,
+                expression: HIRExpression::ConstructNamedStruct {
+                    struct_type: HIRType::Named { path: vec![enum_name, variant_name], generics_arguments: Vec::new() },
+                    members: BTreeMap::new(),
+                },
+            });
+        }
+        loaded_items.pending_module_initializers.push_back((file_path.to_path_buf(), statements, variables));
+    }
+    for function in functions {
+        let function_name = function.item.name.clone();
+        let public = function.item.public;
+        let generics_argument_count = function.item.generics_arguments.len();
+        let (function_body, mut arguments, mut result, bounds) = analysis_function(function)?;
+        for ty in arguments.iter_mut().chain(iter::once(&mut result)) {
+            canonicalize_type(parser, file_path, ty, file_resolver, loaded_items)?;
+        }
+        let id = Uuid::new_v4();
+        if !public {
+            loaded_items.private_items.functions.insert(id);
+        }
+        let function_type = HIRType::Function { arguments, result: Box::new(result) };
+        loaded_items.function_types.entry((file_path.to_path_buf(), function_name.clone())).or_default().push((id, function_type.clone()));
+        register_function_body(id, function_name, file_path, generics_argument_count, function_type, function_body, loaded_items);
+        if !bounds.is_empty() {
+            loaded_items.pending_generics_bounds.push((id, file_path.to_path_buf(), bounds));
+        }
+    }
+    Ok(())
+}
+
+// エラーメッセージ用に参照元のファイルからの相対パスにする
+fn relative_file_path(base_file_path: &Path, file_path: PathBuf) -> PathBuf {
+    match base_file_path.parent().and_then(|base| file_path.strip_prefix(base).ok()) {
+        Some(relative) => relative.to_path_buf(),
+        None => file_path,
+    }
+}
+
+fn register_private_global_variables(file_path: &Path, variables: &BTreeMap<String, usize>, public_variables: &BTreeSet<String>, loaded_items: &mut LoadedItems) {
+    for name in variables.keys().filter(|name| !public_variables.contains(*name)) {
+        loaded_items.private_items.global_variables.insert((file_path.to_path_buf(), name.clone()));
+    }
+}
+
+fn register_function_body(id: Uuid, name: String, file_path: &Path, generics_argument_count: usize, function_type: HIRType, body: Vec<HIRStatement<SearchPaths>>, loaded_items: &mut LoadedItems) {
+    if generics_argument_count == 0 {
+        loaded_items.parsed_functions.insert(id, body);
+    } else {
+        loaded_items.generic_functions.insert(id, GenericFunction { name, file_path: file_path.to_path_buf(), generics_argument_count, function_type, body });
+    }
+}
+
+// 型の別名は、そこから参照されている別名(他のファイルのものも含む)を先に定義してから登録する
+// 定義中の別名への参照は再帰的な型になり、定義が終わった別名はfinished_type_aliasesに入る
+fn define_type_alias<F: KirlFileResolver>(parser: &KirlParser, path: &[String], file_resolver: &mut F, loaded_items: &mut LoadedItems) -> Result<(), KirlCompileError<F::ResolveError>> {
+    let (file_path, name, generics_argument_count, mut ty) = match loaded_items.pending_type_aliases.remove(path) {
+        Some(alias) => alias,
+        None => return Ok(()),
+    };
+    let path = path.to_vec();
+    // 定義が終わるまでは自分自身を指す名前付きの型として展開させる
+    let placeholder = HIRType::Named { path: path.clone(), generics_arguments: (0..generics_argument_count).map(HIRType::GenericsTypeArgument).collect() };
+    loaded_items.type_aliases.insert(path.clone(), (generics_argument_count, placeholder));
+    loaded_items.defining_type_aliases.push((path.clone(), generics_argument_count));
+    let dependencies = type_references(&ty).into_iter().map(|path| path.to_vec()).collect::<Vec<_>>();
+    for dependency in dependencies {
+        // 他のファイルの別名は、そのファイルを読み込んで定義待ちにしてから探す
+        if let [module @ .., _] = dependency.as_slice() {
+            if !module.is_empty() {
+                load_file(parser, &module_file_path(&file_path, module), file_resolver, loaded_items)?;
+            }
+        }
+        let (dependency_file_path, dependency_name) = loaded_items.resolver(&file_path).split_path(&dependency);
+        let dependency_path = struct_path(&loaded_items.base_directory, &dependency_file_path, &dependency_name);
+        define_type_alias(parser, &dependency_path, file_resolver, loaded_items)?;
+    }
+    canonicalize_type(parser, &file_path, &mut ty, file_resolver, loaded_items)?;
+    let mut ty = into_recursive_reference(ty, &loaded_items.defining_type_aliases)?;
+    loaded_items.defining_type_aliases.pop();
+    if ty.contains_recursive_reference(&path) {
+        if !ty.is_guarded_recursion(&path) {
+            return Err(AnalysisStatementError::UnguardedRecursiveTypeAlias(name).into());
+        }
+        ty = HIRType::Recursive { path: path.clone(), body: Box::new(ty) };
+    }
+    loaded_items.type_aliases.insert(path.clone(), (generics_argument_count, ty));
+    loaded_items.finished_type_aliases.push(path);
+    if loaded_items.defining_type_aliases.is_empty() {
+        // 後に定義が終わったものほど外側なので、そちらから順に内側の別名に残った参照へ埋め込む
+        let finished = mem::take(&mut loaded_items.finished_type_aliases);
+        for (i, outer) in finished.iter().enumerate().rev() {
+            let outer_type = loaded_items.type_aliases[outer].1.clone();
+            for inner in &finished[..i] {
+                let (_, inner_type) = loaded_items.type_aliases.get_mut(inner).unwrap();
+                *inner_type = inner_type.replace_recursive_reference(outer, &outer_type);
+            }
+        }
+    }
+    Ok(())
+}
+
+fn fold_type_aliases(ty: HIRType, aliases: &[(&Vec<String>, usize, HIRType)]) -> HIRType {
+    let normalized = ty.clone().into_normalized();
+    for (path, generics_argument_count, body) in aliases {
+        let mut generics_arguments = vec![None; *generics_argument_count];
+        if match_type_alias(body, &normalized, &mut generics_arguments) {
+            let generics_arguments = generics_arguments.into_iter().map(|ty| fold_type_aliases(ty.unwrap_or(HIRType::Infer), aliases)).collect();
+            return HIRType::Named { path: (*path).clone(), generics_arguments };
+        }
+    }
+    let fold = |ty| fold_type_aliases(ty, aliases);
+    match ty {
+        HIRType::Named { path, generics_arguments } => HIRType::Named { path, generics_arguments: generics_arguments.into_iter().map(fold).collect() },
+        HIRType::Tuple(items) => HIRType::Tuple(items.into_iter().map(fold).collect()),
+        HIRType::Array(item) => HIRType::Array(Box::new(fold(*item))),
+        HIRType::Function { arguments, result } => HIRType::Function { arguments: arguments.into_iter().map(fold).collect(), result: Box::new(fold(*result)) },
+        HIRType::AnonymousStruct(members) => HIRType::AnonymousStruct(members.into_iter().map(|(k, ty)| (k, fold(ty))).collect()),
+        HIRType::Or(items) => HIRType::Or(items.into_iter().map(fold).collect()),
+        ty => ty,
+    }
+}
+
+// 別名の展開結果bodyとtyが一致するか調べ、一致すればbody中の型引数に当たる型を求める
+fn match_type_alias(body: &HIRType, ty: &HIRType, generics_arguments: &mut [Option<HIRType>]) -> bool {
+    let all_match = |items1: &[HIRType], items2: &[HIRType], generics_arguments: &mut [Option<HIRType>]| items1.len() == items2.len() && items1.iter().zip(items2).all(|(ty1, ty2)| match_type_alias(ty1, ty2, generics_arguments));
+    match (body, ty) {
+        (HIRType::GenericsTypeArgument(i), ty) => match generics_arguments.get_mut(*i) {
+            Some(Some(bound)) => bound == ty,
+            Some(argument) => {
+                *argument = Some(ty.clone());
+                true
+            }
+            None => body == ty,
+        },
+        (HIRType::Named { path: path1, generics_arguments: items1 }, HIRType::Named { path: path2, generics_arguments: items2 }) => path1 == path2 && all_match(items1, items2, generics_arguments),
+        (HIRType::Tuple(items1), HIRType::Tuple(items2)) | (HIRType::Or(items1), HIRType::Or(items2)) => all_match(items1, items2, generics_arguments),
+        (HIRType::Array(item1), HIRType::Array(item2)) => match_type_alias(item1, item2, generics_arguments),
+        (HIRType::Function { arguments: arguments1, result: result1 }, HIRType::Function { arguments: arguments2, result: result2 }) => all_match(arguments1, arguments2, generics_arguments) && match_type_alias(result1, result2, generics_arguments),
+        (HIRType::AnonymousStruct(members1), HIRType::AnonymousStruct(members2)) => members1.len() == members2.len() && members1.iter().zip(members2).all(|((k1, ty1), (k2, ty2))| k1 == k2 && match_type_alias(ty1, ty2, generics_arguments)),
+        (body, ty) => body == ty,
+    }
+}
+
+// 定義中の別名は自分自身を指す名前付きの型として展開されているので、再帰的な型の参照に置き換える
+fn into_recursive_reference(ty: HIRType, defining: &[(Vec<String>, usize)]) -> Result<HIRType, AnalysisStatementError> {
+    let into = |ty| into_recursive_reference(ty, defining);
+    Ok(match ty {
+        HIRType::Named { path, generics_arguments } => match defining.iter().find(|(defining_path, _)| *defining_path == path) {
+            Some((_, generics_argument_count)) => {
+                if !generics_arguments.iter().cloned().eq((0..*generics_argument_count).map(HIRType::GenericsTypeArgument)) {
+                    return Err(AnalysisStatementError::IrregularRecursiveTypeAlias(path.last().cloned().unwrap_or_default()));
+                }
+                HIRType::RecursiveReference(path)
+            }
+            None => HIRType::Named { path, generics_arguments: generics_arguments.into_iter().map(into).collect::<Result<_, _>>()? },
+        },
+        HIRType::Tuple(items) => HIRType::Tuple(items.into_iter().map(into).collect::<Result<_, _>>()?),
+        HIRType::Array(item) => HIRType::Array(Box::new(into(*item)?)),
+        HIRType::Function { arguments, result } => HIRType::Function { arguments: arguments.into_iter().map(into).collect::<Result<_, _>>()?, result: Box::new(into(*result)?) },
+        HIRType::AnonymousStruct(members) => HIRType::AnonymousStruct(members.into_iter().map(|(k, ty)| Ok((k, into(ty)?))).collect::<Result<_, AnalysisStatementError>>()?),
+        HIRType::Or(items) => HIRType::Or(items.into_iter().map(into).collect::<Result<_, _>>()?),
+        HIRType::Recursive { path, body } => HIRType::Recursive { path, body: Box::new(into(*body)?) },
+        ty @ (HIRType::Infer | HIRType::Unreachable | HIRType::GenericsTypeArgument(_) | HIRType::RecursiveReference(_)) => ty,
+    })
+}
+
+// 型中の構造体名を定義されたファイルに基づく名前に置き換える
+fn canonicalize_type<F: KirlFileResolver>(parser: &KirlParser, file_path: &Path, ty: &mut HIRType, file_resolver: &mut F, loaded_items: &mut LoadedItems) -> Result<(), KirlCompileError<F::ResolveError>> {
+    let modules = type_references(ty).into_iter().filter_map(|path| path.split_last().map(|(_, module)| module.to_vec())).filter(|module| !module.is_empty()).collect::<Vec<_>>();
+    for module in modules {
+        load_file(parser, &module_file_path(file_path, &module), file_resolver, loaded_items)?;
+        // module::Enum::Variantの場合はmoduleを読み込む
+        if let [enum_module @ .., _] = module.as_slice() {
+            if !enum_module.is_empty() {
+                load_file(parser, &module_file_path(file_path, enum_module), file_resolver, loaded_items)?;
+            }
+        }
+    }
+    if let Some((kind, name, file_path)) = type_references(ty).into_iter().find_map(|path| loaded_items.find_private_type(file_path, path)) {
+        return Err(KirlCompileError::PrivateItem { kind, name, file_path });
+    }
+    *ty = resolve_type(mem::replace(ty, HIRType::Infer), &mut (loaded_items.resolver(file_path), std_resolver())).map_err(|e| loaded_items.compile_error(file_path, e))?;
+    check_generics_argument_count(ty, &loaded_items.struct_definitions)?;
     Ok(())
 }
 
+// 標準ライブラリはstd::以下の名前で参照する
+fn std_resolver() -> HashMap<String, &'static KirlStdLib> {
+    HashMap::from([("std".to_string(), get_stdlib())])
+}
+
 pub fn compile<F: KirlFileResolver>(file_resolver: &mut F, entry_point: impl AsRef<Path>) -> Result<KirlVMExecutable, KirlCompileError<F::ResolveError>> {
     let stdlib = get_stdlib();
-    let mut resolver = HashMap::new();
-    resolver.insert("std".to_string(), stdlib);
+    let mut resolver = std_resolver();
     let parser = KirlParser::new();
-    let entry_point_body = file_resolver.resolve_file_by_path(&entry_point).map_err(KirlCompileError::FileResolveError)?;
+    let entry_point_body = file_resolver.resolve_file_by_path(&entry_point).map_err(KirlCompileError::FileResolveError)?.ok_or_else(|| KirlCompileError::EntryPointNotFound(entry_point.as_ref().to_path_buf()))?;
     let syntax_tree = parser.parse(&entry_point_body)?;
-    let KirlTopLevelItems { statements, functions, .. } = collect_top_level_item_with_imports(syntax_tree);
-    let hir = analysis_statements(statements)?;
-    let mut parsed_files = HashSet::new();
-    let mut function_types = HashMap::new();
-    let mut parsed_functions = HashMap::new();
-    let mut loaded_functions = HashMap::new();
-    for function in functions {
-        add_parsed_function(&mut function_types, &mut parsed_functions, &entry_point.as_ref().to_path_buf(), function)?;
-    }
-    let hir = resolve(
-        &parser,
-        hir,
-        HIRType::Function {
-            arguments: Vec::new(),
-            result: Box::new(HIRType::Or(vec![HIRType::Tuple(Vec::new()), HIRType::Named { path: vec!["Number".to_string()], generics_arguments: Vec::new() }])),
-        },
-        &entry_point,
-        file_resolver,
-        &mut parsed_files,
-        &mut resolver,
-        &mut function_types,
-        &mut parsed_functions,
-        &mut loaded_functions,
-    )?;
-    let lir = hir_to_lir(hir, 0)?;
-    loaded_functions.insert(Uuid::nil(), lir);
-    Ok(KirlVMExecutable::new(loaded_functions.into_iter().map(|(id, code)| (id, code.0)), stdlib.static_values(), stdlib.functions(), Uuid::nil()))
+    let KirlTopLevelItems { statements, structs, enums, type_aliases, functions } = collect_top_level_item_with_imports(syntax_tree);
+    let mut loaded_items = LoadedItems { base_directory: entry_point.as_ref().parent().map(Path::to_path_buf).unwrap_or_default(), ..LoadedItems::default() };
+    if !uses_prelude(&statements) {
+        loaded_items.no_prelude_files.insert(entry_point.as_ref().to_path_buf());
+    }
+    validate_imports(&parser, entry_point.as_ref(), &statements, &enums, file_resolver, &mut loaded_items)?;
+    let (hir, top_level_variables, public_variables) = analysis_module_statements(statements)?;
+    register_private_global_variables(entry_point.as_ref(), &top_level_variables, &public_variables, &mut loaded_items);
+    load_items(&parser, entry_point.as_ref(), structs, enums, type_aliases, functions, file_resolver, &mut loaded_items)?;
+    resolve(&parser, Uuid::nil(), TOP_LEVEL_NAME, hir, top_level_type(), &entry_point, &[], &top_level_variables, file_resolver, &mut resolver, &mut loaded_items)?;
+    let LoadedItems { module_initializers, mut loaded_functions, .. } = loaded_items;
+    // モジュールのトップレベルの文はエントリーポイントより先に実行する
+    let (entry_point_debug_info, entry_point_code) = loaded_functions.remove(&Uuid::nil()).expect("");
+    let initializer_calls = module_initializers.into_iter().flat_map(|id| [LIRStatement::from(LIRInstruction::CallFunction(id)), LIRStatement::from(LIRInstruction::Pop)]);
+    loaded_functions.insert(Uuid::nil(), (entry_point_debug_info, initializer_calls.chain(entry_point_code.0).collect::<Vec<_>>().into()));
+    Ok(KirlVMExecutable::new(loaded_functions.into_iter().map(|(id, (debug_info, code))| (id, debug_info, code.0)), stdlib.static_values(), stdlib.functions(), Uuid::nil()))
 }
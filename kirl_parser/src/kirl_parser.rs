@@ -23,12 +23,15 @@ pub struct Statement {
 
 #[derive(Debug, PartialEq, Clone)]
 pub enum StatementItem {
-    Import(ImportPath),
+    // トップレベルのpub importのみpublicがtrueで、他のファイルへ再公開される
+    Import { public: bool, path: ImportPath },
     Expression(Expression),
     LetBinding(LetBinding),
     Return(Option<Expression>),
+    Yield(Expression),
     Continue(Option<String>),
-    Break(Option<String>),
+    // ラベルは break 'label value; のように'を付けて書き、値の変数と区別する
+    Break(Option<String>, Option<Expression>),
     For(ForStatement),
     While(WhileStatement),
 }
@@ -79,10 +82,15 @@ pub struct Block {
 pub struct Function {
     pub position: Range<CharacterPosition>,
     pub name: String,
-    pub generics_arguments: Vec<String>,
+    // 型引数の名前と、その型引数に課す制約の名前
+    pub generics_arguments: Vec<(String, Vec<String>)>,
     pub arguments: Vec<(Pattern, Type)>,
     pub return_type: Type,
     pub expression: Expression,
+    // fn*で定義されたジェネレータ関数
+    pub generator: bool,
+    // pubが付いていれば他のファイルから参照できる
+    pub public: bool,
 }
 
 #[derive(Debug, Default, PartialEq, Clone)]
@@ -90,6 +98,37 @@ pub struct Struct {
     pub name: String,
     pub generics_arguments: Vec<String>,
     pub members: Vec<(String, Type)>,
+    pub public: bool,
+}
+
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct Enum {
+    pub name: String,
+    pub generics_arguments: Vec<String>,
+    pub variants: Vec<(String, EnumVariant)>,
+    pub public: bool,
+}
+
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct TypeAlias {
+    pub name: String,
+    pub generics_arguments: Vec<String>,
+    pub aliased_type: Type,
+    pub public: bool,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum EnumVariant {
+    // Circle(Number) 値の無いものは要素0個のTupleとし、Shape::Emptyのように値やパターンとして書ける
+    Tuple(Vec<Type>),
+    // Rect#{w: Number, h: Number}
+    Struct(Vec<(String, Type)>),
+}
+
+impl Default for EnumVariant {
+    fn default() -> Self {
+        EnumVariant::Tuple(Vec::new())
+    }
 }
 
 #[derive(Debug, PartialEq, Clone)]
@@ -260,6 +299,8 @@ pub enum ExpressionItem {
     AccessVariable(Path, Vec<Type>),
     StringImmediate(String),
     NumberImmediate(Decimal128),
+    FormatString(Vec<FormatStringItem>),
+    // 式の部分はto_stringして_addでつなげる
     AccessTupleItem(Box<Expression>, usize),
     AccessMember(Box<Expression>, String),
     CallFunction(FunctionReference, Vec<Expression>),
@@ -300,10 +341,32 @@ pub enum ExpressionItem {
     // _xor
     Or(Box<Expression>, Box<Expression>),
     // _or
+    LogicalAnd(Box<Expression>, Box<Expression>),
+    // 左辺がtrueの時のみ右辺を評価する
+    LogicalOr(Box<Expression>, Box<Expression>),
+    // 左辺がfalseの時のみ右辺を評価する
+    Range(Box<Expression>, Box<Expression>),
+    // _range
+    RangeInclusive(Box<Expression>, Box<Expression>),
+    // _range_inclusive
     Assign(Box<Expression>, Box<Expression>),
+    AddAssign(Box<Expression>, Box<Expression>),
+    // _addして代入(左辺の参照先は一度だけ評価する)
+    SubAssign(Box<Expression>, Box<Expression>),
+    // _subして代入
+    MulAssign(Box<Expression>, Box<Expression>),
+    // _mulして代入
+    DivAssign(Box<Expression>, Box<Expression>),
+    // _divして代入
+    RemAssign(Box<Expression>, Box<Expression>),
+    // _remして代入
+    Try(Box<Expression>),
+    // #{error: String}ならそのまま関数から返す
     Closure(Closure),
     If(If),
     Match(Match),
+    Loop(Loop),
+    // 値はbreakで指定されたものの和
 }
 
 impl Default for ExpressionItem {
@@ -312,6 +375,12 @@ impl Default for ExpressionItem {
     }
 }
 
+#[derive(Debug, PartialEq, Clone)]
+pub enum FormatStringItem {
+    Text(String),
+    Expression(Expression),
+}
+
 #[derive(Debug, PartialEq, Clone)]
 pub enum FunctionReference {
     Dynamic(Box<Expression>),
@@ -335,6 +404,8 @@ pub enum KirlTopLevelStatement {
     Statement((Range<CharacterPosition>, Statement)),
     FunctionDefinition((Range<CharacterPosition>, Function)),
     StructDefinition((Range<CharacterPosition>, Struct)),
+    EnumDefinition((Range<CharacterPosition>, Enum)),
+    TypeAliasDefinition((Range<CharacterPosition>, TypeAlias)),
 }
 
 impl Default for KirlTopLevelStatement {
@@ -347,6 +418,7 @@ impl Default for KirlTopLevelStatement {
 pub enum Pattern {
     Variable(String),
     Tuple(Vec<Pattern>),
+    // Shape::Emptyのような2要素以上のパスはShape::Empty()と同じく中身の無い構造体のパターンとする
     Struct(StructName, Vec<(String, Pattern)>),
 }
 
@@ -362,6 +434,8 @@ pub struct LetBinding {
     pub pattern: Pattern,
     pub type_hint: Option<Type>,
     pub expression: Box<Expression>,
+    // トップレベルのpub varのみtrue
+    pub public: bool,
 }
 
 #[derive(Debug, Default, PartialEq, Clone)]
@@ -379,6 +453,13 @@ pub struct If {
     pub other: Option<Box<Expression>>,
 }
 
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct Loop {
+    pub label: Option<String>,
+    pub position: Range<CharacterPosition>,
+    pub block: Block,
+}
+
 #[derive(Debug, Default, PartialEq, Clone)]
 pub struct Match {
     pub position: Range<CharacterPosition>,
@@ -400,6 +481,10 @@ pub enum Symbol {
     ConstructStruct((Range<CharacterPosition>, ConstructStruct)),
     StructName((Range<CharacterPosition>, StructName)),
     StructDefinition((Range<CharacterPosition>, Struct)),
+    EnumDefinition((Range<CharacterPosition>, Enum)),
+    EnumVariants((Range<CharacterPosition>, Vec<(String, EnumVariant)>)),
+    EnumVariant((Range<CharacterPosition>, (String, EnumVariant))),
+    TypeAliasDefinition((Range<CharacterPosition>, TypeAlias)),
     ConstructStructItems((Range<CharacterPosition>, Vec<(String, Expression)>)),
     LetBinding((Range<CharacterPosition>, LetBinding)),
     UnExpressionStatement((Range<CharacterPosition>, Statement)),
@@ -409,6 +494,7 @@ pub enum Symbol {
     StatementBlock((Range<CharacterPosition>, Block)),
     VariableReference((Range<CharacterPosition>, Path, Vec<Type>)),
     CallFunctionArguments((Range<CharacterPosition>, Vec<Expression>)),
+    FormatStringItems((Range<CharacterPosition>, Vec<FormatStringItem>)),
     Expression8((Range<CharacterPosition>, Expression)),
     Expression7((Range<CharacterPosition>, Expression)),
     Expression6((Range<CharacterPosition>, Expression)),
@@ -418,10 +504,13 @@ pub enum Symbol {
     Expression2((Range<CharacterPosition>, Expression)),
     Expression1((Range<CharacterPosition>, Expression)),
     Expression0((Range<CharacterPosition>, Expression)),
+    LogicalAndExpression((Range<CharacterPosition>, Expression)),
+    LogicalOrExpression((Range<CharacterPosition>, Expression)),
     Expression((Range<CharacterPosition>, Expression)),
     ClosureExpression((Range<CharacterPosition>, Closure)),
     IfExpression((Range<CharacterPosition>, If)),
     MatchExpression((Range<CharacterPosition>, Match)),
+    LoopExpression((Range<CharacterPosition>, Loop)),
     MatchPatterns((Range<CharacterPosition>, Vec<(Pattern, Expression)>)),
     ForStatement((Range<CharacterPosition>, ForStatement)),
     WhileStatement((Range<CharacterPosition>, WhileStatement)),
@@ -431,12 +520,17 @@ pub enum Symbol {
     ClosureArguments((Range<CharacterPosition>, Vec<(Pattern, Option<Type>)>)),
     CommaSeparatedExpressions((Range<CharacterPosition>, Vec<Expression>)),
     FunctionDefinition((Range<CharacterPosition>, Function)),
+    FunctionKeyword((Range<CharacterPosition>, bool)),
     FunctionArguments((Range<CharacterPosition>, Vec<(Pattern, Type)>)),
     ImportStatement((Range<CharacterPosition>, ImportPath)),
     ImportPath((Range<CharacterPosition>, ImportPath)),
     ImportPathChild((Range<CharacterPosition>, ImportPath)),
     CommaSeparatedImportPathChildren((Range<CharacterPosition>, Vec<ImportPath>)),
     GenericsTypeArguments((Range<CharacterPosition>, Vec<String>)),
+    GenericsTypeParameters((Range<CharacterPosition>, Vec<(String, Vec<String>)>)),
+    BoundedGenericsTypeArguments((Range<CharacterPosition>, Vec<(String, Vec<String>)>)),
+    BoundedGenericsTypeArgument((Range<CharacterPosition>, (String, Vec<String>))),
+    GenericsBounds((Range<CharacterPosition>, Vec<String>)),
 }
 
 pub type Parser = LR1Parser<Symbol, Token, ParseErrorDetail>;
@@ -445,8 +539,12 @@ parser! {
     pub fn get_parser() -> LR1Parser {
         token Token{
             "import" = Import(Default::default()),
+            "pub" = Pub(Default::default()),
             "fn" = Fn(Default::default()),
+            "fn*" = GeneratorFn(Default::default()),
             "struct" = Struct(Default::default()),
+            "enum" = Enum(Default::default()),
+            "type" = Type(Default::default()),
             "let" = Let(Default::default()),
             "var" = Var(Default::default()),
             "if" = If(Default::default()),
@@ -455,18 +553,27 @@ parser! {
             "for" = For(Default::default()),
             "in" = In(Default::default()),
             "while" = While(Default::default()),
+            "loop" = Loop(Default::default()),
             "return" = Return(Default::default()),
+            "yield" = Yield(Default::default()),
             "break" = Break(Default::default()),
             "continue" = Continue(Default::default()),
             Identifier = Identifier(Default::default()),
+            Label = Label(Default::default()),
             StringImmediate = StringImmediate(Default::default()),
             NumberImmediate = NumberImmediate(Default::default()),
+            FormatStringStart = FormatStringStart(Default::default()),
+            FormatStringMiddle = FormatStringMiddle(Default::default()),
+            FormatStringEnd = FormatStringEnd(Default::default()),
             TupleIndex = TupleIndex(Default::default()),
             "!" = Not(Default::default()),
+            "?" = Question(Default::default()),
             "." = Dot(Default::default()),
             "," = Comma(Default::default()),
             "#" = Sharp(Default::default()),
             "::" = DoubleColon(Default::default()),
+            ".." = DoubleDot(Default::default()),
+            "..=" = DoubleDotEquals(Default::default()),
             ":" = Colon(Default::default()),
             ";" = Semicolon(Default::default()),
             ">" = GreaterThan(Default::default()),
@@ -476,6 +583,11 @@ parser! {
             "=" = Assign(Default::default()),
             "==" = Equals(Default::default()),
             "!=" = NotEquals(Default::default()),
+            "+=" = AddAssign(Default::default()),
+            "-=" = SubAssign(Default::default()),
+            "*=" = MulAssign(Default::default()),
+            "/=" = DivAssign(Default::default()),
+            "%=" = RemAssign(Default::default()),
             "+" = Add(Default::default()),
             "-" = Sub(Default::default()),
             "*" = Mul(Default::default()),
@@ -484,6 +596,8 @@ parser! {
             "&" = And(Default::default()),
             "|" = Or(Default::default()),
             "^" = Xor(Default::default()),
+            "&&" = LogicalAnd(Default::default()),
+            "||" = LogicalOr(Default::default()),
             "(" = RoundBracketOpen(Default::default()),
             ")" = RoundBracketClose(Default::default()),
             "[" = SquareBracketOpen(Default::default()),
@@ -507,6 +621,10 @@ parser! {
             ConstructStruct = ConstructStruct(Default::default()),
             StructName = StructName(Default::default()),
             StructDefinition = StructDefinition(Default::default()),
+            EnumDefinition = EnumDefinition(Default::default()),
+            EnumVariants = EnumVariants(Default::default()),
+            EnumVariant = EnumVariant(Default::default()),
+            TypeAliasDefinition = TypeAliasDefinition(Default::default()),
             ConstructStructItems = ConstructStructItems(Default::default()),
             LetBinding = LetBinding(Default::default()),
             UnExpressionStatement = UnExpressionStatement(Default::default()),
@@ -516,6 +634,7 @@ parser! {
             StatementBlock = StatementBlock(Default::default()),
             VariableReference = VariableReference(Default::default()),
             CallFunctionArguments = CallFunctionArguments(Default::default()),
+            FormatStringItems = FormatStringItems(Default::default()),
             Expression8 = Expression8(Default::default()),
             Expression7 = Expression7(Default::default()),
             Expression6 = Expression6(Default::default()),
@@ -525,10 +644,13 @@ parser! {
             Expression2 = Expression2(Default::default()),
             Expression1 = Expression1(Default::default()),
             Expression0 = Expression0(Default::default()),
+            LogicalAndExpression = LogicalAndExpression(Default::default()),
+            LogicalOrExpression = LogicalOrExpression(Default::default()),
             Expression = Expression(Default::default()),
             ClosureExpression = ClosureExpression(Default::default()),
             IfExpression = IfExpression(Default::default()),
             MatchExpression = MatchExpression(Default::default()),
+            LoopExpression = LoopExpression(Default::default()),
             MatchPatterns = MatchPatterns(Default::default()),
             ForStatement = ForStatement(Default::default()),
             WhileStatement = WhileStatement(Default::default()),
@@ -538,12 +660,17 @@ parser! {
             ClosureArguments = ClosureArguments(Default::default()),
             CommaSeparatedExpressions = CommaSeparatedExpressions(Default::default()),
             FunctionDefinition = FunctionDefinition(Default::default()),
+            FunctionKeyword = FunctionKeyword(Default::default()),
             FunctionArguments = FunctionArguments(Default::default()),
             ImportStatement = ImportStatement(Default::default()),
             ImportPath = ImportPath(Default::default()),
             ImportPathChild = ImportPathChild(Default::default()),
             CommaSeparatedImportPathChildren = CommaSeparatedImportPathChildren(Default::default()),
             GenericsTypeArguments = GenericsTypeArguments(Default::default()),
+            GenericsTypeParameters = GenericsTypeParameters(Default::default()),
+            BoundedGenericsTypeArguments = BoundedGenericsTypeArguments(Default::default()),
+            BoundedGenericsTypeArgument = BoundedGenericsTypeArgument(Default::default()),
+            GenericsBounds = GenericsBounds(Default::default()),
         }
         errortype ParseErrorDetail;
         start ValidKirlCode;
@@ -572,9 +699,78 @@ parser! {
                     }))),
                     _ => unreachable!(),
                 };
+            |<ValidKirlCode> <EnumDefinition>: list => match list {
+                    [NonTerminal(Symbol::ValidKirlCode((_, list))), NonTerminal(Symbol::EnumDefinition((range, e)))] if list.is_empty() => Ok(Symbol::ValidKirlCode((range.clone(), vec![KirlTopLevelStatement::EnumDefinition((mem::take(range), mem::take(e)))]))),
+                    [NonTerminal(Symbol::ValidKirlCode((Range { start, .. }, list))), NonTerminal(Symbol::EnumDefinition((range, e)))] => Ok(Symbol::ValidKirlCode((*start..range.end, {
+                        list.push(KirlTopLevelStatement::EnumDefinition((mem::take(range), mem::take(e))));
+                        mem::take(list)
+                    }))),
+                    _ => unreachable!(),
+                };
+            |<ValidKirlCode> <TypeAliasDefinition>: list => match list {
+                    [NonTerminal(Symbol::ValidKirlCode((_, list))), NonTerminal(Symbol::TypeAliasDefinition((range, alias)))] if list.is_empty() => Ok(Symbol::ValidKirlCode((range.clone(), vec![KirlTopLevelStatement::TypeAliasDefinition((mem::take(range), mem::take(alias)))]))),
+                    [NonTerminal(Symbol::ValidKirlCode((Range { start, .. }, list))), NonTerminal(Symbol::TypeAliasDefinition((range, alias)))] => Ok(Symbol::ValidKirlCode((*start..range.end, {
+                        list.push(KirlTopLevelStatement::TypeAliasDefinition((mem::take(range), mem::take(alias))));
+                        mem::take(list)
+                    }))),
+                    _ => unreachable!(),
+                };
+            |<ValidKirlCode> "pub" <FunctionDefinition>: list => match list {
+                    [NonTerminal(Symbol::ValidKirlCode((_, list))), Terminal(Token::Pub(Range { start, .. })), NonTerminal(Symbol::FunctionDefinition((Range { end, .. }, function)))] if list.is_empty() => Ok(Symbol::ValidKirlCode((*start..*end, vec![KirlTopLevelStatement::FunctionDefinition((*start..*end, Function { public: true, ..mem::take(function) }))]))),
+                    [NonTerminal(Symbol::ValidKirlCode((Range { start, .. }, list))), Terminal(Token::Pub(Range { start: pub_start, .. })), NonTerminal(Symbol::FunctionDefinition((Range { end, .. }, function)))] => Ok(Symbol::ValidKirlCode((*start..*end, {
+                        list.push(KirlTopLevelStatement::FunctionDefinition((*pub_start..*end, Function { public: true, ..mem::take(function) })));
+                        mem::take(list)
+                    }))),
+                    _ => unreachable!(),
+                };
+            |<ValidKirlCode> "pub" <StructDefinition>: list => match list {
+                    [NonTerminal(Symbol::ValidKirlCode((_, list))), Terminal(Token::Pub(Range { start, .. })), NonTerminal(Symbol::StructDefinition((Range { end, .. }, s)))] if list.is_empty() => Ok(Symbol::ValidKirlCode((*start..*end, vec![KirlTopLevelStatement::StructDefinition((*start..*end, Struct { public: true, ..mem::take(s) }))]))),
+                    [NonTerminal(Symbol::ValidKirlCode((Range { start, .. }, list))), Terminal(Token::Pub(Range { start: pub_start, .. })), NonTerminal(Symbol::StructDefinition((Range { end, .. }, s)))] => Ok(Symbol::ValidKirlCode((*start..*end, {
+                        list.push(KirlTopLevelStatement::StructDefinition((*pub_start..*end, Struct { public: true, ..mem::take(s) })));
+                        mem::take(list)
+                    }))),
+                    _ => unreachable!(),
+                };
+            |<ValidKirlCode> "pub" <EnumDefinition>: list => match list {
+                    [NonTerminal(Symbol::ValidKirlCode((_, list))), Terminal(Token::Pub(Range { start, .. })), NonTerminal(Symbol::EnumDefinition((Range { end, .. }, e)))] if list.is_empty() => Ok(Symbol::ValidKirlCode((*start..*end, vec![KirlTopLevelStatement::EnumDefinition((*start..*end, Enum { public: true, ..mem::take(e) }))]))),
+                    [NonTerminal(Symbol::ValidKirlCode((Range { start, .. }, list))), Terminal(Token::Pub(Range { start: pub_start, .. })), NonTerminal(Symbol::EnumDefinition((Range { end, .. }, e)))] => Ok(Symbol::ValidKirlCode((*start..*end, {
+                        list.push(KirlTopLevelStatement::EnumDefinition((*pub_start..*end, Enum { public: true, ..mem::take(e) })));
+                        mem::take(list)
+                    }))),
+                    _ => unreachable!(),
+                };
+            |<ValidKirlCode> "pub" <TypeAliasDefinition>: list => match list {
+                    [NonTerminal(Symbol::ValidKirlCode((_, list))), Terminal(Token::Pub(Range { start, .. })), NonTerminal(Symbol::TypeAliasDefinition((Range { end, .. }, alias)))] if list.is_empty() => Ok(Symbol::ValidKirlCode((*start..*end, vec![KirlTopLevelStatement::TypeAliasDefinition((*start..*end, TypeAlias { public: true, ..mem::take(alias) }))]))),
+                    [NonTerminal(Symbol::ValidKirlCode((Range { start, .. }, list))), Terminal(Token::Pub(Range { start: pub_start, .. })), NonTerminal(Symbol::TypeAliasDefinition((Range { end, .. }, alias)))] => Ok(Symbol::ValidKirlCode((*start..*end, {
+                        list.push(KirlTopLevelStatement::TypeAliasDefinition((*pub_start..*end, TypeAlias { public: true, ..mem::take(alias) })));
+                        mem::take(list)
+                    }))),
+                    _ => unreachable!(),
+                };
+            |<ValidKirlCode> "pub" <LetBinding> ";": list => match list {
+                    [NonTerminal(Symbol::ValidKirlCode((_, list))), Terminal(Token::Pub(Range { start, .. })), NonTerminal(Symbol::LetBinding((_, let_binding))), Terminal(Token::Semicolon(Range { end, .. }))] if list.is_empty() => Ok(Symbol::ValidKirlCode((*start..*end, vec![KirlTopLevelStatement::Statement((*start..*end, Statement { position: *start..*end, statement: StatementItem::LetBinding(LetBinding { public: true, ..mem::take(let_binding) }) }))]))),
+                    [NonTerminal(Symbol::ValidKirlCode((Range { start, .. }, list))), Terminal(Token::Pub(Range { start: pub_start, .. })), NonTerminal(Symbol::LetBinding((_, let_binding))), Terminal(Token::Semicolon(Range { end, .. }))] => Ok(Symbol::ValidKirlCode((*start..*end, {
+                        list.push(KirlTopLevelStatement::Statement((*pub_start..*end, Statement { position: *pub_start..*end, statement: StatementItem::LetBinding(LetBinding { public: true, ..mem::take(let_binding) }) })));
+                        mem::take(list)
+                    }))),
+                    _ => unreachable!(),
+                };
+            |<ValidKirlCode> "pub" <ImportStatement>: list => match list {
+                    [NonTerminal(Symbol::ValidKirlCode((_, list))), Terminal(Token::Pub(Range { start, .. })), NonTerminal(Symbol::ImportStatement((Range { end, .. }, path)))] if list.is_empty() => Ok(Symbol::ValidKirlCode((*start..*end, vec![KirlTopLevelStatement::Statement((*start..*end, Statement { position: *start..*end, statement: StatementItem::Import { public: true, path: mem::take(path) } }))]))),
+                    [NonTerminal(Symbol::ValidKirlCode((Range { start, .. }, list))), Terminal(Token::Pub(Range { start: pub_start, .. })), NonTerminal(Symbol::ImportStatement((Range { end, .. }, path)))] => Ok(Symbol::ValidKirlCode((*start..*end, {
+                        list.push(KirlTopLevelStatement::Statement((*pub_start..*end, Statement { position: *pub_start..*end, statement: StatementItem::Import { public: true, path: mem::take(path) } })));
+                        mem::take(list)
+                    }))),
+                    _ => unreachable!(),
+                };
             |<ValidKirlCode> ERROR: [_, Error(error_tokens)] => Err(ParseErrorDetail::SyntaxErrorAt(error_tokens.first().map(Token::get_position).map(|range|range.start).unwrap_or_default()..error_tokens.last().map(Token::get_position).map(|range|range.end).unwrap_or_default()));
         <Type1>::=<FullPath>: [NonTerminal(Symbol::FullPath((position, Path { path, .. })))] => Ok(Symbol::Type1((position.clone(), Type::NamedType(NamedType { position: position.clone(), path: mem::take(path), generics_arguments: Vec::new() }))));
             |"!": [Terminal(Token::Not(position))] => Ok(Symbol::Type1((position.clone(), Type::Unreachable(position.clone()))));
+            |<FullPath> "::" "<" <Type> ">": [NonTerminal(Symbol::FullPath((Range { start, .. }, Path { path, .. }))), _, _, NonTerminal(Symbol::Type((_, ty))), Terminal(Token::GreaterThan(Range { end, .. }))] => Ok(Symbol::Type1((*start..*end, Type::NamedType(NamedType {
+                        position: *start..*end,
+                        path: mem::take(path),
+                        generics_arguments: vec![mem::take(ty)],
+                    }))));
             |<FullPath> "::" "<" <CommaSeparatedTypes> ">": [NonTerminal(Symbol::FullPath((Range { start, .. }, Path { path, .. }))), _, _, NonTerminal(Symbol::CommaSeparatedTypes((_, types))), Terminal(Token::GreaterThan(Range { end, .. }))] => Ok(Symbol::Type1((*start..*end, Type::NamedType(NamedType {
                         position: *start..*end,
                         path: mem::take(path),
@@ -629,10 +825,32 @@ parser! {
             |<StructName> "{" <ConstructStructItems> "," "}": [NonTerminal(Symbol::StructName((Range { start, .. }, name))), _, NonTerminal(Symbol::ConstructStructItems((_, items))), _, Terminal(Token::WaveBracketClose(Range { end, .. }))] => Ok(Symbol::ConstructStruct((*start..*end, ConstructStruct { name: mem::take(name), items: mem::take(items) })));
             |<StructName> "{" "}": [NonTerminal(Symbol::StructName((Range { start, .. }, name))), _, Terminal(Token::WaveBracketClose(Range { end, .. }))] => Ok(Symbol::ConstructStruct((*start..*end, ConstructStruct { name: mem::take(name), items: Vec::new() })));
         <StructName>::="#": [Terminal(Token::Sharp(position))] => Ok(Symbol::StructName((position.clone(), StructName::Anonymous)));
-        <StructDefinition>::="struct" [Identifier] "{" "}": [Terminal(Token::Struct(Range { start, .. })), Terminal(Token::Identifier((_, name))), _, Terminal(Token::WaveBracketClose(Range { end, .. }))] => Ok(Symbol::StructDefinition((*start..*end, Struct { name: name.clone(), generics_arguments: Vec::new(), members: Vec::new() })));
-            |"struct" [Identifier] "::" "<" <GenericsTypeArguments> ">" "{" "}": [Terminal(Token::Struct(Range { start, .. })), Terminal(Token::Identifier((_, name))), _, _, NonTerminal(Symbol::GenericsTypeArguments((_, types))), _, _, Terminal(Token::WaveBracketClose(Range { end, .. }))] => Ok(Symbol::StructDefinition((*start..*end, Struct { name: name.clone(), generics_arguments: mem::take(types), members: Vec::new() })));
-            |"struct" [Identifier] "{" <StructDefinitionItems> "}": [Terminal(Token::Struct(Range { start, .. })), Terminal(Token::Identifier((_, name))), _, NonTerminal(Symbol::StructDefinitionItems((_, items))), Terminal(Token::WaveBracketClose(Range { end, .. }))] => Ok(Symbol::StructDefinition((*start..*end, Struct { name: name.clone(), generics_arguments: Vec::new(), members: mem::take(items) })));
-            |"struct" [Identifier] "::" "<" <GenericsTypeArguments> ">" "{" <StructDefinitionItems> "}": [Terminal(Token::Struct(Range { start, .. })), Terminal(Token::Identifier((_, name))), _, _, NonTerminal(Symbol::GenericsTypeArguments((_, types))), _, _, NonTerminal(Symbol::StructDefinitionItems((_, items))), Terminal(Token::WaveBracketClose(Range { end, .. }))] => Ok(Symbol::StructDefinition((*start..*end, Struct { name: name.clone(), generics_arguments: mem::take(types), members: mem::take(items) })));
+            |<VariableReference> "#": [NonTerminal(Symbol::VariableReference((Range { start, .. }, Path { path, .. }, generics_arguments))), Terminal(Token::Sharp(Range { end, .. }))] => Ok(Symbol::StructName((*start..*end, StructName::Named(NamedType {
+                    position: *start..*end,
+                    path: mem::take(path),
+                    generics_arguments: mem::take(generics_arguments),
+                }))));
+        <StructDefinition>::="struct" [Identifier] "{" "}": [Terminal(Token::Struct(Range { start, .. })), Terminal(Token::Identifier((_, name))), _, Terminal(Token::WaveBracketClose(Range { end, .. }))] => Ok(Symbol::StructDefinition((*start..*end, Struct { name: name.clone(), generics_arguments: Vec::new(), members: Vec::new(), public: false })));
+            |"struct" [Identifier] "::" "<" <GenericsTypeArguments> ">" "{" "}": [Terminal(Token::Struct(Range { start, .. })), Terminal(Token::Identifier((_, name))), _, _, NonTerminal(Symbol::GenericsTypeArguments((_, types))), _, _, Terminal(Token::WaveBracketClose(Range { end, .. }))] => Ok(Symbol::StructDefinition((*start..*end, Struct { name: name.clone(), generics_arguments: mem::take(types), members: Vec::new(), public: false })));
+            |"struct" [Identifier] "{" <StructDefinitionItems> "}": [Terminal(Token::Struct(Range { start, .. })), Terminal(Token::Identifier((_, name))), _, NonTerminal(Symbol::StructDefinitionItems((_, items))), Terminal(Token::WaveBracketClose(Range { end, .. }))] => Ok(Symbol::StructDefinition((*start..*end, Struct { name: name.clone(), generics_arguments: Vec::new(), members: mem::take(items), public: false })));
+            |"struct" [Identifier] "::" "<" <GenericsTypeArguments> ">" "{" <StructDefinitionItems> "}": [Terminal(Token::Struct(Range { start, .. })), Terminal(Token::Identifier((_, name))), _, _, NonTerminal(Symbol::GenericsTypeArguments((_, types))), _, _, NonTerminal(Symbol::StructDefinitionItems((_, items))), Terminal(Token::WaveBracketClose(Range { end, .. }))] => Ok(Symbol::StructDefinition((*start..*end, Struct { name: name.clone(), generics_arguments: mem::take(types), members: mem::take(items), public: false })));
+        <EnumDefinition>::="enum" [Identifier] "{" <EnumVariants> "}": [Terminal(Token::Enum(Range { start, .. })), Terminal(Token::Identifier((_, name))), _, NonTerminal(Symbol::EnumVariants((_, variants))), Terminal(Token::WaveBracketClose(Range { end, .. }))] => Ok(Symbol::EnumDefinition((*start..*end, Enum { name: name.clone(), generics_arguments: Vec::new(), variants: mem::take(variants), public: false })));
+            |"enum" [Identifier] "{" <EnumVariants> "," "}": [Terminal(Token::Enum(Range { start, .. })), Terminal(Token::Identifier((_, name))), _, NonTerminal(Symbol::EnumVariants((_, variants))), _, Terminal(Token::WaveBracketClose(Range { end, .. }))] => Ok(Symbol::EnumDefinition((*start..*end, Enum { name: name.clone(), generics_arguments: Vec::new(), variants: mem::take(variants), public: false })));
+            |"enum" [Identifier] "::" "<" <GenericsTypeArguments> ">" "{" <EnumVariants> "}": [Terminal(Token::Enum(Range { start, .. })), Terminal(Token::Identifier((_, name))), _, _, NonTerminal(Symbol::GenericsTypeArguments((_, types))), _, _, NonTerminal(Symbol::EnumVariants((_, variants))), Terminal(Token::WaveBracketClose(Range { end, .. }))] => Ok(Symbol::EnumDefinition((*start..*end, Enum { name: name.clone(), generics_arguments: mem::take(types), variants: mem::take(variants), public: false })));
+            |"enum" [Identifier] "::" "<" <GenericsTypeArguments> ">" "{" <EnumVariants> "," "}": [Terminal(Token::Enum(Range { start, .. })), Terminal(Token::Identifier((_, name))), _, _, NonTerminal(Symbol::GenericsTypeArguments((_, types))), _, _, NonTerminal(Symbol::EnumVariants((_, variants))), _, Terminal(Token::WaveBracketClose(Range { end, .. }))] => Ok(Symbol::EnumDefinition((*start..*end, Enum { name: name.clone(), generics_arguments: mem::take(types), variants: mem::take(variants), public: false })));
+        <TypeAliasDefinition>::="type" [Identifier] "=" <Type> ";": [Terminal(Token::Type(Range { start, .. })), Terminal(Token::Identifier((_, name))), _, NonTerminal(Symbol::Type((_, ty))), Terminal(Token::Semicolon(Range { end, .. }))] => Ok(Symbol::TypeAliasDefinition((*start..*end, TypeAlias { name: name.clone(), generics_arguments: Vec::new(), aliased_type: mem::take(ty), public: false })));
+            |"type" [Identifier] "::" "<" <GenericsTypeArguments> ">" "=" <Type> ";": [Terminal(Token::Type(Range { start, .. })), Terminal(Token::Identifier((_, name))), _, _, NonTerminal(Symbol::GenericsTypeArguments((_, types))), _, _, NonTerminal(Symbol::Type((_, ty))), Terminal(Token::Semicolon(Range { end, .. }))] => Ok(Symbol::TypeAliasDefinition((*start..*end, TypeAlias { name: name.clone(), generics_arguments: mem::take(types), aliased_type: mem::take(ty), public: false })));
+            |"type" [Identifier] "<" <GenericsTypeArguments> ">" "=" <Type> ";": [Terminal(Token::Type(Range { start, .. })), Terminal(Token::Identifier((_, name))), _, NonTerminal(Symbol::GenericsTypeArguments((_, types))), _, _, NonTerminal(Symbol::Type((_, ty))), Terminal(Token::Semicolon(Range { end, .. }))] => Ok(Symbol::TypeAliasDefinition((*start..*end, TypeAlias { name: name.clone(), generics_arguments: mem::take(types), aliased_type: mem::take(ty), public: false })));
+        <EnumVariants>::=<EnumVariant>: [NonTerminal(Symbol::EnumVariant((range, variant)))] => Ok(Symbol::EnumVariants((range.clone(), vec![mem::take(variant)])));
+            |<EnumVariants> "," <EnumVariant>: [NonTerminal(Symbol::EnumVariants((Range { start, .. }, list))), _, NonTerminal(Symbol::EnumVariant((Range { end, .. }, variant)))] => Ok(Symbol::EnumVariants((*start..*end, {
+                    list.push(mem::take(variant));
+                    mem::take(list)
+                })));
+        <EnumVariant>::=[Identifier]: [Terminal(Token::Identifier((position, name)))] => Ok(Symbol::EnumVariant((position.clone(), (name.clone(), EnumVariant::Tuple(Vec::new())))));
+            |[Identifier] "(" <Type> ")": [Terminal(Token::Identifier((Range { start, .. }, name))), _, NonTerminal(Symbol::Type((_, ty))), Terminal(Token::RoundBracketClose(Range { end, .. }))] => Ok(Symbol::EnumVariant((*start..*end, (name.clone(), EnumVariant::Tuple(vec![mem::take(ty)])))));
+            |[Identifier] "(" <CommaSeparatedTypes> ")": [Terminal(Token::Identifier((Range { start, .. }, name))), _, NonTerminal(Symbol::CommaSeparatedTypes((_, types))), Terminal(Token::RoundBracketClose(Range { end, .. }))] => Ok(Symbol::EnumVariant((*start..*end, (name.clone(), EnumVariant::Tuple(mem::take(types))))));
+            |[Identifier] "#" "{" "}": [Terminal(Token::Identifier((Range { start, .. }, name))), _, _, Terminal(Token::WaveBracketClose(Range { end, .. }))] => Ok(Symbol::EnumVariant((*start..*end, (name.clone(), EnumVariant::Struct(Vec::new())))));
+            |[Identifier] "#" "{" <StructDefinitionItems> "}": [Terminal(Token::Identifier((Range { start, .. }, name))), _, _, NonTerminal(Symbol::StructDefinitionItems((_, items))), Terminal(Token::WaveBracketClose(Range { end, .. }))] => Ok(Symbol::EnumVariant((*start..*end, (name.clone(), EnumVariant::Struct(mem::take(items))))));
         <ConstructStructItems>::=[Identifier] ":" <Expression>: [Terminal(Token::Identifier((Range { start, .. }, name))), _, NonTerminal(Symbol::Expression((Range { end, .. }, expression)))] => Ok(Symbol::ConstructStructItems((*start..*end, vec![(name.clone(), mem::take(expression))])));
             |[Identifier]: [Terminal(Token::Identifier((position, name)))] => Ok(Symbol::ConstructStructItems((position.clone(), vec![(name.clone(), Expression { position: position.clone(), expression: ExpressionItem::AccessVariable(Path { position: position.clone(), path: vec![name.clone()] }, Vec::new()) })])));
             |<ConstructStructItems> "," [Identifier] ":" <Expression>: [NonTerminal(Symbol::ConstructStructItems((Range { start, .. }, items))), _, Terminal(Token::Identifier((_, name))), _, NonTerminal(Symbol::Expression((Range { end, .. }, expression)))] => Ok(Symbol::ConstructStructItems((*start..*end, {
@@ -646,22 +864,26 @@ parser! {
                     }));
                     mem::take(items)
                 })));
-        <LetBinding>::="var" <Pattern> "=" <Expression>: [Terminal(Token::Var(Range { start, .. })), NonTerminal(Symbol::Pattern((_, pattern))), _, NonTerminal(Symbol::Expression((Range { end, .. }, expression)))] => Ok(Symbol::LetBinding((*start..*end, LetBinding { position: *start..*end, pattern: mem::take(pattern), type_hint: None, expression: Box::new(mem::take(expression)) })));
-            |"var" <Pattern> ":" <Type> "=" <Expression>: [Terminal(Token::Var(Range { start, .. })), NonTerminal(Symbol::Pattern((_, pattern))), _, NonTerminal(Symbol::Type((_, type_hint))), _, NonTerminal(Symbol::Expression((Range { end, .. }, expression)))] => Ok(Symbol::LetBinding((*start..*end, LetBinding { position: *start..*end, pattern: mem::take(pattern), type_hint: Some(mem::take(type_hint)), expression: Box::new(mem::take(expression)) })));
-        <UnExpressionStatement>::=<ImportStatement>: [NonTerminal(Symbol::ImportStatement((position, path)))] => Ok(Symbol::UnExpressionStatement((position.clone(), Statement { position: position.clone(), statement: StatementItem::Import(mem::take(path)) })));
+        <LetBinding>::="var" <Pattern> "=" <Expression>: [Terminal(Token::Var(Range { start, .. })), NonTerminal(Symbol::Pattern((_, pattern))), _, NonTerminal(Symbol::Expression((Range { end, .. }, expression)))] => Ok(Symbol::LetBinding((*start..*end, LetBinding { position: *start..*end, pattern: mem::take(pattern), type_hint: None, expression: Box::new(mem::take(expression)), public: false })));
+            |"var" <Pattern> ":" <Type> "=" <Expression>: [Terminal(Token::Var(Range { start, .. })), NonTerminal(Symbol::Pattern((_, pattern))), _, NonTerminal(Symbol::Type((_, type_hint))), _, NonTerminal(Symbol::Expression((Range { end, .. }, expression)))] => Ok(Symbol::LetBinding((*start..*end, LetBinding { position: *start..*end, pattern: mem::take(pattern), type_hint: Some(mem::take(type_hint)), expression: Box::new(mem::take(expression)), public: false })));
+        <UnExpressionStatement>::=<ImportStatement>: [NonTerminal(Symbol::ImportStatement((position, path)))] => Ok(Symbol::UnExpressionStatement((position.clone(), Statement { position: position.clone(), statement: StatementItem::Import { public: false, path: mem::take(path) } })));
             |<Expression> ";": [NonTerminal(Symbol::Expression((Range { start, .. }, expression))), Terminal(Token::Semicolon(Range { end, .. }))] => Ok(Symbol::UnExpressionStatement((*start..*end, Statement { position: *start..*end, statement: StatementItem::Expression(mem::take(expression)) })));
             |<LetBinding> ";": [NonTerminal(Symbol::LetBinding((Range { start, .. }, let_binding))), Terminal(Token::Semicolon(Range { end, .. }))] => Ok(Symbol::UnExpressionStatement((*start..*end, Statement { position: *start..*end, statement: StatementItem::LetBinding(mem::take(let_binding)) })));
             |"return" <Expression> ";": [Terminal(Token::Return(Range { start, .. })), NonTerminal(Symbol::Expression((_, expression))), Terminal(Token::Semicolon(Range { end, .. }))] => Ok(Symbol::UnExpressionStatement((*start..*end, Statement { position: *start..*end, statement: StatementItem::Return(Some(mem::take(expression))) })));
             |"return" ";": [Terminal(Token::Return(Range { start, .. })), Terminal(Token::Semicolon(Range { end, .. }))] => Ok(Symbol::UnExpressionStatement((*start..*end, Statement { position: *start..*end, statement: StatementItem::Return(None) })));
+            |"yield" <Expression> ";": [Terminal(Token::Yield(Range { start, .. })), NonTerminal(Symbol::Expression((_, expression))), Terminal(Token::Semicolon(Range { end, .. }))] => Ok(Symbol::UnExpressionStatement((*start..*end, Statement { position: *start..*end, statement: StatementItem::Yield(mem::take(expression)) })));
             |"continue" ";": [Terminal(Token::Continue(Range { start, .. })), Terminal(Token::Semicolon(Range { end, .. }))] => Ok(Symbol::UnExpressionStatement((*start..*end, Statement { position: *start..*end, statement: StatementItem::Continue(None) })));
-            |"continue" [Identifier] ";": [Terminal(Token::Continue(Range { start, .. })), Terminal(Token::Identifier((_, label))), Terminal(Token::Semicolon(Range { end, .. }))] => Ok(Symbol::UnExpressionStatement((*start..*end, Statement { position: *start..*end, statement: StatementItem::Continue(Some(label.clone())) })));
-            |"break" ";": [Terminal(Token::Break(Range { start, .. })), Terminal(Token::Semicolon(Range { end, .. }))] => Ok(Symbol::UnExpressionStatement((*start..*end, Statement { position: *start..*end, statement: StatementItem::Break(None) })));
-            |"break" [Identifier] ";": [Terminal(Token::Break(Range { start, .. })), Terminal(Token::Identifier((_, label))), Terminal(Token::Semicolon(Range { end, .. }))] => Ok(Symbol::UnExpressionStatement((*start..*end, Statement { position: *start..*end, statement: StatementItem::Break(Some(label.clone())) })));
+            |"continue" [Label] ";": [Terminal(Token::Continue(Range { start, .. })), Terminal(Token::Label((_, label))), Terminal(Token::Semicolon(Range { end, .. }))] => Ok(Symbol::UnExpressionStatement((*start..*end, Statement { position: *start..*end, statement: StatementItem::Continue(Some(label.clone())) })));
+            |"break" ";": [Terminal(Token::Break(Range { start, .. })), Terminal(Token::Semicolon(Range { end, .. }))] => Ok(Symbol::UnExpressionStatement((*start..*end, Statement { position: *start..*end, statement: StatementItem::Break(None, None) })));
+            |"break" [Label] ";": [Terminal(Token::Break(Range { start, .. })), Terminal(Token::Label((_, label))), Terminal(Token::Semicolon(Range { end, .. }))] => Ok(Symbol::UnExpressionStatement((*start..*end, Statement { position: *start..*end, statement: StatementItem::Break(Some(label.clone()), None) })));
+            |"break" <Expression> ";": [Terminal(Token::Break(Range { start, .. })), NonTerminal(Symbol::Expression((_, expression))), Terminal(Token::Semicolon(Range { end, .. }))] => Ok(Symbol::UnExpressionStatement((*start..*end, Statement { position: *start..*end, statement: StatementItem::Break(None, Some(mem::take(expression))) })));
+            |"break" [Label] <Expression> ";": [Terminal(Token::Break(Range { start, .. })), Terminal(Token::Label((_, label))), NonTerminal(Symbol::Expression((_, expression))), Terminal(Token::Semicolon(Range { end, .. }))] => Ok(Symbol::UnExpressionStatement((*start..*end, Statement { position: *start..*end, statement: StatementItem::Break(Some(label.clone()), Some(mem::take(expression))) })));
             |<ForStatement>: [NonTerminal(Symbol::ForStatement((position, for_statement)))] => Ok(Symbol::UnExpressionStatement((position.clone(), Statement { position: position.clone(), statement: StatementItem::For(mem::take(for_statement)) })));
             |<WhileStatement>: [NonTerminal(Symbol::WhileStatement((position, while_statement)))] => Ok(Symbol::UnExpressionStatement((position.clone(), Statement { position: position.clone(), statement: StatementItem::While(mem::take(while_statement)) })));
         <Statement>::=<UnExpressionStatement>: [NonTerminal(Symbol::UnExpressionStatement(item))] => Ok(Symbol::Statement(mem::take(item)));
             |<IfExpression>: [NonTerminal(Symbol::IfExpression((range, expression)))] => Ok(Symbol::Statement((range.clone(), Statement { position: range.clone(), statement: StatementItem::Expression(Expression{ position: range.clone(), expression: ExpressionItem::If(mem::take(expression)) }) })));
             |<MatchExpression>: [NonTerminal(Symbol::MatchExpression((range, expression)))] => Ok(Symbol::Statement((range.clone(), Statement { position: range.clone(), statement: StatementItem::Expression(Expression { position: range.clone(), expression: ExpressionItem::Match(mem::take(expression)) }) })));
+            |<LoopExpression>: [NonTerminal(Symbol::LoopExpression((range, expression)))] => Ok(Symbol::Statement((range.clone(), Statement { position: range.clone(), statement: StatementItem::Expression(Expression { position: range.clone(), expression: ExpressionItem::Loop(mem::take(expression)) }) })));
         <StatementList>::=<Statement>: [NonTerminal(Symbol::Statement((position, statement)))] => Ok(Symbol::StatementList((position.clone(), vec![mem::take(statement)])));
             |<StatementList> <Statement>: [NonTerminal(Symbol::StatementList((Range { start, .. }, list))), NonTerminal(Symbol::Statement((Range { end, .. }, statement)))] => Ok(Symbol::StatementList((*start..*end, {
                     list.push(mem::take(statement));
@@ -716,14 +938,25 @@ parser! {
             |"(" <Expression> "," ")": [Terminal(Token::RoundBracketOpen(Range { start, .. })), NonTerminal(Symbol::Expression((_, expression))), _, Terminal(Token::RoundBracketClose(Range { end, .. }))] => Ok(Symbol::CallFunctionArguments((*start..*end, vec![mem::take(expression)])));
             |"(" <CommaSeparatedExpressions> ")": [Terminal(Token::RoundBracketOpen(Range { start, .. })), NonTerminal(Symbol::CommaSeparatedExpressions((_, expressions))), Terminal(Token::RoundBracketClose(Range { end, .. }))] => Ok(Symbol::CallFunctionArguments((*start..*end, mem::take(expressions))));
             |"(" <CommaSeparatedExpressions> "," ")": [Terminal(Token::RoundBracketOpen(Range { start, .. })), NonTerminal(Symbol::CommaSeparatedExpressions((_, expressions))), _, Terminal(Token::RoundBracketClose(Range { end, .. }))] => Ok(Symbol::CallFunctionArguments((*start..*end, mem::take(expressions))));
+        <FormatStringItems>::=[FormatStringStart] <Expression>: [Terminal(Token::FormatStringStart((Range { start, .. }, text))), NonTerminal(Symbol::Expression((Range { end, .. }, expression)))] => Ok(Symbol::FormatStringItems((*start..*end, vec![FormatStringItem::Text(text.clone()), FormatStringItem::Expression(mem::take(expression))])));
+            |<FormatStringItems> [FormatStringMiddle] <Expression>: [NonTerminal(Symbol::FormatStringItems((Range { start, .. }, items))), Terminal(Token::FormatStringMiddle((_, text))), NonTerminal(Symbol::Expression((Range { end, .. }, expression)))] => Ok(Symbol::FormatStringItems((*start..*end, {
+                    items.push(FormatStringItem::Text(text.clone()));
+                    items.push(FormatStringItem::Expression(mem::take(expression)));
+                    mem::take(items)
+                })));
         <Expression8>::=<VariableReference>: [NonTerminal(Symbol::VariableReference((position, path, types)))] => Ok(Symbol::Expression8((position.clone(), Expression { position: position.clone(), expression: ExpressionItem::AccessVariable(mem::take(path), mem::take(types)) })));
             |[StringImmediate]: [Terminal(Token::StringImmediate((position, value)))] => Ok(Symbol::Expression8((position.clone(), Expression { position: position.clone(), expression: ExpressionItem::StringImmediate(value.clone()) })));
             |[NumberImmediate]: [Terminal(Token::NumberImmediate((position, value)))] => Ok(Symbol::Expression8((position.clone(), Expression { position: position.clone(), expression: ExpressionItem::NumberImmediate(*value) })));
+            |<FormatStringItems> [FormatStringEnd]: [NonTerminal(Symbol::FormatStringItems((Range { start, .. }, items))), Terminal(Token::FormatStringEnd((Range { end, .. }, text)))] => Ok(Symbol::Expression8((*start..*end, Expression { position: *start..*end, expression: ExpressionItem::FormatString({
+                    items.push(FormatStringItem::Text(text.clone()));
+                    mem::take(items)
+                }) })));
             |<VariableReference> <CallFunctionArguments>: [NonTerminal(Symbol::VariableReference((Range { start, .. }, path, types))), NonTerminal(Symbol::CallFunctionArguments((Range { end, .. }, expressions)))] => Ok(Symbol::Expression8((*start..*end, Expression { position: *start..*end, expression: ExpressionItem::CallFunction(FunctionReference::Static { path: mem::take(path), generics_type_arguments: mem::take(types)}, mem::take(expressions)) })));
             |<Expression8> "." <VariableReference> <CallFunctionArguments>: [NonTerminal(Symbol::Expression8((Range { start, .. }, expression))), _, NonTerminal(Symbol::VariableReference((_, path, types))), NonTerminal(Symbol::CallFunctionArguments((Range { end, .. }, expressions)))] => Ok(Symbol::Expression8((*start..*end, Expression { position: *start..*end, expression: ExpressionItem::CallFunction(FunctionReference::Static { path: mem::take(path), generics_type_arguments: mem::take(types)}, [mem::take(expression)].into_iter().chain(mem::take(expressions)).collect()) })));
             |<Expression8> "." <CallFunctionArguments>: [NonTerminal(Symbol::Expression8((Range { start, .. }, expression))), _, NonTerminal(Symbol::CallFunctionArguments((Range { end, .. }, expressions)))] => Ok(Symbol::Expression8((*start..*end, Expression { position: *start..*end, expression: ExpressionItem::CallFunction(FunctionReference::Dynamic(Box::new(mem::take(expression))), mem::take(expressions)) })));
             |<Expression8> "." [Identifier]: [NonTerminal(Symbol::Expression8((Range { start, .. }, expression))), _, Terminal(Token::Identifier((Range { end, .. }, member)))] => Ok(Symbol::Expression8((*start..*end, Expression { position: *start..*end, expression: ExpressionItem::AccessMember(Box::new(mem::take(expression)), member.clone()) })));
             |<Expression8> "." [TupleIndex]: [NonTerminal(Symbol::Expression8((Range { start, .. }, expression))), _, Terminal(Token::TupleIndex((Range { end, .. }, index)))] => Ok(Symbol::Expression8((*start..*end, Expression { position: *start..*end, expression: ExpressionItem::AccessTupleItem(Box::new(mem::take(expression)), *index) })));
+            |<Expression8> "?": [NonTerminal(Symbol::Expression8((Range { start, .. }, expression))), Terminal(Token::Question(Range { end, .. }))] => Ok(Symbol::Expression8((*start..*end, Expression { position: *start..*end, expression: ExpressionItem::Try(Box::new(mem::take(expression))) })));
             |<Expression8> "[" <Expression> "]": [NonTerminal(Symbol::Expression8((Range { start, .. }, expression))), _, NonTerminal(Symbol::Expression((_, index))), Terminal(Token::SquareBracketClose(Range { end, .. }))] => Ok(Symbol::Expression8((*start..*end, Expression { position: *start..*end, expression: ExpressionItem::Indexer(Box::new(mem::take(expression)), Box::new(mem::take(index))) })));
             |"(" <CommaSeparatedExpressions> ")": [Terminal(Token::RoundBracketOpen(Range { start, .. })), NonTerminal(Symbol::CommaSeparatedExpressions((_, expressions))), Terminal(Token::RoundBracketClose(Range { end, .. }))] => Ok(Symbol::Expression8((*start..*end, Expression { position: *start..*end, expression: ExpressionItem::ConstructTuple(mem::take(expressions)) })));
             |"(" <CommaSeparatedExpressions> "," ")": [Terminal(Token::RoundBracketOpen(Range { start, .. })), NonTerminal(Symbol::CommaSeparatedExpressions((_, expressions))), _, Terminal(Token::RoundBracketClose(Range { end, .. }))] => Ok(Symbol::Expression8((*start..*end, Expression { position: *start..*end, expression: ExpressionItem::ConstructTuple(mem::take(expressions)) })));
@@ -761,11 +994,23 @@ parser! {
             |<Expression1> "^" <Expression2>: [NonTerminal(Symbol::Expression1((Range { start, .. }, first))), _, NonTerminal(Symbol::Expression2((Range { end, .. }, second)))] => Ok(Symbol::Expression1((*start..*end, Expression { position: *start..*end, expression: ExpressionItem::Xor(Box::new(mem::take(first)), Box::new(mem::take(second))) })));
         <Expression0>::=<Expression1>: [NonTerminal(Symbol::Expression1(item))] => Ok(Symbol::Expression0(mem::take(item)));
             |<Expression0> "|" <Expression1>: [NonTerminal(Symbol::Expression0((Range { start, .. }, first))), _, NonTerminal(Symbol::Expression1((Range { end, .. }, second)))] => Ok(Symbol::Expression0((*start..*end, Expression { position: *start..*end, expression: ExpressionItem::Or(Box::new(mem::take(first)), Box::new(mem::take(second))) })));
-        <Expression>::=<Expression0>: [NonTerminal(Symbol::Expression0(item))] => Ok(Symbol::Expression(mem::take(item)));
+        <LogicalAndExpression>::=<Expression0>: [NonTerminal(Symbol::Expression0(item))] => Ok(Symbol::LogicalAndExpression(mem::take(item)));
+            |<LogicalAndExpression> "&&" <Expression0>: [NonTerminal(Symbol::LogicalAndExpression((Range { start, .. }, first))), _, NonTerminal(Symbol::Expression0((Range { end, .. }, second)))] => Ok(Symbol::LogicalAndExpression((*start..*end, Expression { position: *start..*end, expression: ExpressionItem::LogicalAnd(Box::new(mem::take(first)), Box::new(mem::take(second))) })));
+        <LogicalOrExpression>::=<LogicalAndExpression>: [NonTerminal(Symbol::LogicalAndExpression(item))] => Ok(Symbol::LogicalOrExpression(mem::take(item)));
+            |<LogicalOrExpression> "||" <LogicalAndExpression>: [NonTerminal(Symbol::LogicalOrExpression((Range { start, .. }, first))), _, NonTerminal(Symbol::LogicalAndExpression((Range { end, .. }, second)))] => Ok(Symbol::LogicalOrExpression((*start..*end, Expression { position: *start..*end, expression: ExpressionItem::LogicalOr(Box::new(mem::take(first)), Box::new(mem::take(second))) })));
+        <Expression>::=<LogicalOrExpression>: [NonTerminal(Symbol::LogicalOrExpression(item))] => Ok(Symbol::Expression(mem::take(item)));
+            |<LogicalOrExpression> ".." <LogicalOrExpression>: [NonTerminal(Symbol::LogicalOrExpression((Range { start, .. }, first))), _, NonTerminal(Symbol::LogicalOrExpression((Range { end, .. }, second)))] => Ok(Symbol::Expression((*start..*end, Expression { position: *start..*end, expression: ExpressionItem::Range(Box::new(mem::take(first)), Box::new(mem::take(second))) })));
+            |<LogicalOrExpression> "..=" <LogicalOrExpression>: [NonTerminal(Symbol::LogicalOrExpression((Range { start, .. }, first))), _, NonTerminal(Symbol::LogicalOrExpression((Range { end, .. }, second)))] => Ok(Symbol::Expression((*start..*end, Expression { position: *start..*end, expression: ExpressionItem::RangeInclusive(Box::new(mem::take(first)), Box::new(mem::take(second))) })));
             |<ClosureExpression>: [NonTerminal(Symbol::ClosureExpression((position, closure)))] => Ok(Symbol::Expression((position.clone(), Expression { position: position.clone(), expression: ExpressionItem::Closure(mem::take(closure)) })));
             |<IfExpression>: [NonTerminal(Symbol::IfExpression((position, expression)))] => Ok(Symbol::Expression((position.clone(), Expression { position: position.clone(), expression: ExpressionItem::If(mem::take(expression)) })));
             |<MatchExpression>: [NonTerminal(Symbol::MatchExpression((position, expression)))] => Ok(Symbol::Expression((position.clone(), Expression { position: position.clone(), expression: ExpressionItem::Match(mem::take(expression)) })));
+            |<LoopExpression>: [NonTerminal(Symbol::LoopExpression((position, expression)))] => Ok(Symbol::Expression((position.clone(), Expression { position: position.clone(), expression: ExpressionItem::Loop(mem::take(expression)) })));
             |<Expression8> "=" <Expression>: [NonTerminal(Symbol::Expression8((Range { start, .. }, variable))), _, NonTerminal(Symbol::Expression((Range { end, .. }, expression)))] => Ok(Symbol::Expression((*start..*end, Expression { position: *start..*end, expression: ExpressionItem::Assign(Box::new(mem::take(variable)), Box::new(mem::take(expression))) })));
+            |<Expression8> "+=" <Expression>: [NonTerminal(Symbol::Expression8((Range { start, .. }, variable))), _, NonTerminal(Symbol::Expression((Range { end, .. }, expression)))] => Ok(Symbol::Expression((*start..*end, Expression { position: *start..*end, expression: ExpressionItem::AddAssign(Box::new(mem::take(variable)), Box::new(mem::take(expression))) })));
+            |<Expression8> "-=" <Expression>: [NonTerminal(Symbol::Expression8((Range { start, .. }, variable))), _, NonTerminal(Symbol::Expression((Range { end, .. }, expression)))] => Ok(Symbol::Expression((*start..*end, Expression { position: *start..*end, expression: ExpressionItem::SubAssign(Box::new(mem::take(variable)), Box::new(mem::take(expression))) })));
+            |<Expression8> "*=" <Expression>: [NonTerminal(Symbol::Expression8((Range { start, .. }, variable))), _, NonTerminal(Symbol::Expression((Range { end, .. }, expression)))] => Ok(Symbol::Expression((*start..*end, Expression { position: *start..*end, expression: ExpressionItem::MulAssign(Box::new(mem::take(variable)), Box::new(mem::take(expression))) })));
+            |<Expression8> "/=" <Expression>: [NonTerminal(Symbol::Expression8((Range { start, .. }, variable))), _, NonTerminal(Symbol::Expression((Range { end, .. }, expression)))] => Ok(Symbol::Expression((*start..*end, Expression { position: *start..*end, expression: ExpressionItem::DivAssign(Box::new(mem::take(variable)), Box::new(mem::take(expression))) })));
+            |<Expression8> "%=" <Expression>: [NonTerminal(Symbol::Expression8((Range { start, .. }, variable))), _, NonTerminal(Symbol::Expression((Range { end, .. }, expression)))] => Ok(Symbol::Expression((*start..*end, Expression { position: *start..*end, expression: ExpressionItem::RemAssign(Box::new(mem::take(variable)), Box::new(mem::take(expression))) })));
         <ClosureExpression>::="fn" ":" <ClosureArguments> "|->" <Expression>: [Terminal(Token::Fn(Range { start, .. })), _, NonTerminal(Symbol::ClosureArguments((_, arguments))), _, NonTerminal(Symbol::Expression((Range { end, .. }, expression)))] => Ok(Symbol::ClosureExpression((*start..*end, Closure { position: *start..*end, arguments: mem::take(arguments), expression: Box::new(mem::take(expression)) })));
             |"fn" ":" "|->" <Expression>: [Terminal(Token::Fn(Range { start, .. })), _, _, NonTerminal(Symbol::Expression((Range { end, .. }, expression)))] => Ok(Symbol::ClosureExpression((*start..*end, Closure { position: *start..*end, arguments: Vec::new(), expression: Box::new(mem::take(expression)) })));
         <IfExpression>::="if" <Expression> <ExpressionBlock>: [Terminal(Token::If(Range { start, .. })), NonTerminal(Symbol::Expression((_, condition))), NonTerminal(Symbol::ExpressionBlock((Range { end, .. }, block)))] => Ok(Symbol::IfExpression((*start..*end, If { position: *start..*end, condition: Box::new(Condition::BoolExpression(mem::take(condition))), then: Box::new(Expression { position: block.position.clone(), expression: ExpressionItem::Block(mem::take(block)) }), other: None })));
@@ -781,6 +1026,8 @@ parser! {
                     patterns.push((mem::take(pattern), mem::take(expression)));
                     mem::take(patterns)
                 })));
+        <LoopExpression>::="loop" <StatementBlock>: [Terminal(Token::Loop(Range { start, .. })), NonTerminal(Symbol::StatementBlock((Range { end, .. }, block)))] => Ok(Symbol::LoopExpression((*start..*end, Loop { label: None, position: *start..*end, block: mem::take(block) })));
+            |[Identifier] ":" "loop" <StatementBlock>: [Terminal(Token::Identifier((Range { start, .. }, label))), _, _, NonTerminal(Symbol::StatementBlock((Range { end, .. }, block)))] => Ok(Symbol::LoopExpression((*start..*end, Loop { label: Some(label.clone()), position: *start..*end, block: mem::take(block) })));
         <ForStatement>::="for" <Pattern> "in" <Expression> <StatementBlock>: [Terminal(Token::For(Range { start, .. })), NonTerminal(Symbol::Pattern((_, pattern))), _, NonTerminal(Symbol::Expression((_, iter))), NonTerminal(Symbol::StatementBlock((Range { end, .. }, block)))] => Ok(Symbol::ForStatement((*start..*end, ForStatement { label: None, position: *start..*end, pattern: mem::take(pattern), iter: mem::take(iter), block: mem::take(block) })));
             |[Identifier] ":" "for" <Pattern> "in" <Expression> <StatementBlock>: [Terminal(Token::Identifier((Range { start, .. }, label))), _, _, NonTerminal(Symbol::Pattern((_, pattern))), _, NonTerminal(Symbol::Expression((_, iter))), NonTerminal(Symbol::StatementBlock((Range { end, .. }, block)))] => Ok(Symbol::ForStatement((*start..*end, ForStatement { label: Some(label.clone()), position: *start..*end, pattern: mem::take(pattern), iter: mem::take(iter), block: mem::take(block) })));
         <WhileStatement>::="while" <Expression> <StatementBlock>: [Terminal(Token::While(Range { start, .. })), NonTerminal(Symbol::Expression((_, condition))), NonTerminal(Symbol::StatementBlock((Range { end, .. }, block)))] => Ok(Symbol::WhileStatement((*start..*end, WhileStatement { label: None, position: *start..*end, condition: Condition::BoolExpression(mem::take(condition)), block: mem::take(block) })));
@@ -791,6 +1038,17 @@ parser! {
             |"(" <Pattern> ")": [Terminal(Token::RoundBracketOpen(Range { start, .. })), NonTerminal(Symbol::Pattern((_, inner_pattern))), Terminal(Token::RoundBracketClose(Range { end, .. }))] => Ok(Symbol::Pattern((*start..*end, mem::take(inner_pattern))));
             |"(" <CommaSeparatedPatterns> ")": [Terminal(Token::RoundBracketOpen(Range { start, .. })), NonTerminal(Symbol::CommaSeparatedPatterns((_, patterns))), Terminal(Token::RoundBracketClose(Range { end, .. }))] => Ok(Symbol::Pattern((*start..*end, Pattern::Tuple(mem::take(patterns)))));
             |"(" <CommaSeparatedPatterns> "," ")": [Terminal(Token::RoundBracketOpen(Range { start, .. })), NonTerminal(Symbol::CommaSeparatedPatterns((_, patterns))), _, Terminal(Token::RoundBracketClose(Range { end, .. }))] => Ok(Symbol::Pattern((*start..*end, Pattern::Tuple(mem::take(patterns)))));
+            |<VariableReference> "(" ")": [NonTerminal(Symbol::VariableReference((Range { start, .. }, Path { path, .. }, generics_arguments))), _, Terminal(Token::RoundBracketClose(Range { end, .. }))] => Ok(Symbol::Pattern((*start..*end, Pattern::Struct(StructName::Named(NamedType { position: *start..*end, path: mem::take(path), generics_arguments: mem::take(generics_arguments) }), Vec::new()))));
+            |<FullPath> "::" [Identifier]: [NonTerminal(Symbol::FullPath((Range { start, .. }, Path { path, .. }))), _, Terminal(Token::Identifier((Range { end, .. }, last)))] => Ok(Symbol::Pattern((*start..*end, Pattern::Struct(StructName::Named(NamedType {
+                    position: *start..*end,
+                    path: {
+                        path.push(last.clone());
+                        mem::take(path)
+                    },
+                    generics_arguments: Vec::new(),
+                }), Vec::new()))));
+            |<VariableReference> "(" <Pattern> ")": [NonTerminal(Symbol::VariableReference((Range { start, .. }, Path { path, .. }, generics_arguments))), _, NonTerminal(Symbol::Pattern((_, pattern))), Terminal(Token::RoundBracketClose(Range { end, .. }))] => Ok(Symbol::Pattern((*start..*end, Pattern::Struct(StructName::Named(NamedType { position: *start..*end, path: mem::take(path), generics_arguments: mem::take(generics_arguments) }), vec![("0".to_string(), mem::take(pattern))]))));
+            |<VariableReference> "(" <CommaSeparatedPatterns> ")": [NonTerminal(Symbol::VariableReference((Range { start, .. }, Path { path, .. }, generics_arguments))), _, NonTerminal(Symbol::CommaSeparatedPatterns((_, patterns))), Terminal(Token::RoundBracketClose(Range { end, .. }))] => Ok(Symbol::Pattern((*start..*end, Pattern::Struct(StructName::Named(NamedType { position: *start..*end, path: mem::take(path), generics_arguments: mem::take(generics_arguments) }), patterns.drain(..).enumerate().map(|(i, pattern)| (i.to_string(), pattern)).collect()))));
             |<StructName> "{" "}": [NonTerminal(Symbol::StructName((Range { start, .. }, name))), _, Terminal(Token::WaveBracketClose(Range { end, .. }))] => Ok(Symbol::Pattern((*start..*end, Pattern::Struct(mem::take(name), Vec::new()))));
             |<StructName> "{" <PatternStructItems> "}": [NonTerminal(Symbol::StructName((Range { start, .. }, name))), _, NonTerminal(Symbol::PatternStructItems((_, items))), Terminal(Token::WaveBracketClose(Range { end, .. }))] => Ok(Symbol::Pattern((*start..*end, Pattern::Struct(mem::take(name), mem::take(items)))));
             |<StructName> "{" <PatternStructItems> "," "}": [NonTerminal(Symbol::StructName((Range { start, .. }, name))), _, NonTerminal(Symbol::PatternStructItems((_, items))), _, Terminal(Token::WaveBracketClose(Range { end, .. }))] => Ok(Symbol::Pattern((*start..*end, Pattern::Struct(mem::take(name), mem::take(items)))));
@@ -824,30 +1082,32 @@ parser! {
                     list.push(mem::take(expression));
                     mem::take(list)
                 })));
-        <FunctionDefinition>::="fn" [Identifier] "(" <FunctionArguments> ")" "->" <Type> <ExpressionBlock>: [Terminal(Token::Fn(Range { start, .. })), Terminal(Token::Identifier((_, name))), _, NonTerminal(Symbol::FunctionArguments((_, arguments))), _, _, NonTerminal(Symbol::Type((_, t))), NonTerminal(Symbol::ExpressionBlock((Range { end, .. }, expression)))] => Ok(Symbol::FunctionDefinition((*start..*end, Function { position: *start..*end, name: name.clone(), generics_arguments: Vec::new(), arguments: mem::take(arguments), return_type: mem::take(t), expression: Expression { position: expression.position.clone(), expression: ExpressionItem::Block(mem::take(expression)) } })));
-            |"fn" [Identifier] "(" <FunctionArguments> ")" <ExpressionBlock>: [Terminal(Token::Fn(Range { start, .. })), Terminal(Token::Identifier((_, name))), _, NonTerminal(Symbol::FunctionArguments((_, arguments))), _, NonTerminal(Symbol::ExpressionBlock((Range { end, .. }, expression)))] => Ok(Symbol::FunctionDefinition((*start..*end, Function { position: *start..*end, name: name.clone(), generics_arguments: Vec::new(), arguments: mem::take(arguments), return_type: Type::None, expression: Expression { position: expression.position.clone(), expression: ExpressionItem::Block(mem::take(expression)) } })));
-            |"fn" [Identifier] "::" "<" <GenericsTypeArguments> ">" "(" <FunctionArguments> ")" "->" <Type> <ExpressionBlock>: [Terminal(Token::Fn(Range { start, .. })), Terminal(Token::Identifier((_, name))), _, _, NonTerminal(Symbol::GenericsTypeArguments((_, generics_arguments))), _, _, NonTerminal(Symbol::FunctionArguments((_, arguments))), _, _, NonTerminal(Symbol::Type((_, t))), NonTerminal(Symbol::ExpressionBlock((Range { end, .. }, expression)))] => Ok(Symbol::FunctionDefinition((*start..*end, Function { position: *start..*end, name: name.clone(), generics_arguments: mem::take(generics_arguments), arguments: mem::take(arguments), return_type: mem::take(t), expression: Expression { position: expression.position.clone(), expression: ExpressionItem::Block(mem::take(expression)) } })));
-            |"fn" [Identifier] "::" "<" <GenericsTypeArguments> ">" "(" <FunctionArguments> ")" <ExpressionBlock>: [Terminal(Token::Fn(Range { start, .. })), Terminal(Token::Identifier((_, name))), _, _, NonTerminal(Symbol::GenericsTypeArguments((_, generics_arguments))), _, _, NonTerminal(Symbol::FunctionArguments((_, arguments))), _, NonTerminal(Symbol::ExpressionBlock((Range { end, .. }, expression)))] => Ok(Symbol::FunctionDefinition((*start..*end, Function { position: *start..*end, name: name.clone(), generics_arguments: mem::take(generics_arguments), arguments: mem::take(arguments), return_type: Type::None, expression: Expression { position: expression.position.clone(), expression: ExpressionItem::Block(mem::take(expression)) } })));
-            |"fn" [Identifier] "(" <FunctionArguments> "," ")" "->" <Type> <ExpressionBlock>: [Terminal(Token::Fn(Range { start, .. })), Terminal(Token::Identifier((_, name))), _, NonTerminal(Symbol::FunctionArguments((_, arguments))), _, _, _, NonTerminal(Symbol::Type((_, t))), NonTerminal(Symbol::ExpressionBlock((Range { end, .. }, expression)))] => Ok(Symbol::FunctionDefinition((*start..*end, Function { position: *start..*end, name: name.clone(), generics_arguments: Vec::new(), arguments: mem::take(arguments), return_type: mem::take(t), expression: Expression { position: expression.position.clone(), expression: ExpressionItem::Block(mem::take(expression)) } })));
-            |"fn" [Identifier] "(" <FunctionArguments> "," ")" <ExpressionBlock>: [Terminal(Token::Fn(Range { start, .. })), Terminal(Token::Identifier((_, name))), _, NonTerminal(Symbol::FunctionArguments((_, arguments))), _, _, NonTerminal(Symbol::ExpressionBlock((Range { end, .. }, expression)))] => Ok(Symbol::FunctionDefinition((*start..*end, Function { position: *start..*end, name: name.clone(), generics_arguments: Vec::new(), arguments: mem::take(arguments), return_type: Type::None, expression: Expression { position: expression.position.clone(), expression: ExpressionItem::Block(mem::take(expression)) } })));
-            |"fn" [Identifier] "::" "<" <GenericsTypeArguments> ">" "(" <FunctionArguments> "," ")" "->" <Type> <ExpressionBlock>: [Terminal(Token::Fn(Range { start, .. })), Terminal(Token::Identifier((_, name))), _, _, NonTerminal(Symbol::GenericsTypeArguments((_, generics_arguments))), _, _, NonTerminal(Symbol::FunctionArguments((_, arguments))), _, _, _, NonTerminal(Symbol::Type((_, t))), NonTerminal(Symbol::ExpressionBlock((Range { end, .. }, expression)))] => Ok(Symbol::FunctionDefinition((*start..*end, Function { position: *start..*end, name: name.clone(), generics_arguments: mem::take(generics_arguments), arguments: mem::take(arguments), return_type: mem::take(t), expression: Expression { position: expression.position.clone(), expression: ExpressionItem::Block(mem::take(expression)) } })));
-            |"fn" [Identifier] "::" "<" <GenericsTypeArguments> ">" "(" <FunctionArguments> "," ")" <ExpressionBlock>: [Terminal(Token::Fn(Range { start, .. })), Terminal(Token::Identifier((_, name))), _, _, NonTerminal(Symbol::GenericsTypeArguments((_, generics_arguments))), _, _, NonTerminal(Symbol::FunctionArguments((_, arguments))), _, _, NonTerminal(Symbol::ExpressionBlock((Range { end, .. }, expression)))] => Ok(Symbol::FunctionDefinition((*start..*end, Function { position: *start..*end, name: name.clone(), generics_arguments: mem::take(generics_arguments), arguments: mem::take(arguments), return_type: Type::None, expression: Expression { position: expression.position.clone(), expression: ExpressionItem::Block(mem::take(expression)) } })));
-            |"fn" [Identifier] "(" ")" "->" <Type> <ExpressionBlock>: [Terminal(Token::Fn(Range { start, .. })), Terminal(Token::Identifier((_, name))), _, _, _, NonTerminal(Symbol::Type((_, t))), NonTerminal(Symbol::ExpressionBlock((Range { end, .. }, expression)))] => Ok(Symbol::FunctionDefinition((*start..*end, Function { position: *start..*end, name: name.clone(), generics_arguments: Vec::new(), arguments: Vec::new(), return_type: mem::take(t), expression: Expression { position: expression.position.clone(), expression: ExpressionItem::Block(mem::take(expression)) } })));
-            |"fn" [Identifier] "(" ")" <ExpressionBlock>: [Terminal(Token::Fn(Range { start, .. })), Terminal(Token::Identifier((_, name))), _, _, NonTerminal(Symbol::ExpressionBlock((Range { end, .. }, expression)))] => Ok(Symbol::FunctionDefinition((*start..*end, Function { position: *start..*end, name: name.clone(), generics_arguments: Vec::new(), arguments: Vec::new(), return_type: Type::None, expression: Expression { position: expression.position.clone(), expression: ExpressionItem::Block(mem::take(expression)) } })));
-            |"fn" [Identifier] "::" "<" <GenericsTypeArguments> ">" "(" ")" "->" <Type> <ExpressionBlock>: [Terminal(Token::Fn(Range { start, .. })), Terminal(Token::Identifier((_, name))), _, _, NonTerminal(Symbol::GenericsTypeArguments((_, generics_arguments))), _, _, _, _, NonTerminal(Symbol::Type((_, t))), NonTerminal(Symbol::ExpressionBlock((Range { end, .. }, expression)))] => Ok(Symbol::FunctionDefinition((*start..*end, Function { position: *start..*end, name: name.clone(), generics_arguments: mem::take(generics_arguments), arguments: Vec::new(), return_type: mem::take(t), expression: Expression { position: expression.position.clone(), expression: ExpressionItem::Block(mem::take(expression)) } })));
-            |"fn" [Identifier] "::" "<" <GenericsTypeArguments> ">" "(" ")" <ExpressionBlock>: [Terminal(Token::Fn(Range { start, .. })), Terminal(Token::Identifier((_, name))), _, _, NonTerminal(Symbol::GenericsTypeArguments((_, generics_arguments))), _, _, _, NonTerminal(Symbol::ExpressionBlock((Range { end, .. }, expression)))] => Ok(Symbol::FunctionDefinition((*start..*end, Function { position: *start..*end, name: name.clone(), generics_arguments: mem::take(generics_arguments), arguments: Vec::new(), return_type: Type::None, expression: Expression { position: expression.position.clone(), expression: ExpressionItem::Block(mem::take(expression)) } })));
-            |"fn" [Identifier] "(" <FunctionArguments> ")" "->" <Type> "|->" <Expression> ";": [Terminal(Token::Fn(Range { start, .. })), Terminal(Token::Identifier((_, name))), _, NonTerminal(Symbol::FunctionArguments((_, arguments))), _, _, NonTerminal(Symbol::Type((_, t))), _, NonTerminal(Symbol::Expression((_, expression))), Terminal(Token::Semicolon(Range { end, .. }))] => Ok(Symbol::FunctionDefinition((*start..*end, Function { position: *start..*end, name: name.clone(), generics_arguments: Vec::new(), arguments: mem::take(arguments), return_type: mem::take(t), expression: mem::take(expression) })));
-            |"fn" [Identifier] "(" <FunctionArguments> ")" "|->" <Expression> ";": [Terminal(Token::Fn(Range { start, .. })), Terminal(Token::Identifier((_, name))), _, NonTerminal(Symbol::FunctionArguments((_, arguments))), _, _, NonTerminal(Symbol::Expression((_, expression))), Terminal(Token::Semicolon(Range { end, .. }))] => Ok(Symbol::FunctionDefinition((*start..*end, Function { position: *start..*end, name: name.clone(), generics_arguments: Vec::new(), arguments: mem::take(arguments), return_type: Type::None, expression: mem::take(expression) })));
-            |"fn" [Identifier] "::" "<" <GenericsTypeArguments> ">" "(" <FunctionArguments> ")" "->" <Type> "|->" <Expression> ";": [Terminal(Token::Fn(Range { start, .. })), Terminal(Token::Identifier((_, name))), _, _, NonTerminal(Symbol::GenericsTypeArguments((_, generics_arguments))), _, _, NonTerminal(Symbol::FunctionArguments((_, arguments))), _, _, NonTerminal(Symbol::Type((_, t))), _, NonTerminal(Symbol::Expression((_, expression))), Terminal(Token::Semicolon(Range { end, .. }))] => Ok(Symbol::FunctionDefinition((*start..*end, Function { position: *start..*end, name: name.clone(), generics_arguments: mem::take(generics_arguments), arguments: mem::take(arguments), return_type: mem::take(t), expression: mem::take(expression) })));
-            |"fn" [Identifier] "::" "<" <GenericsTypeArguments> ">" "(" <FunctionArguments> ")" "|->" <Expression> ";": [Terminal(Token::Fn(Range { start, .. })), Terminal(Token::Identifier((_, name))), _, _, NonTerminal(Symbol::GenericsTypeArguments((_, generics_arguments))), _, _, NonTerminal(Symbol::FunctionArguments((_, arguments))), _, _, NonTerminal(Symbol::Expression((_, expression))), Terminal(Token::Semicolon(Range { end, .. }))] => Ok(Symbol::FunctionDefinition((*start..*end, Function { position: *start..*end, name: name.clone(), generics_arguments: mem::take(generics_arguments), arguments: mem::take(arguments), return_type: Type::None, expression: mem::take(expression) })));
-            |"fn" [Identifier] "(" <FunctionArguments> "," ")" "->" <Type> "|->" <Expression> ";": [Terminal(Token::Fn(Range { start, .. })), Terminal(Token::Identifier((_, name))), _, NonTerminal(Symbol::FunctionArguments((_, arguments))), Terminal(Token::Comma(_)), _, _, NonTerminal(Symbol::Type((_, t))), _, NonTerminal(Symbol::Expression((_, expression))), Terminal(Token::Semicolon(Range { end, .. }))] => Ok(Symbol::FunctionDefinition((*start..*end, Function { position: *start..*end, name: name.clone(), generics_arguments: Vec::new(), arguments: mem::take(arguments), return_type: mem::take(t), expression: mem::take(expression) })));
-            |"fn" [Identifier] "(" <FunctionArguments> "," ")" "|->" <Expression> ";": [Terminal(Token::Fn(Range { start, .. })), Terminal(Token::Identifier((_, name))), _, NonTerminal(Symbol::FunctionArguments((_, arguments))), Terminal(Token::Comma(_)), _, _, NonTerminal(Symbol::Expression((_, expression))), Terminal(Token::Semicolon(Range { end, .. }))] => Ok(Symbol::FunctionDefinition((*start..*end, Function { position: *start..*end, name: name.clone(), generics_arguments: Vec::new(), arguments: mem::take(arguments), return_type: Type::None, expression: mem::take(expression) })));
-            |"fn" [Identifier] "::" "<" <GenericsTypeArguments> ">" "(" <FunctionArguments> "," ")" "->" <Type> "|->" <Expression> ";": [Terminal(Token::Fn(Range { start, .. })), Terminal(Token::Identifier((_, name))), _, _, NonTerminal(Symbol::GenericsTypeArguments((_, generics_arguments))), _, _, NonTerminal(Symbol::FunctionArguments((_, arguments))), Terminal(Token::Comma(_)), _, _, NonTerminal(Symbol::Type((_, t))), _, NonTerminal(Symbol::Expression((_, expression))), Terminal(Token::Semicolon(Range { end, .. }))] => Ok(Symbol::FunctionDefinition((*start..*end, Function { position: *start..*end, name: name.clone(), generics_arguments: mem::take(generics_arguments), arguments: mem::take(arguments), return_type: mem::take(t), expression: mem::take(expression) })));
-            |"fn" [Identifier] "::" "<" <GenericsTypeArguments> ">" "(" <FunctionArguments> "," ")" "|->" <Expression> ";": [Terminal(Token::Fn(Range { start, .. })), Terminal(Token::Identifier((_, name))), _, _, NonTerminal(Symbol::GenericsTypeArguments((_, generics_arguments))), _, _, NonTerminal(Symbol::FunctionArguments((_, arguments))), Terminal(Token::Comma(_)), _, _, NonTerminal(Symbol::Expression((_, expression))), Terminal(Token::Semicolon(Range { end, .. }))] => Ok(Symbol::FunctionDefinition((*start..*end, Function { position: *start..*end, name: name.clone(), generics_arguments: mem::take(generics_arguments), arguments: mem::take(arguments), return_type: Type::None, expression: mem::take(expression) })));
-            |"fn" [Identifier] "(" ")" "->" <Type> "|->" <Expression> ";": [Terminal(Token::Fn(Range { start, .. })), Terminal(Token::Identifier((_, name))), _, _, _, NonTerminal(Symbol::Type((_, t))), _, NonTerminal(Symbol::Expression((_, expression))), Terminal(Token::Semicolon(Range { end, .. }))] => Ok(Symbol::FunctionDefinition((*start..*end, Function { position: *start..*end, name: name.clone(), generics_arguments: Vec::new(), arguments: Vec::new(), return_type: mem::take(t), expression: mem::take(expression) })));
-            |"fn" [Identifier] "(" ")" "|->" <Expression> ";": [Terminal(Token::Fn(Range { start, .. })), Terminal(Token::Identifier((_, name))), _, _, _, NonTerminal(Symbol::Expression((_, expression))), Terminal(Token::Semicolon(Range { end, .. }))] => Ok(Symbol::FunctionDefinition((*start..*end, Function { position: *start..*end, name: name.clone(), generics_arguments: Vec::new(), arguments: Vec::new(), return_type: Type::None, expression: mem::take(expression) })));
-            |"fn" [Identifier] "::" "<" <GenericsTypeArguments> ">" "(" ")" "->" <Type> "|->" <Expression> ";": [Terminal(Token::Fn(Range { start, .. })), Terminal(Token::Identifier((_, name))), _, _, NonTerminal(Symbol::GenericsTypeArguments((_, generics_arguments))), _, _, _, _, NonTerminal(Symbol::Type((_, t))), _, NonTerminal(Symbol::Expression((_, expression))), Terminal(Token::Semicolon(Range { end, .. }))] => Ok(Symbol::FunctionDefinition((*start..*end, Function { position: *start..*end, name: name.clone(), generics_arguments: mem::take(generics_arguments), arguments: Vec::new(), return_type: mem::take(t), expression: mem::take(expression) })));
-            |"fn" [Identifier] "::" "<" <GenericsTypeArguments> ">" "(" ")" "|->" <Expression> ";": [Terminal(Token::Fn(Range { start, .. })), Terminal(Token::Identifier((_, name))), _, _, NonTerminal(Symbol::GenericsTypeArguments((_, generics_arguments))), _, _, _, _, NonTerminal(Symbol::Expression((_, expression))), Terminal(Token::Semicolon(Range { end, .. }))] => Ok(Symbol::FunctionDefinition((*start..*end, Function { position: *start..*end, name: name.clone(), generics_arguments: mem::take(generics_arguments), arguments: Vec::new(), return_type: Type::None, expression: mem::take(expression) })));
+        <FunctionKeyword>::="fn": [Terminal(Token::Fn(range))] => Ok(Symbol::FunctionKeyword((range.clone(), false)));
+            |"fn*": [Terminal(Token::GeneratorFn(range))] => Ok(Symbol::FunctionKeyword((range.clone(), true)));
+        <FunctionDefinition>::=<FunctionKeyword> [Identifier] "(" <FunctionArguments> ")" "->" <Type> <ExpressionBlock>: [NonTerminal(Symbol::FunctionKeyword((Range { start, .. }, generator))), Terminal(Token::Identifier((_, name))), _, NonTerminal(Symbol::FunctionArguments((_, arguments))), _, _, NonTerminal(Symbol::Type((_, t))), NonTerminal(Symbol::ExpressionBlock((Range { end, .. }, expression)))] => Ok(Symbol::FunctionDefinition((*start..*end, Function { position: *start..*end, name: name.clone(), generics_arguments: Vec::new(), arguments: mem::take(arguments), return_type: mem::take(t), expression: Expression { position: expression.position.clone(), expression: ExpressionItem::Block(mem::take(expression)) }, generator: *generator, public: false })));
+            |<FunctionKeyword> [Identifier] "(" <FunctionArguments> ")" <ExpressionBlock>: [NonTerminal(Symbol::FunctionKeyword((Range { start, .. }, generator))), Terminal(Token::Identifier((_, name))), _, NonTerminal(Symbol::FunctionArguments((_, arguments))), _, NonTerminal(Symbol::ExpressionBlock((Range { end, .. }, expression)))] => Ok(Symbol::FunctionDefinition((*start..*end, Function { position: *start..*end, name: name.clone(), generics_arguments: Vec::new(), arguments: mem::take(arguments), return_type: Type::None, expression: Expression { position: expression.position.clone(), expression: ExpressionItem::Block(mem::take(expression)) }, generator: *generator, public: false })));
+            |<FunctionKeyword> [Identifier] <GenericsTypeParameters> "(" <FunctionArguments> ")" "->" <Type> <ExpressionBlock>: [NonTerminal(Symbol::FunctionKeyword((Range { start, .. }, generator))), Terminal(Token::Identifier((_, name))), NonTerminal(Symbol::GenericsTypeParameters((_, generics_arguments))), _, NonTerminal(Symbol::FunctionArguments((_, arguments))), _, _, NonTerminal(Symbol::Type((_, t))), NonTerminal(Symbol::ExpressionBlock((Range { end, .. }, expression)))] => Ok(Symbol::FunctionDefinition((*start..*end, Function { position: *start..*end, name: name.clone(), generics_arguments: mem::take(generics_arguments), arguments: mem::take(arguments), return_type: mem::take(t), expression: Expression { position: expression.position.clone(), expression: ExpressionItem::Block(mem::take(expression)) }, generator: *generator, public: false })));
+            |<FunctionKeyword> [Identifier] <GenericsTypeParameters> "(" <FunctionArguments> ")" <ExpressionBlock>: [NonTerminal(Symbol::FunctionKeyword((Range { start, .. }, generator))), Terminal(Token::Identifier((_, name))), NonTerminal(Symbol::GenericsTypeParameters((_, generics_arguments))), _, NonTerminal(Symbol::FunctionArguments((_, arguments))), _, NonTerminal(Symbol::ExpressionBlock((Range { end, .. }, expression)))] => Ok(Symbol::FunctionDefinition((*start..*end, Function { position: *start..*end, name: name.clone(), generics_arguments: mem::take(generics_arguments), arguments: mem::take(arguments), return_type: Type::None, expression: Expression { position: expression.position.clone(), expression: ExpressionItem::Block(mem::take(expression)) }, generator: *generator, public: false })));
+            |<FunctionKeyword> [Identifier] "(" <FunctionArguments> "," ")" "->" <Type> <ExpressionBlock>: [NonTerminal(Symbol::FunctionKeyword((Range { start, .. }, generator))), Terminal(Token::Identifier((_, name))), _, NonTerminal(Symbol::FunctionArguments((_, arguments))), _, _, _, NonTerminal(Symbol::Type((_, t))), NonTerminal(Symbol::ExpressionBlock((Range { end, .. }, expression)))] => Ok(Symbol::FunctionDefinition((*start..*end, Function { position: *start..*end, name: name.clone(), generics_arguments: Vec::new(), arguments: mem::take(arguments), return_type: mem::take(t), expression: Expression { position: expression.position.clone(), expression: ExpressionItem::Block(mem::take(expression)) }, generator: *generator, public: false })));
+            |<FunctionKeyword> [Identifier] "(" <FunctionArguments> "," ")" <ExpressionBlock>: [NonTerminal(Symbol::FunctionKeyword((Range { start, .. }, generator))), Terminal(Token::Identifier((_, name))), _, NonTerminal(Symbol::FunctionArguments((_, arguments))), _, _, NonTerminal(Symbol::ExpressionBlock((Range { end, .. }, expression)))] => Ok(Symbol::FunctionDefinition((*start..*end, Function { position: *start..*end, name: name.clone(), generics_arguments: Vec::new(), arguments: mem::take(arguments), return_type: Type::None, expression: Expression { position: expression.position.clone(), expression: ExpressionItem::Block(mem::take(expression)) }, generator: *generator, public: false })));
+            |<FunctionKeyword> [Identifier] <GenericsTypeParameters> "(" <FunctionArguments> "," ")" "->" <Type> <ExpressionBlock>: [NonTerminal(Symbol::FunctionKeyword((Range { start, .. }, generator))), Terminal(Token::Identifier((_, name))), NonTerminal(Symbol::GenericsTypeParameters((_, generics_arguments))), _, NonTerminal(Symbol::FunctionArguments((_, arguments))), _, _, _, NonTerminal(Symbol::Type((_, t))), NonTerminal(Symbol::ExpressionBlock((Range { end, .. }, expression)))] => Ok(Symbol::FunctionDefinition((*start..*end, Function { position: *start..*end, name: name.clone(), generics_arguments: mem::take(generics_arguments), arguments: mem::take(arguments), return_type: mem::take(t), expression: Expression { position: expression.position.clone(), expression: ExpressionItem::Block(mem::take(expression)) }, generator: *generator, public: false })));
+            |<FunctionKeyword> [Identifier] <GenericsTypeParameters> "(" <FunctionArguments> "," ")" <ExpressionBlock>: [NonTerminal(Symbol::FunctionKeyword((Range { start, .. }, generator))), Terminal(Token::Identifier((_, name))), NonTerminal(Symbol::GenericsTypeParameters((_, generics_arguments))), _, NonTerminal(Symbol::FunctionArguments((_, arguments))), _, _, NonTerminal(Symbol::ExpressionBlock((Range { end, .. }, expression)))] => Ok(Symbol::FunctionDefinition((*start..*end, Function { position: *start..*end, name: name.clone(), generics_arguments: mem::take(generics_arguments), arguments: mem::take(arguments), return_type: Type::None, expression: Expression { position: expression.position.clone(), expression: ExpressionItem::Block(mem::take(expression)) }, generator: *generator, public: false })));
+            |<FunctionKeyword> [Identifier] "(" ")" "->" <Type> <ExpressionBlock>: [NonTerminal(Symbol::FunctionKeyword((Range { start, .. }, generator))), Terminal(Token::Identifier((_, name))), _, _, _, NonTerminal(Symbol::Type((_, t))), NonTerminal(Symbol::ExpressionBlock((Range { end, .. }, expression)))] => Ok(Symbol::FunctionDefinition((*start..*end, Function { position: *start..*end, name: name.clone(), generics_arguments: Vec::new(), arguments: Vec::new(), return_type: mem::take(t), expression: Expression { position: expression.position.clone(), expression: ExpressionItem::Block(mem::take(expression)) }, generator: *generator, public: false })));
+            |<FunctionKeyword> [Identifier] "(" ")" <ExpressionBlock>: [NonTerminal(Symbol::FunctionKeyword((Range { start, .. }, generator))), Terminal(Token::Identifier((_, name))), _, _, NonTerminal(Symbol::ExpressionBlock((Range { end, .. }, expression)))] => Ok(Symbol::FunctionDefinition((*start..*end, Function { position: *start..*end, name: name.clone(), generics_arguments: Vec::new(), arguments: Vec::new(), return_type: Type::None, expression: Expression { position: expression.position.clone(), expression: ExpressionItem::Block(mem::take(expression)) }, generator: *generator, public: false })));
+            |<FunctionKeyword> [Identifier] <GenericsTypeParameters> "(" ")" "->" <Type> <ExpressionBlock>: [NonTerminal(Symbol::FunctionKeyword((Range { start, .. }, generator))), Terminal(Token::Identifier((_, name))), NonTerminal(Symbol::GenericsTypeParameters((_, generics_arguments))), _, _, _, NonTerminal(Symbol::Type((_, t))), NonTerminal(Symbol::ExpressionBlock((Range { end, .. }, expression)))] => Ok(Symbol::FunctionDefinition((*start..*end, Function { position: *start..*end, name: name.clone(), generics_arguments: mem::take(generics_arguments), arguments: Vec::new(), return_type: mem::take(t), expression: Expression { position: expression.position.clone(), expression: ExpressionItem::Block(mem::take(expression)) }, generator: *generator, public: false })));
+            |<FunctionKeyword> [Identifier] <GenericsTypeParameters> "(" ")" <ExpressionBlock>: [NonTerminal(Symbol::FunctionKeyword((Range { start, .. }, generator))), Terminal(Token::Identifier((_, name))), NonTerminal(Symbol::GenericsTypeParameters((_, generics_arguments))), _, _, NonTerminal(Symbol::ExpressionBlock((Range { end, .. }, expression)))] => Ok(Symbol::FunctionDefinition((*start..*end, Function { position: *start..*end, name: name.clone(), generics_arguments: mem::take(generics_arguments), arguments: Vec::new(), return_type: Type::None, expression: Expression { position: expression.position.clone(), expression: ExpressionItem::Block(mem::take(expression)) }, generator: *generator, public: false })));
+            |<FunctionKeyword> [Identifier] "(" <FunctionArguments> ")" "->" <Type> "|->" <Expression> ";": [NonTerminal(Symbol::FunctionKeyword((Range { start, .. }, generator))), Terminal(Token::Identifier((_, name))), _, NonTerminal(Symbol::FunctionArguments((_, arguments))), _, _, NonTerminal(Symbol::Type((_, t))), _, NonTerminal(Symbol::Expression((_, expression))), Terminal(Token::Semicolon(Range { end, .. }))] => Ok(Symbol::FunctionDefinition((*start..*end, Function { position: *start..*end, name: name.clone(), generics_arguments: Vec::new(), arguments: mem::take(arguments), return_type: mem::take(t), expression: mem::take(expression), generator: *generator, public: false })));
+            |<FunctionKeyword> [Identifier] "(" <FunctionArguments> ")" "|->" <Expression> ";": [NonTerminal(Symbol::FunctionKeyword((Range { start, .. }, generator))), Terminal(Token::Identifier((_, name))), _, NonTerminal(Symbol::FunctionArguments((_, arguments))), _, _, NonTerminal(Symbol::Expression((_, expression))), Terminal(Token::Semicolon(Range { end, .. }))] => Ok(Symbol::FunctionDefinition((*start..*end, Function { position: *start..*end, name: name.clone(), generics_arguments: Vec::new(), arguments: mem::take(arguments), return_type: Type::None, expression: mem::take(expression), generator: *generator, public: false })));
+            |<FunctionKeyword> [Identifier] <GenericsTypeParameters> "(" <FunctionArguments> ")" "->" <Type> "|->" <Expression> ";": [NonTerminal(Symbol::FunctionKeyword((Range { start, .. }, generator))), Terminal(Token::Identifier((_, name))), NonTerminal(Symbol::GenericsTypeParameters((_, generics_arguments))), _, NonTerminal(Symbol::FunctionArguments((_, arguments))), _, _, NonTerminal(Symbol::Type((_, t))), _, NonTerminal(Symbol::Expression((_, expression))), Terminal(Token::Semicolon(Range { end, .. }))] => Ok(Symbol::FunctionDefinition((*start..*end, Function { position: *start..*end, name: name.clone(), generics_arguments: mem::take(generics_arguments), arguments: mem::take(arguments), return_type: mem::take(t), expression: mem::take(expression), generator: *generator, public: false })));
+            |<FunctionKeyword> [Identifier] <GenericsTypeParameters> "(" <FunctionArguments> ")" "|->" <Expression> ";": [NonTerminal(Symbol::FunctionKeyword((Range { start, .. }, generator))), Terminal(Token::Identifier((_, name))), NonTerminal(Symbol::GenericsTypeParameters((_, generics_arguments))), _, NonTerminal(Symbol::FunctionArguments((_, arguments))), _, _, NonTerminal(Symbol::Expression((_, expression))), Terminal(Token::Semicolon(Range { end, .. }))] => Ok(Symbol::FunctionDefinition((*start..*end, Function { position: *start..*end, name: name.clone(), generics_arguments: mem::take(generics_arguments), arguments: mem::take(arguments), return_type: Type::None, expression: mem::take(expression), generator: *generator, public: false })));
+            |<FunctionKeyword> [Identifier] "(" <FunctionArguments> "," ")" "->" <Type> "|->" <Expression> ";": [NonTerminal(Symbol::FunctionKeyword((Range { start, .. }, generator))), Terminal(Token::Identifier((_, name))), _, NonTerminal(Symbol::FunctionArguments((_, arguments))), Terminal(Token::Comma(_)), _, _, NonTerminal(Symbol::Type((_, t))), _, NonTerminal(Symbol::Expression((_, expression))), Terminal(Token::Semicolon(Range { end, .. }))] => Ok(Symbol::FunctionDefinition((*start..*end, Function { position: *start..*end, name: name.clone(), generics_arguments: Vec::new(), arguments: mem::take(arguments), return_type: mem::take(t), expression: mem::take(expression), generator: *generator, public: false })));
+            |<FunctionKeyword> [Identifier] "(" <FunctionArguments> "," ")" "|->" <Expression> ";": [NonTerminal(Symbol::FunctionKeyword((Range { start, .. }, generator))), Terminal(Token::Identifier((_, name))), _, NonTerminal(Symbol::FunctionArguments((_, arguments))), Terminal(Token::Comma(_)), _, _, NonTerminal(Symbol::Expression((_, expression))), Terminal(Token::Semicolon(Range { end, .. }))] => Ok(Symbol::FunctionDefinition((*start..*end, Function { position: *start..*end, name: name.clone(), generics_arguments: Vec::new(), arguments: mem::take(arguments), return_type: Type::None, expression: mem::take(expression), generator: *generator, public: false })));
+            |<FunctionKeyword> [Identifier] <GenericsTypeParameters> "(" <FunctionArguments> "," ")" "->" <Type> "|->" <Expression> ";": [NonTerminal(Symbol::FunctionKeyword((Range { start, .. }, generator))), Terminal(Token::Identifier((_, name))), NonTerminal(Symbol::GenericsTypeParameters((_, generics_arguments))), _, NonTerminal(Symbol::FunctionArguments((_, arguments))), Terminal(Token::Comma(_)), _, _, NonTerminal(Symbol::Type((_, t))), _, NonTerminal(Symbol::Expression((_, expression))), Terminal(Token::Semicolon(Range { end, .. }))] => Ok(Symbol::FunctionDefinition((*start..*end, Function { position: *start..*end, name: name.clone(), generics_arguments: mem::take(generics_arguments), arguments: mem::take(arguments), return_type: mem::take(t), expression: mem::take(expression), generator: *generator, public: false })));
+            |<FunctionKeyword> [Identifier] <GenericsTypeParameters> "(" <FunctionArguments> "," ")" "|->" <Expression> ";": [NonTerminal(Symbol::FunctionKeyword((Range { start, .. }, generator))), Terminal(Token::Identifier((_, name))), NonTerminal(Symbol::GenericsTypeParameters((_, generics_arguments))), _, NonTerminal(Symbol::FunctionArguments((_, arguments))), Terminal(Token::Comma(_)), _, _, NonTerminal(Symbol::Expression((_, expression))), Terminal(Token::Semicolon(Range { end, .. }))] => Ok(Symbol::FunctionDefinition((*start..*end, Function { position: *start..*end, name: name.clone(), generics_arguments: mem::take(generics_arguments), arguments: mem::take(arguments), return_type: Type::None, expression: mem::take(expression), generator: *generator, public: false })));
+            |<FunctionKeyword> [Identifier] "(" ")" "->" <Type> "|->" <Expression> ";": [NonTerminal(Symbol::FunctionKeyword((Range { start, .. }, generator))), Terminal(Token::Identifier((_, name))), _, _, _, NonTerminal(Symbol::Type((_, t))), _, NonTerminal(Symbol::Expression((_, expression))), Terminal(Token::Semicolon(Range { end, .. }))] => Ok(Symbol::FunctionDefinition((*start..*end, Function { position: *start..*end, name: name.clone(), generics_arguments: Vec::new(), arguments: Vec::new(), return_type: mem::take(t), expression: mem::take(expression), generator: *generator, public: false })));
+            |<FunctionKeyword> [Identifier] "(" ")" "|->" <Expression> ";": [NonTerminal(Symbol::FunctionKeyword((Range { start, .. }, generator))), Terminal(Token::Identifier((_, name))), _, _, _, NonTerminal(Symbol::Expression((_, expression))), Terminal(Token::Semicolon(Range { end, .. }))] => Ok(Symbol::FunctionDefinition((*start..*end, Function { position: *start..*end, name: name.clone(), generics_arguments: Vec::new(), arguments: Vec::new(), return_type: Type::None, expression: mem::take(expression), generator: *generator, public: false })));
+            |<FunctionKeyword> [Identifier] <GenericsTypeParameters> "(" ")" "->" <Type> "|->" <Expression> ";": [NonTerminal(Symbol::FunctionKeyword((Range { start, .. }, generator))), Terminal(Token::Identifier((_, name))), NonTerminal(Symbol::GenericsTypeParameters((_, generics_arguments))), _, _, _, NonTerminal(Symbol::Type((_, t))), _, NonTerminal(Symbol::Expression((_, expression))), Terminal(Token::Semicolon(Range { end, .. }))] => Ok(Symbol::FunctionDefinition((*start..*end, Function { position: *start..*end, name: name.clone(), generics_arguments: mem::take(generics_arguments), arguments: Vec::new(), return_type: mem::take(t), expression: mem::take(expression), generator: *generator, public: false })));
+            |<FunctionKeyword> [Identifier] <GenericsTypeParameters> "(" ")" "|->" <Expression> ";": [NonTerminal(Symbol::FunctionKeyword((Range { start, .. }, generator))), Terminal(Token::Identifier((_, name))), NonTerminal(Symbol::GenericsTypeParameters((_, generics_arguments))), _, _, _, NonTerminal(Symbol::Expression((_, expression))), Terminal(Token::Semicolon(Range { end, .. }))] => Ok(Symbol::FunctionDefinition((*start..*end, Function { position: *start..*end, name: name.clone(), generics_arguments: mem::take(generics_arguments), arguments: Vec::new(), return_type: Type::None, expression: mem::take(expression), generator: *generator, public: false })));
         <FunctionArguments>::=<Pattern> ":" <Type>: [NonTerminal(Symbol::Pattern((Range { start, .. }, pattern))), _, NonTerminal(Symbol::Type((Range { end, .. }, t)))] => Ok(Symbol::FunctionArguments((*start..*end, vec![(mem::take(pattern), mem::take(t))])));
             |<FunctionArguments> "," <Pattern> ":" <Type>: [NonTerminal(Symbol::FunctionArguments((Range { start, .. }, list))), _, NonTerminal(Symbol::Pattern((_, pattern))), _, NonTerminal(Symbol::Type((Range { end, .. }, t)))] => Ok(Symbol::FunctionArguments((*start..*end, {
                     list.push((mem::take(pattern), mem::take(t)));
@@ -869,5 +1129,19 @@ parser! {
                     list.push(name.clone());
                     mem::take(list)
                 })));
+        <GenericsTypeParameters>::="::" "<" <BoundedGenericsTypeArguments> ">": [Terminal(Token::DoubleColon(Range { start, .. })), _, NonTerminal(Symbol::BoundedGenericsTypeArguments((_, list))), Terminal(Token::GreaterThan(Range { end, .. }))] => Ok(Symbol::GenericsTypeParameters((*start..*end, mem::take(list))));
+            |"<" <BoundedGenericsTypeArguments> ">": [Terminal(Token::LessThan(Range { start, .. })), NonTerminal(Symbol::BoundedGenericsTypeArguments((_, list))), Terminal(Token::GreaterThan(Range { end, .. }))] => Ok(Symbol::GenericsTypeParameters((*start..*end, mem::take(list))));
+        <BoundedGenericsTypeArguments>::=<BoundedGenericsTypeArgument>: [NonTerminal(Symbol::BoundedGenericsTypeArgument((position, argument)))] => Ok(Symbol::BoundedGenericsTypeArguments((position.clone(), vec![mem::take(argument)])));
+            |<BoundedGenericsTypeArguments> "," <BoundedGenericsTypeArgument>: [NonTerminal(Symbol::BoundedGenericsTypeArguments((Range { start, .. }, list))), _, NonTerminal(Symbol::BoundedGenericsTypeArgument((Range { end, .. }, argument)))] => Ok(Symbol::BoundedGenericsTypeArguments((*start..*end, {
+                    list.push(mem::take(argument));
+                    mem::take(list)
+                })));
+        <BoundedGenericsTypeArgument>::=[Identifier]: [Terminal(Token::Identifier((position, name)))] => Ok(Symbol::BoundedGenericsTypeArgument((position.clone(), (name.clone(), Vec::new()))));
+            |[Identifier] ":" <GenericsBounds>: [Terminal(Token::Identifier((Range { start, .. }, name))), _, NonTerminal(Symbol::GenericsBounds((Range { end, .. }, bounds)))] => Ok(Symbol::BoundedGenericsTypeArgument((*start..*end, (name.clone(), mem::take(bounds)))));
+        <GenericsBounds>::=[Identifier]: [Terminal(Token::Identifier((position, name)))] => Ok(Symbol::GenericsBounds((position.clone(), vec![name.clone()])));
+            |<GenericsBounds> "+" [Identifier]: [NonTerminal(Symbol::GenericsBounds((Range { start, .. }, list))), _, Terminal(Token::Identifier((Range { end, .. }, name)))] => Ok(Symbol::GenericsBounds((*start..*end, {
+                    list.push(name.clone());
+                    mem::take(list)
+                })));
     }
 }
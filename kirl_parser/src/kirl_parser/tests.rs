@@ -2,7 +2,7 @@ use parser::Parse;
 use tokenizer::Tokenize;
 
 use crate::kirl_parser::{get_parser, AnonymousStructType, Block, Condition, ConstructStruct, Expression, ExpressionItem, Function, If, KirlTopLevelStatement, LetBinding, NamedType, Path, Pattern, Statement, StatementItem, StructName, Symbol, Type};
-use crate::kirl_tokenizer::get_tokenizer;
+use crate::kirl_tokenizer::{expand_format_string, get_tokenizer};
 use crate::{CharacterPosition, KirlParser};
 use kirl_common::dec::Decimal128;
 
@@ -50,9 +50,9 @@ var #{value_a, value_b} = my_function(10);
 
 a:while true {
     if value_a > 10 {
-        break a;
+        break 'a;
     } else {
-        continue a;
+        continue 'a;
     }
 }
 
@@ -61,6 +61,9 @@ struct MyStruct::<T> {
     value_b: string
 }
 
+var s: MyStruct::<Number> = MyStruct::<Number>#{value_a: [1], value_b: "b"};
+var MyStruct#{value_a, value_b} = s;
+
 std::test::new();
 std::test::new(a);
 std::test::new(a,);
@@ -93,6 +96,76 @@ a.(a,);
 a.(a, b);
 a.(a, b,);
 
+a.std::test::new()?;
+a?.b?.0th;
+
+a && b || c & d;
+!a || b == c && d;
+
+var found = a: loop {
+    loop {
+        break 'a value;
+    }
+};
+loop {
+    break (1, 2);
+}
+
+enum Shape::<T> {
+    Circle(Number),
+    Rect#{w: Number, h: Number},
+    Pair(T, T),
+    Empty,
+}
+var area = match s {
+    Shape::Circle(r) => r,
+    Shape::Pair(a, b) => a,
+    Shape::Rect#{w, h} => w * h,
+    Shape::Empty() => 0,
+};
+var empty = match s {
+    Shape::Empty => Shape::Empty,
+    other => other,
+};
+
+type Pair::<T> = (T, T);
+type Point = #{x: Number, y: Number};
+type List::<T> = () | (T, List::<T>);
+
+fn max::<T: Comparable + Equatable, U>(a: T, b: U) -> T |-> a;
+fn min<T: Comparable>(a: T, b: T) -> T |-> b;
+
+i += 1;
+list[i] -= 2;
+s.value_a *= 3;
+s.0th /= 4;
+i %= 5;
+
+f"value = {x}, name = {p.name}".println();
+f"{a + b}".println();
+
+for i in 0..10 {}
+var slice = list[1..=3];
+(0..10).step(2);
+
+fn* numbers() -> std::iter::Iterator::<Number> {
+    yield 1;
+    yield 2;
+}
+fn* repeat::<T>(value: T, count: Number) -> std::iter::Iterator::<T> {
+    for _ in 0..count {
+        yield value;
+    }
+}
+
+pub fn helper() {}
+pub struct Shared {
+    value: Number
+}
+pub var counter = 0;
+pub var (a, b): (Number, Number) = (1, 2);
+pub import std::{io::println, num::_add};
+
 "#;
     let parse = |code: &str| {
         code.chars()
@@ -106,6 +179,7 @@ a.(a, b,);
             })
             .tokenize_with(&tokenizer, |(_, c)| *c)
             .flat_map(|token| token.unwrap())
+            .flat_map(|token| expand_format_string(token, &tokenizer).unwrap())
             .parse(&parser)
     };
     if let Err(err) = parse(CODE) {
@@ -265,6 +339,7 @@ a.(a, b,);
                                                                     .into(),
                                                                 ),
                                                             }),
+                                                            public: false,
                                                         }),
                                                     },],
                                                     last_expression: Some(
@@ -308,6 +383,8 @@ a.(a, b,);
                             ),
                         }),
                     },
+                    generator: false,
+                    public: false,
                 },
             )),],
         ))
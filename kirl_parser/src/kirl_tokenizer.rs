@@ -7,7 +7,7 @@ use std::str::FromStr;
 use kirl_common::dec::{Decimal128, ParseDecimalError};
 use parser::enum_index;
 use parser::enum_index_derive::*;
-use tokenizer::DFATokenizer;
+use tokenizer::{DFATokenizer, Tokenize};
 use tokenizer_generator::tokenizer;
 
 use crate::CharacterPosition;
@@ -16,10 +16,18 @@ use crate::CharacterPosition;
 pub enum Token {
     /// import
     Import(Range<CharacterPosition>),
+    /// pub
+    Pub(Range<CharacterPosition>),
     /// fn
     Fn(Range<CharacterPosition>),
+    /// fn*
+    GeneratorFn(Range<CharacterPosition>),
     /// struct
     Struct(Range<CharacterPosition>),
+    /// enum
+    Enum(Range<CharacterPosition>),
+    /// type
+    Type(Range<CharacterPosition>),
     /// let
     Let(Range<CharacterPosition>),
     /// var
@@ -36,8 +44,12 @@ pub enum Token {
     In(Range<CharacterPosition>),
     /// while
     While(Range<CharacterPosition>),
+    /// loop
+    Loop(Range<CharacterPosition>),
     /// return
     Return(Range<CharacterPosition>),
+    /// yield
+    Yield(Range<CharacterPosition>),
     /// break
     Break(Range<CharacterPosition>),
     /// continue
@@ -46,12 +58,24 @@ pub enum Token {
     TupleIndex((Range<CharacterPosition>, usize)),
     /// 変数とかの識別子
     Identifier((Range<CharacterPosition>, String)),
+    /// break 'labelやcontinue 'labelで参照するループのラベル('は含まない)
+    Label((Range<CharacterPosition>, String)),
     /// 文字列即値
     StringImmediate((Range<CharacterPosition>, String)),
     /// 数値即値
     NumberImmediate((Range<CharacterPosition>, Decimal128)),
+    /// f"...{式}..." (パーサに渡す前にexpand_format_stringで以下の3種類と式のトークンに分解する)
+    FormatString((Range<CharacterPosition>, Vec<FormatStringPart>)),
+    /// f"...{
+    FormatStringStart((Range<CharacterPosition>, String)),
+    /// }...{
+    FormatStringMiddle((Range<CharacterPosition>, String)),
+    /// }..."
+    FormatStringEnd((Range<CharacterPosition>, String)),
     /// !
     Not(Range<CharacterPosition>),
+    /// ?
+    Question(Range<CharacterPosition>),
     /// .
     Dot(Range<CharacterPosition>),
     /// ,
@@ -60,6 +84,10 @@ pub enum Token {
     Sharp(Range<CharacterPosition>),
     /// ::
     DoubleColon(Range<CharacterPosition>),
+    /// ..
+    DoubleDot(Range<CharacterPosition>),
+    /// ..=
+    DoubleDotEquals(Range<CharacterPosition>),
     /// :
     Colon(Range<CharacterPosition>),
     /// ;
@@ -78,6 +106,16 @@ pub enum Token {
     Equals(Range<CharacterPosition>),
     /// !=
     NotEquals(Range<CharacterPosition>),
+    /// +=
+    AddAssign(Range<CharacterPosition>),
+    /// -=
+    SubAssign(Range<CharacterPosition>),
+    /// *=
+    MulAssign(Range<CharacterPosition>),
+    /// /=
+    DivAssign(Range<CharacterPosition>),
+    /// %=
+    RemAssign(Range<CharacterPosition>),
     /// +
     Add(Range<CharacterPosition>),
     /// -
@@ -94,6 +132,10 @@ pub enum Token {
     Or(Range<CharacterPosition>),
     /// ^
     Xor(Range<CharacterPosition>),
+    /// &&
+    LogicalAnd(Range<CharacterPosition>),
+    /// ||
+    LogicalOr(Range<CharacterPosition>),
     /// (
     RoundBracketOpen(Range<CharacterPosition>),
     /// )
@@ -119,7 +161,11 @@ impl Token {
         match self {
             Token::Import(range) => range,
             Token::Fn(range) => range,
+            Token::GeneratorFn(range) => range,
+            Token::Pub(range) => range,
             Token::Struct(range) => range,
+            Token::Enum(range) => range,
+            Token::Type(range) => range,
             Token::Let(range) => range,
             Token::Var(range) => range,
             Token::If(range) => range,
@@ -128,18 +174,28 @@ impl Token {
             Token::For(range) => range,
             Token::In(range) => range,
             Token::While(range) => range,
+            Token::Loop(range) => range,
             Token::Return(range) => range,
+            Token::Yield(range) => range,
             Token::Break(range) => range,
             Token::Continue(range) => range,
             Token::TupleIndex((range, _)) => range,
             Token::Identifier((range, _)) => range,
+            Token::Label((range, _)) => range,
             Token::StringImmediate((range, _)) => range,
             Token::NumberImmediate((range, _)) => range,
+            Token::FormatString((range, _)) => range,
+            Token::FormatStringStart((range, _)) => range,
+            Token::FormatStringMiddle((range, _)) => range,
+            Token::FormatStringEnd((range, _)) => range,
             Token::Not(range) => range,
+            Token::Question(range) => range,
             Token::Dot(range) => range,
             Token::Comma(range) => range,
             Token::Sharp(range) => range,
             Token::DoubleColon(range) => range,
+            Token::DoubleDot(range) => range,
+            Token::DoubleDotEquals(range) => range,
             Token::Colon(range) => range,
             Token::Semicolon(range) => range,
             Token::GreaterThan(range) => range,
@@ -149,6 +205,11 @@ impl Token {
             Token::Assign(range) => range,
             Token::Equals(range) => range,
             Token::NotEquals(range) => range,
+            Token::AddAssign(range) => range,
+            Token::SubAssign(range) => range,
+            Token::MulAssign(range) => range,
+            Token::DivAssign(range) => range,
+            Token::RemAssign(range) => range,
             Token::Add(range) => range,
             Token::Sub(range) => range,
             Token::Mul(range) => range,
@@ -157,6 +218,8 @@ impl Token {
             Token::And(range) => range,
             Token::Or(range) => range,
             Token::Xor(range) => range,
+            Token::LogicalAnd(range) => range,
+            Token::LogicalOr(range) => range,
             Token::RoundBracketOpen(range) => range,
             Token::RoundBracketClose(range) => range,
             Token::SquareBracketOpen(range) => range,
@@ -170,11 +233,19 @@ impl Token {
     }
 }
 
+#[derive(Debug, PartialEq)]
+pub enum FormatStringPart {
+    Text((Range<CharacterPosition>, String)),
+    // 埋め込まれた式の文字列
+    Hole(Vec<(CharacterPosition, char)>),
+}
+
 #[derive(Debug, PartialEq)]
 pub enum TokenizeError {
     IntegerParseError { raw: String, position: Range<CharacterPosition> },
     DecimalParseError { raw: String, position: Range<CharacterPosition>, error: ParseDecimalError },
     StringParseError { raw: String, position: Range<CharacterPosition> },
+    UnsupportedFormatStringHole { raw: String, position: Range<CharacterPosition> },
     UnknownCharacter { character: char, position: CharacterPosition },
 }
 
@@ -207,8 +278,12 @@ tokenizer! {
         "/\\*(\\*[^/]|[^\\*])*\\*/": |_, _| Ok(array![]);
         "\\s": |_, _| Ok(array![]);
         "import": |_, v| Ok(array![Token::Import(v.first().unwrap().0..v.last().unwrap().0.next())]);
+        "pub": |_, v| Ok(array![Token::Pub(v.first().unwrap().0..v.last().unwrap().0.next())]);
         "fn": |_, v| Ok(array![Token::Fn(v.first().unwrap().0..v.last().unwrap().0.next())]);
+        "fn\\*": |_, v| Ok(array![Token::GeneratorFn(v.first().unwrap().0..v.last().unwrap().0.next())]);
         "struct": |_, v| Ok(array![Token::Struct(v.first().unwrap().0..v.last().unwrap().0.next())]);
+        "enum": |_, v| Ok(array![Token::Enum(v.first().unwrap().0..v.last().unwrap().0.next())]);
+        "type": |_, v| Ok(array![Token::Type(v.first().unwrap().0..v.last().unwrap().0.next())]);
         "let": |_, v| Ok(array![Token::Let(v.first().unwrap().0..v.last().unwrap().0.next())]);
         "var": |_, v| Ok(array![Token::Var(v.first().unwrap().0..v.last().unwrap().0.next())]);
         "if": |_, v| Ok(array![Token::If(v.first().unwrap().0..v.last().unwrap().0.next())]);
@@ -217,20 +292,27 @@ tokenizer! {
         "for": |_, v| Ok(array![Token::For(v.first().unwrap().0..v.last().unwrap().0.next())]);
         "in": |_, v| Ok(array![Token::In(v.first().unwrap().0..v.last().unwrap().0.next())]);
         "while": |_, v| Ok(array![Token::While(v.first().unwrap().0..v.last().unwrap().0.next())]);
+        "loop": |_, v| Ok(array![Token::Loop(v.first().unwrap().0..v.last().unwrap().0.next())]);
         "return": |_, v| Ok(array![Token::Return(v.first().unwrap().0..v.last().unwrap().0.next())]);
+        "yield": |_, v| Ok(array![Token::Yield(v.first().unwrap().0..v.last().unwrap().0.next())]);
         "break": |_, v| Ok(array![Token::Break(v.first().unwrap().0..v.last().unwrap().0.next())]);
         "continue": |_, v| Ok(array![Token::Continue(v.first().unwrap().0..v.last().unwrap().0.next())]);
         "\\.[0-9][1-9]*(th|st|nd|rd)": |s, v| Ok(array![Token::Dot(v.first().unwrap().0..v[1].0), Token::TupleIndex((v[1].0..v.last().unwrap().0.next(), parse_tuple_index(&s[1..])))]);
         "[0-9][1-9]*(th|st|nd|rd)": |s, v| Ok(array![Token::TupleIndex((v.first().unwrap().0..v.last().unwrap().0.next(), parse_tuple_index(s)))]);
         "[a-zA-Z_][a-zA-Z0-9_]*": |s, v| Ok(array![Token::Identifier((v.first().unwrap().0..v.last().unwrap().0.next(), s.to_string()))]);
+        "'[a-zA-Z_][a-zA-Z0-9_]*": |s, v| Ok(array![Token::Label((v.first().unwrap().0..v.last().unwrap().0.next(), s[1..].to_string()))]);
         "\"(\\\\(\n|\r|\r\n|r|n|t|x[0-9a-fA-F]{2}|u\\{[0-9a-fA-F]{1,6}\\}|\\\\|\")|[^\\\\\"])*\"": |s, v| {
-                parse_string_literal(s)
+                parse_string_literal(s, false)
                     .ok_or_else(|| TokenizeError::StringParseError {
                         raw: s.to_string(),
                         position: v.first().unwrap().0..v.last().unwrap().0.next(),
                     })
                     .map(|s| array![Token::StringImmediate((v.first().unwrap().0..v.last().unwrap().0.next(), s))])
             };
+        // 埋め込む式には文字列即値と一段までの{}を書ける
+        "f\"(\\\\(\n|\r|\r\n|r|n|t|x[0-9a-fA-F]{2}|u\\{[0-9a-fA-F]{1,6}\\}|\\\\|\"|\\{|\\})|[^\\\\\"\\{\\}]|\\{([^\\\\\"\\{\\}]|\"(\\\\(\n|\r|\r\n|r|n|t|x[0-9a-fA-F]{2}|u\\{[0-9a-fA-F]{1,6}\\}|\\\\|\")|[^\\\\\"])*\"|\\{([^\\\\\"\\{\\}]|\"(\\\\(\n|\r|\r\n|r|n|t|x[0-9a-fA-F]{2}|u\\{[0-9a-fA-F]{1,6}\\}|\\\\|\")|[^\\\\\"])*\")*\\})*\\})*\"": |s, v| split_format_string(s, v);
+        // それ以外の\や深い{}を含む埋め込みは、ただの文字列として読まずにエラーにする
+        "f\"(\\\\(\n|\r|\r\n|r|n|t|x[0-9a-fA-F]{2}|u\\{[0-9a-fA-F]{1,6}\\}|\\\\|\"|\\{|\\})|[^\\\\\"\\{\\}]|\\{([^\\\\\"\\{\\}]|\"(\\\\(\n|\r|\r\n|r|n|t|x[0-9a-fA-F]{2}|u\\{[0-9a-fA-F]{1,6}\\}|\\\\|\")|[^\\\\\"])*\"|\\{([^\\\\\"\\{\\}]|\"(\\\\(\n|\r|\r\n|r|n|t|x[0-9a-fA-F]{2}|u\\{[0-9a-fA-F]{1,6}\\}|\\\\|\")|[^\\\\\"])*\")*\\})*\\})*\\{([^\\\\\"\\{\\}]|\"(\\\\(\n|\r|\r\n|r|n|t|x[0-9a-fA-F]{2}|u\\{[0-9a-fA-F]{1,6}\\}|\\\\|\")|[^\\\\\"])*\"|\\{([^\\\\\"\\{\\}]|\"(\\\\(\n|\r|\r\n|r|n|t|x[0-9a-fA-F]{2}|u\\{[0-9a-fA-F]{1,6}\\}|\\\\|\")|[^\\\\\"])*\")*\\})*(\\\\|\\{([^\\\\\"\\{\\}]|\"(\\\\(\n|\r|\r\n|r|n|t|x[0-9a-fA-F]{2}|u\\{[0-9a-fA-F]{1,6}\\}|\\\\|\")|[^\\\\\"])*\")*(\\\\|\\{))": |s, v| Err(TokenizeError::UnsupportedFormatStringHole { raw: s.to_string(), position: v.first().unwrap().0..v.last().unwrap().0.next() });
         "0b[01_]*[01]|0o[0-7_]*[0-7]|0d[0-9_]*[0-9]|0x[0-9a-fA-F_]*[0-9a-fA-F]|[0-9]|[0-9][0-9_]*[0-9]": |s, v| Ok(array![Token::NumberImmediate((v.first().unwrap().0..v.last().unwrap().0.next(), parse_integer(s)))]);
         "([0-9][0-9_]*[0-9]|[0-9]|([0-9][0-9_]*)?\\.[0-9_]*[0-9])([eE][\\+\\-]?[0-9_]*[0-9])?": |s, v| {
                 Decimal128::from_str(&s.replace("_", ""))
@@ -243,7 +325,10 @@ tokenizer! {
                         })
         };
         "!": |_, v| Ok(array![Token::Not(v.first().unwrap().0..v.last().unwrap().0.next())]);
+        "\\?": |_, v| Ok(array![Token::Question(v.first().unwrap().0..v.last().unwrap().0.next())]);
         "\\.": |_, v| Ok(array![Token::Dot(v.first().unwrap().0..v.last().unwrap().0.next())]);
+        "\\.\\.": |_, v| Ok(array![Token::DoubleDot(v.first().unwrap().0..v.last().unwrap().0.next())]);
+        "\\.\\.=": |_, v| Ok(array![Token::DoubleDotEquals(v.first().unwrap().0..v.last().unwrap().0.next())]);
         ",": |_, v| Ok(array![Token::Comma(v.first().unwrap().0..v.last().unwrap().0.next())]);
         "#": |_, v| Ok(array![Token::Sharp(v.first().unwrap().0..v.last().unwrap().0.next())]);
         "::": |_, v| Ok(array![Token::DoubleColon(v.first().unwrap().0..v.last().unwrap().0.next())]);
@@ -256,6 +341,11 @@ tokenizer! {
         "=": |_, v| Ok(array![Token::Assign(v.first().unwrap().0..v.last().unwrap().0.next())]);
         "==": |_, v| Ok(array![Token::Equals(v.first().unwrap().0..v.last().unwrap().0.next())]);
         "!=": |_, v| Ok(array![Token::NotEquals(v.first().unwrap().0..v.last().unwrap().0.next())]);
+        "\\+=": |_, v| Ok(array![Token::AddAssign(v.first().unwrap().0..v.last().unwrap().0.next())]);
+        "\\-=": |_, v| Ok(array![Token::SubAssign(v.first().unwrap().0..v.last().unwrap().0.next())]);
+        "\\*=": |_, v| Ok(array![Token::MulAssign(v.first().unwrap().0..v.last().unwrap().0.next())]);
+        "/=": |_, v| Ok(array![Token::DivAssign(v.first().unwrap().0..v.last().unwrap().0.next())]);
+        "%=": |_, v| Ok(array![Token::RemAssign(v.first().unwrap().0..v.last().unwrap().0.next())]);
         "\\+": |_, v| Ok(array![Token::Add(v.first().unwrap().0..v.last().unwrap().0.next())]);
         "\\-": |_, v| Ok(array![Token::Sub(v.first().unwrap().0..v.last().unwrap().0.next())]);
         "\\*": |_, v| Ok(array![Token::Mul(v.first().unwrap().0..v.last().unwrap().0.next())]);
@@ -264,6 +354,8 @@ tokenizer! {
         "&": |_, v| Ok(array![Token::And(v.first().unwrap().0..v.last().unwrap().0.next())]);
         "\\|": |_, v| Ok(array![Token::Or(v.first().unwrap().0..v.last().unwrap().0.next())]);
         "\\^": |_, v| Ok(array![Token::Xor(v.first().unwrap().0..v.last().unwrap().0.next())]);
+        "&&": |_, v| Ok(array![Token::LogicalAnd(v.first().unwrap().0..v.last().unwrap().0.next())]);
+        "\\|\\|": |_, v| Ok(array![Token::LogicalOr(v.first().unwrap().0..v.last().unwrap().0.next())]);
         "\\(": |_, v| Ok(array![Token::RoundBracketOpen(v.first().unwrap().0..v.last().unwrap().0.next())]);
         "\\)": |_, v| Ok(array![Token::RoundBracketClose(v.first().unwrap().0..v.last().unwrap().0.next())]);
         "\\[": |_, v| Ok(array![Token::SquareBracketOpen(v.first().unwrap().0..v.last().unwrap().0.next())]);
@@ -277,6 +369,92 @@ tokenizer! {
     }
 }
 
+// f"..."を文字列の部分と埋め込まれた式の部分に分ける(式を含まなければただの文字列即値)
+fn split_format_string(s: &str, v: &[(CharacterPosition, char)]) -> Result<ArrayVec<Token, 2>, TokenizeError> {
+    let position = v.first().unwrap().0..v.last().unwrap().0.next();
+    let parse_error = || TokenizeError::StringParseError { raw: s.to_string(), position: position.clone() };
+    let mut parts = Vec::new();
+    let mut text_start = v.first().unwrap().0;
+    let mut text = String::from("\"");
+    let mut iter = v[2..v.len() - 1].iter().copied();
+    while let Some((character_position, c)) = iter.next() {
+        match c {
+            '\\' => {
+                text.push(c);
+                let (_, escaped) = iter.next().expect("正規表現でチェックしてるのであんりーちゃぶる");
+                text.push(escaped);
+                if escaped == 'u' {
+                    for (_, c) in iter.by_ref() {
+                        text.push(c);
+                        if c == '}' {
+                            break;
+                        }
+                    }
+                }
+            }
+            '{' => {
+                text.push('"');
+                parts.push(FormatStringPart::Text((text_start..character_position.next(), parse_string_literal(&text, true).ok_or_else(parse_error)?)));
+                // 埋め込みの中の文字列即値や{}の中の}では埋め込みを終えない
+                let mut hole = Vec::new();
+                let mut depth = 0usize;
+                let mut in_string = false;
+                while let Some((character_position, c)) = iter.next() {
+                    match c {
+                        '\\' if in_string => {
+                            hole.push((character_position, c));
+                            hole.extend(iter.next());
+                            continue;
+                        }
+                        '"' => in_string = !in_string,
+                        '{' if !in_string => depth += 1,
+                        '}' if !in_string && depth == 0 => {
+                            text_start = character_position;
+                            break;
+                        }
+                        '}' if !in_string => depth -= 1,
+                        _ => {}
+                    }
+                    hole.push((character_position, c));
+                }
+                parts.push(FormatStringPart::Hole(hole));
+                text = String::from("\"");
+            }
+            c => text.push(c),
+        }
+    }
+    text.push('"');
+    let text = parse_string_literal(&text, true).ok_or_else(parse_error)?;
+    if parts.is_empty() {
+        return Ok(array![Token::StringImmediate((position, text))]);
+    }
+    parts.push(FormatStringPart::Text((text_start..position.end, text)));
+    Ok(array![Token::FormatString((position, parts))])
+}
+
+// FormatStringを FormatStringStart 式 FormatStringMiddle 式 ... FormatStringEnd のトークン列にする
+pub fn expand_format_string(token: Token, tokenizer: &Tokenizer) -> Result<Vec<Token>, TokenizeError> {
+    let parts = match token {
+        Token::FormatString((_, parts)) => parts,
+        token => return Ok(vec![token]),
+    };
+    let last = parts.len() - 1;
+    let mut result = Vec::new();
+    for (i, part) in parts.into_iter().enumerate() {
+        match part {
+            FormatStringPart::Text(text) if i == 0 => result.push(Token::FormatStringStart(text)),
+            FormatStringPart::Text(text) if i == last => result.push(Token::FormatStringEnd(text)),
+            FormatStringPart::Text(text) => result.push(Token::FormatStringMiddle(text)),
+            FormatStringPart::Hole(characters) => {
+                for tokens in characters.into_iter().tokenize_with(tokenizer, |(_, c)| *c) {
+                    result.extend(tokens?);
+                }
+            }
+        }
+    }
+    Ok(result)
+}
+
 fn parse_tuple_index(s: &str) -> usize {
     usize::from_str(&s[..s.len() - 2]).expect("正規表現でチェックしてるのであんりーちゃぶる(オーバーフローする場合があるが)")
 }
@@ -298,7 +476,8 @@ fn parse_integer(s: &str) -> Decimal128 {
     s.chars().fold(Decimal128::ZERO, |acc, c| acc * dec_radix + Decimal128::from(c.to_digit(radix as u32).expect("正規表現でチェックしてるのであんりーちゃぶる")))
 }
 
-fn parse_string_literal(s: &str) -> Option<String> {
+// \{と\}はf"..."の中でだけ使えるエスケープ
+fn parse_string_literal(s: &str, format_string: bool) -> Option<String> {
     let mut iter = s[1..s.len() - 1].chars().peekable();
     let mut result = String::with_capacity(s.len());
     while let Some(c) = iter.next() {
@@ -316,6 +495,7 @@ fn parse_string_literal(s: &str) -> Option<String> {
             Some('t') => result.push('\t'),
             Some('\\') => result.push('\\'),
             Some('\"') => result.push('\"'),
+            Some(c @ ('{' | '}')) if format_string => result.push(c),
             Some('x') => {
                 let c = iter.by_ref().take(2).fold(0, |acc, c| acc << 4 | c.to_digit(16).expect("事前にとーくないざの正規表現で確認してるのであんりーちゃぶる"));
                 result.push(char::from_u32(c).expect("16進2桁なのであんりーちゃぶる"));
@@ -338,7 +518,7 @@ mod tests {
     use kirl_common::dec::Decimal128;
     use tokenizer::Tokenize;
 
-    use crate::kirl_tokenizer::{get_tokenizer, CharacterPosition, Token};
+    use crate::kirl_tokenizer::{get_tokenizer, CharacterPosition, FormatStringPart, Token, TokenizeError};
 
     #[test]
     fn test_tokenize() {
@@ -486,10 +666,61 @@ test2 */
                 Ok(array![]),
             ]
         );
+        assert_eq!(tokenize("+= -="), vec![Ok(array![Token::AddAssign(new(0, 0)..new(0, 2))]), Ok(array![]), Ok(array![Token::SubAssign(new(0, 3)..new(0, 5))])]);
+        assert_eq!(tokenize("*= /= %="), vec![Ok(array![Token::MulAssign(new(0, 0)..new(0, 2))]), Ok(array![]), Ok(array![Token::DivAssign(new(0, 3)..new(0, 5))]), Ok(array![]), Ok(array![Token::RemAssign(new(0, 6)..new(0, 8))])]);
+        assert_eq!(tokenize(r#"f"abc""#), vec![Ok(array![Token::StringImmediate((new(0, 0)..new(0, 6), "abc".to_string()))])]);
+        assert_eq!(
+            tokenize(r#"f"a{x}b\{""#),
+            vec![Ok(array![Token::FormatString((
+                new(0, 0)..new(0, 10),
+                vec![FormatStringPart::Text((new(0, 0)..new(0, 4), "a".to_string())), FormatStringPart::Hole(vec![(new(0, 4), 'x')]), FormatStringPart::Text((new(0, 5)..new(0, 10), "b{".to_string()))]
+            ))])]
+        );
+        assert_eq!(
+            tokenize(r#"f"{"}"}""#),
+            vec![Ok(array![Token::FormatString((
+                new(0, 0)..new(0, 8),
+                vec![FormatStringPart::Text((new(0, 0)..new(0, 3), "".to_string())), FormatStringPart::Hole(vec![(new(0, 3), '"'), (new(0, 4), '}'), (new(0, 5), '"')]), FormatStringPart::Text((new(0, 6)..new(0, 8), "".to_string()))]
+            ))])]
+        );
+        assert_eq!(tokenize(r#"f"{a\b}""#)[0], Err(TokenizeError::UnsupportedFormatStringHole { raw: r#"f"{a\"#.to_string(), position: new(0, 0)..new(0, 5) }));
+        assert_eq!(
+            tokenize("0..10 a..=b"),
+            vec![
+                Ok(array![Token::NumberImmediate((new(0, 0)..new(0, 1), Decimal128::from(0)))]),
+                Ok(array![Token::DoubleDot(new(0, 1)..new(0, 3))]),
+                Ok(array![Token::NumberImmediate((new(0, 3)..new(0, 5), Decimal128::from(10)))]),
+                Ok(array![]),
+                Ok(array![Token::Identifier((new(0, 6)..new(0, 7), "a".to_string()))]),
+                Ok(array![Token::DoubleDotEquals(new(0, 7)..new(0, 10))]),
+                Ok(array![Token::Identifier((new(0, 10)..new(0, 11), "b".to_string()))]),
+            ]
+        );
+        assert_eq!(
+            tokenize("fn* yield fn *"),
+            vec![
+                Ok(array![Token::GeneratorFn(new(0, 0)..new(0, 3))]),
+                Ok(array![]),
+                Ok(array![Token::Yield(new(0, 4)..new(0, 9))]),
+                Ok(array![]),
+                Ok(array![Token::Fn(new(0, 10)..new(0, 12))]),
+                Ok(array![]),
+                Ok(array![Token::Mul(new(0, 13)..new(0, 14))]),
+            ]
+        );
+        assert_eq!(
+            tokenize("pub public"),
+            vec![
+                Ok(array![Token::Pub(new(0, 0)..new(0, 3))]),
+                Ok(array![]),
+                Ok(array![Token::Identifier((new(0, 4)..new(0, 10), "public".to_string()))]),
+            ]
+        );
         assert_eq!(tokenize("a"), vec![Ok(array![Token::Identifier((new(0, 0)..new(0, 1), "a".to_string()))])]);
         assert_eq!(tokenize("abc_123"), vec![Ok(array![Token::Identifier((new(0, 0)..new(0, 7), "abc_123".to_string()))])]);
         assert_eq!(tokenize("_abc_123"), vec![Ok(array![Token::Identifier((new(0, 0)..new(0, 8), "_abc_123".to_string()))])]);
         assert_ne!(tokenize("0_abc_123"), vec![Ok(array![Token::Identifier((new(0, 0)..new(0, 9), "0_abc_123".to_string()))])]);
+        assert_eq!(tokenize("'outer"), vec![Ok(array![Token::Label((new(0, 0)..new(0, 6), "outer".to_string()))])]);
 
         assert_eq!(tokenize("\"\n\""), vec![Ok(array![Token::StringImmediate((new(0, 0)..new(1, 1), "\n".to_string()))])]);
         assert_eq!(tokenize("\"\\\n\""), vec![Ok(array![Token::StringImmediate((new(0, 0)..new(1, 1), "".to_string()))])]);
@@ -8,7 +8,7 @@ use parser::{Parse, ParseError};
 use tokenizer::Tokenize;
 
 use crate::kirl_parser::{get_parser, KirlTopLevelStatement, Parser, Symbol};
-use crate::kirl_tokenizer::{get_tokenizer, TokenizeError, Tokenizer};
+use crate::kirl_tokenizer::{expand_format_string, get_tokenizer, TokenizeError, Tokenizer};
 
 pub mod kirl_parser;
 pub mod kirl_tokenizer;
@@ -123,6 +123,13 @@ impl KirlParser {
                     ArrayVec::new()
                 }
             })
+            .flat_map(|token| match expand_format_string(token, &self.tokenizer) {
+                Ok(tokens) => tokens,
+                Err(e) => {
+                    let _ = cell.set(e);
+                    Vec::new()
+                }
+            })
             .parse(&self.parser);
         cell.into_inner().map(KirlParseError::TokenizeError).map_or(Ok(()), Err).and_then(|_| match parse_result {
             Ok(Symbol::ValidKirlCode((_, code))) => Ok(code),
@@ -7,7 +7,7 @@ use kirl_common::typing::HIRType;
 use regex::Regex;
 use uuid::Uuid;
 
-use kirl_parser::kirl_parser::{Function, ImportPath, KirlTopLevelStatement, Statement, StatementItem, Struct};
+use kirl_parser::kirl_parser::{Enum, Function, ImportPath, KirlTopLevelStatement, Statement, StatementItem, Struct, TypeAlias};
 use kirl_parser::CharacterPosition;
 
 use crate::name_resolver::ResolvedItems;
@@ -27,18 +27,22 @@ pub struct WithImport<T> {
 pub struct KirlTopLevelItems {
     pub statements: Vec<Statement>,
     pub structs: Vec<WithImport<Struct>>,
+    pub enums: Vec<WithImport<Enum>>,
+    pub type_aliases: Vec<WithImport<TypeAlias>>,
     pub functions: Vec<WithImport<Function>>,
 }
 
 pub fn collect_top_level_item_with_imports(top_level_statements: Vec<KirlTopLevelStatement>) -> KirlTopLevelItems {
     let mut statements = Vec::new();
     let mut structs = Vec::new();
+    let mut enums = Vec::new();
+    let mut type_aliases = Vec::new();
     let mut functions = Vec::new();
     let mut imports = Vec::new();
     for statement in top_level_statements {
         match statement {
             KirlTopLevelStatement::Statement((_, normal_statement)) => {
-                if let StatementItem::Import(import) = &normal_statement.statement {
+                if let StatementItem::Import { path: import, .. } = &normal_statement.statement {
                     imports.push(import.clone());
                 }
                 statements.push(normal_statement);
@@ -49,9 +53,42 @@ pub fn collect_top_level_item_with_imports(top_level_statements: Vec<KirlTopLeve
             KirlTopLevelStatement::StructDefinition((_, struct_definition)) => {
                 structs.push(WithImport { import: ImportPath::List(imports.clone()), item: struct_definition });
             }
+            KirlTopLevelStatement::EnumDefinition((_, enum_definition)) => {
+                enums.push(WithImport { import: ImportPath::List(imports.clone()), item: enum_definition });
+            }
+            KirlTopLevelStatement::TypeAliasDefinition((_, type_alias)) => {
+                type_aliases.push(WithImport { import: ImportPath::List(imports.clone()), item: type_alias });
+            }
         }
     }
-    KirlTopLevelItems { statements, structs, functions }
+    KirlTopLevelItems { statements, structs, enums, type_aliases, functions }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct HIRStructDefinition {
+    pub generics_argument_count: usize,
+    pub members: BTreeMap<String, HIRType>,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum HIREnumVariant {
+    Tuple(Vec<HIRType>),
+    Struct(BTreeMap<String, HIRType>),
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct HIREnumDefinition {
+    pub generics_argument_count: usize,
+    pub variants: Vec<(String, HIREnumVariant)>,
+}
+
+// 型引数の制約は、その型の値だけを引数にして呼び出せなければならない関数として表す
+#[derive(Debug, PartialEq, Clone)]
+pub struct HIRGenericsBound<Reference> {
+    pub generics_argument: usize,
+    pub bound: String,
+    pub function: Variable<Reference>,
+    pub argument_count: usize,
 }
 
 #[derive(Debug, PartialEq, Clone)]
@@ -71,11 +108,12 @@ impl<Reference> From<HIRStatementList<Reference>> for Vec<HIRStatement<Reference
 
 #[derive(Debug, PartialEq, Clone)]
 pub enum HIRStatement<Reference> {
-    Binding { variable_id: usize, variable_type: HIRType, expression: HIRExpression<Reference> },
+    Binding { position: Range<CharacterPosition>, variable_id: usize, variable_type: HIRType, expression: HIRExpression<Reference> },
     Unreachable,
-    Return(Variable<Reference>),
+    Return(Range<CharacterPosition>, Variable<Reference>),
+    Yield(Variable<Reference>),
     Continue(Option<String>),
-    Break(Option<String>),
+    Break(Option<String>, Option<Variable<Reference>>),
 }
 
 #[derive(Debug, PartialEq, Clone)]
@@ -84,6 +122,8 @@ pub enum HIRExpression<Reference> {
     CallFunction {
         function: Variable<Reference>,
         arguments: Vec<Variable<Reference>>,
+        // forから作られた_nextの呼び出しか(型検査のエラーの出し分けに使う)
+        iterator_next: bool,
     },
     AccessVariable(Variable<Reference>),
     AccessMember {
@@ -105,18 +145,41 @@ pub enum HIRExpression<Reference> {
         condition: Variable<Reference>,
         then: (Vec<HIRStatement<Reference>>, Variable<Reference>),
         other: (Vec<HIRStatement<Reference>>, Variable<Reference>),
+        kind: IfLetKind,
     },
-    Loop(Vec<HIRStatement<Reference>>),
+    Loop(Option<String>, Vec<HIRStatement<Reference>>),
     Assign {
         variable: ReferenceAccess<Reference>,
         value: Variable<Reference>,
     },
-    // ConstructClosure,(TODO)
+    ConstructClosure {
+        captures: Vec<(usize, Variable<Reference>)>,
+        arguments: Vec<HIRType>,
+        return_type: HIRType,
+        body: Vec<HIRStatement<Reference>>,
+    },
+    // ジェネレータ関数の本体で、呼び出し時点の変数を引き継いで最初の_nextから実行される
+    ConstructGenerator {
+        item_type: HIRType,
+        body: Vec<HIRStatement<Reference>>,
+    },
     ConstructStruct(BTreeMap<String, Variable<Reference>>),
+    ConstructNamedStruct {
+        struct_type: HIRType,
+        members: BTreeMap<String, Variable<Reference>>,
+    },
     ConstructTuple(Vec<Variable<Reference>>),
     ConstructArray(Vec<Variable<Reference>>),
 }
 
+// IfLetがどの構文から作られたか(型検査のエラーの出し分けに使う)
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum IfLetKind {
+    IfLet,
+    MatchArm,
+    Try,
+}
+
 #[derive(Debug, PartialEq, Clone)]
 pub enum ReferenceAccess<Reference> {
     Variable(Variable<Reference>),
@@ -165,6 +228,91 @@ impl ToString for Variable<(Uuid, HIRType)> {
     }
 }
 
+fn apply_generics_type_argument(ty: HIRType, generics_type_arguments: &[HIRType]) -> HIRType {
+    ty.apply_generics_type_argument(generics_type_arguments).expect("型引数は関数の定義と同じ数だけ与えられる")
+}
+
+impl<Reference> Variable<Reference> {
+    fn apply_generics_type_argument(self, generics_type_arguments: &[HIRType]) -> Self {
+        match self {
+            Variable::Named(position, types, reference) => Variable::Named(position, types.into_iter().map(|ty| apply_generics_type_argument(ty, generics_type_arguments)).collect(), reference),
+            variable @ Variable::Unnamed(_) => variable,
+        }
+    }
+}
+
+impl<Reference> HIRExpression<Reference> {
+    fn apply_generics_type_argument(self, generics_type_arguments: &[HIRType]) -> Self {
+        let variable = |variable: Variable<Reference>| variable.apply_generics_type_argument(generics_type_arguments);
+        let statements = |statements: Vec<HIRStatement<Reference>>| statements.into_iter().map(|statement| statement.apply_generics_type_argument(generics_type_arguments)).collect::<Vec<_>>();
+        match self {
+            expression @ HIRExpression::Immediate(_) => expression,
+            HIRExpression::CallFunction { function, arguments, iterator_next } => HIRExpression::CallFunction { function: variable(function), arguments: arguments.into_iter().map(variable).collect(), iterator_next },
+            HIRExpression::AccessVariable(value) => HIRExpression::AccessVariable(variable(value)),
+            HIRExpression::AccessMember { variable: value, member } => HIRExpression::AccessMember { variable: variable(value), member },
+            HIRExpression::AccessTupleItem { variable: value, index } => HIRExpression::AccessTupleItem { variable: variable(value), index },
+            HIRExpression::If { condition, then: (then_statements, then_value), other: (other_statements, other_value) } => HIRExpression::If {
+                condition: variable(condition),
+                then: (statements(then_statements), variable(then_value)),
+                other: (statements(other_statements), variable(other_value)),
+            },
+            HIRExpression::IfLet { condition_binding, pattern_type, condition, then: (then_statements, then_value), other: (other_statements, other_value), kind } => HIRExpression::IfLet {
+                condition_binding,
+                pattern_type: apply_generics_type_argument(pattern_type, generics_type_arguments),
+                condition: variable(condition),
+                then: (statements(then_statements), variable(then_value)),
+                other: (statements(other_statements), variable(other_value)),
+                kind,
+            },
+            HIRExpression::Loop(label, body) => HIRExpression::Loop(label, statements(body)),
+            HIRExpression::Assign { variable: access, value } => HIRExpression::Assign {
+                variable: match access {
+                    ReferenceAccess::Variable(value) => ReferenceAccess::Variable(variable(value)),
+                    ReferenceAccess::TupleItem(value, index) => ReferenceAccess::TupleItem(variable(value), index),
+                    ReferenceAccess::Member(value, member) => ReferenceAccess::Member(variable(value), member),
+                },
+                value: variable(value),
+            },
+            HIRExpression::ConstructClosure { captures, arguments, return_type, body } => HIRExpression::ConstructClosure {
+                captures: captures.into_iter().map(|(id, value)| (id, variable(value))).collect(),
+                arguments: arguments.into_iter().map(|ty| apply_generics_type_argument(ty, generics_type_arguments)).collect(),
+                return_type: apply_generics_type_argument(return_type, generics_type_arguments),
+                body: statements(body),
+            },
+            HIRExpression::ConstructGenerator { item_type, body } => HIRExpression::ConstructGenerator {
+                item_type: apply_generics_type_argument(item_type, generics_type_arguments),
+                body: statements(body),
+            },
+            HIRExpression::ConstructStruct(members) => HIRExpression::ConstructStruct(members.into_iter().map(|(k, v)| (k, variable(v))).collect()),
+            HIRExpression::ConstructNamedStruct { struct_type, members } => HIRExpression::ConstructNamedStruct {
+                struct_type: apply_generics_type_argument(struct_type, generics_type_arguments),
+                members: members.into_iter().map(|(k, v)| (k, variable(v))).collect(),
+            },
+            HIRExpression::ConstructTuple(items) => HIRExpression::ConstructTuple(items.into_iter().map(variable).collect()),
+            HIRExpression::ConstructArray(items) => HIRExpression::ConstructArray(items.into_iter().map(variable).collect()),
+        }
+    }
+}
+
+impl<Reference> HIRStatement<Reference> {
+    // 型引数を持つ関数の本体を、具体的な型引数のもとでの本体にする
+    pub fn apply_generics_type_argument(self, generics_type_arguments: &[HIRType]) -> Self {
+        match self {
+            HIRStatement::Binding { position, variable_id, variable_type, expression } => HIRStatement::Binding {
+                position,
+                variable_id,
+                variable_type: apply_generics_type_argument(variable_type, generics_type_arguments),
+                expression: expression.apply_generics_type_argument(generics_type_arguments),
+            },
+            HIRStatement::Unreachable => HIRStatement::Unreachable,
+            HIRStatement::Return(position, value) => HIRStatement::Return(position, value.apply_generics_type_argument(generics_type_arguments)),
+            HIRStatement::Yield(value) => HIRStatement::Yield(value.apply_generics_type_argument(generics_type_arguments)),
+            HIRStatement::Continue(label) => HIRStatement::Continue(label),
+            HIRStatement::Break(label, value) => HIRStatement::Break(label, value.map(|value| value.apply_generics_type_argument(generics_type_arguments))),
+        }
+    }
+}
+
 #[derive(Debug, PartialEq, Clone)]
 enum StatementReachable {
     Reachable,
@@ -204,7 +352,7 @@ where
                 Immediate::Number(value) => format!("{}", value),
                 Immediate::String(value) => format!("{:?}", value),
             },
-            HIRExpression::CallFunction { function, arguments } => {
+            HIRExpression::CallFunction { function, arguments, .. } => {
                 let function = ToString::to_string(function);
                 let arguments = arguments.iter().map(ToString::to_string).reduce(|a, b| format!("{}, {}", a, b)).unwrap_or_default();
                 format!("{}({})", function, arguments)
@@ -228,7 +376,7 @@ where
                 result.push_str(&format!(" else {{\n{}\n}}", regex.replace_all(&other, "$1\t$2")));
                 result
             }
-            HIRExpression::IfLet { condition_binding, pattern_type, condition, then, other } => {
+            HIRExpression::IfLet { condition_binding, pattern_type, condition, then, other, .. } => {
                 let regex = Regex::new("(^|\n)(.)").unwrap();
                 let then_statements = then.0.iter().map(ToString::to_string).map(|stmt| format!("{}\n", stmt)).reduce(|a, b| format!("{}{}", a, b)).unwrap_or_default();
                 let then = format!("{}{}", then_statements, ToString::to_string(&then.1));
@@ -238,20 +386,37 @@ where
                 result.push_str(&format!(" else {{\n{}\n}}", regex.replace_all(&other, "$1\t$2")));
                 result
             }
-            HIRExpression::Loop(statements) => {
+            HIRExpression::Loop(label, statements) => {
                 let regex = Regex::new("(^|\n)(.)").unwrap();
                 let statements = statements.iter().map(ToString::to_string).map(|stmt| format!("{}\n", stmt)).reduce(|a, b| format!("{}{}", a, b)).unwrap_or_default();
-                format!("loop {{\n{}}}", regex.replace_all(&statements, "$1\t$2"))
+                let label = label.as_ref().map(|label| format!("{}: ", label)).unwrap_or_default();
+                format!("{}loop {{\n{}}}", label, regex.replace_all(&statements, "$1\t$2"))
             }
             HIRExpression::Assign { variable, value } => match variable {
                 ReferenceAccess::Variable(variable) => format!("{} = {}", ToString::to_string(variable), ToString::to_string(value)),
                 ReferenceAccess::TupleItem(variable, index) => format!("{}.{}{} = {}", ToString::to_string(variable), index, get_ordinal(*index), ToString::to_string(value)),
                 ReferenceAccess::Member(variable, member) => format!("{}.{} = {}", ToString::to_string(variable), member, ToString::to_string(value)),
             },
+            HIRExpression::ConstructClosure { captures, arguments, return_type, body } => {
+                let regex = Regex::new("(^|\n)(.)").unwrap();
+                let captures = captures.iter().map(|(id, variable)| format!("${} = {}", id, ToString::to_string(variable))).reduce(|a, b| format!("{}, {}", a, b)).unwrap_or_default();
+                let arguments = arguments.iter().map(ToString::to_string).reduce(|a, b| format!("{}, {}", a, b)).unwrap_or_default();
+                let statements = body.iter().map(ToString::to_string).map(|stmt| format!("{}\n", stmt)).reduce(|a, b| format!("{}{}", a, b)).unwrap_or_default();
+                format!("fn[{}]({}) -> {} {{\n{}}}", captures, arguments, ToString::to_string(return_type), regex.replace_all(&statements, "$1\t$2"))
+            }
+            HIRExpression::ConstructGenerator { item_type, body } => {
+                let regex = Regex::new("(^|\n)(.)").unwrap();
+                let statements = body.iter().map(ToString::to_string).map(|stmt| format!("{}\n", stmt)).reduce(|a, b| format!("{}{}", a, b)).unwrap_or_default();
+                format!("fn* -> {} {{\n{}}}", ToString::to_string(item_type), regex.replace_all(&statements, "$1\t$2"))
+            }
             HIRExpression::ConstructStruct(members) => {
                 let members = members.iter().map(|(name, value)| format!("{}: {}", name, ToString::to_string(value))).reduce(|a, b| format!("{}, {}", a, b)).unwrap_or_default();
                 format!("#{{{}}}", members)
             }
+            HIRExpression::ConstructNamedStruct { struct_type, members } => {
+                let members = members.iter().map(|(name, value)| format!("{}: {}", name, ToString::to_string(value))).reduce(|a, b| format!("{}, {}", a, b)).unwrap_or_default();
+                format!("{}#{{{}}}", ToString::to_string(struct_type), members)
+            }
             HIRExpression::ConstructTuple(items) => {
                 let items = items.iter().map(ToString::to_string).reduce(|a, b| format!("{}, {}", a, b)).unwrap_or_default();
                 format!("({})", items)
@@ -270,27 +435,29 @@ where
 {
     fn to_string(&self) -> String {
         match self {
-            HIRStatement::Binding { variable_id, variable_type, expression } => {
+            HIRStatement::Binding { variable_id, variable_type, expression, .. } => {
                 format!("let ${}: {} = {};", variable_id, ToString::to_string(variable_type), ToString::to_string(expression))
             }
             HIRStatement::Unreachable => "$unreachable".to_string(),
-            HIRStatement::Return(variable) => {
+            HIRStatement::Return(_, variable) => {
                 format!("return {};", ToString::to_string(variable))
             }
+            HIRStatement::Yield(variable) => {
+                format!("yield {};", ToString::to_string(variable))
+            }
             HIRStatement::Continue(label) => {
                 if let Some(label) = label {
-                    format!("continue {};", label)
+                    format!("continue '{};", label)
                 } else {
                     "continue;".to_string()
                 }
             }
-            HIRStatement::Break(label) => {
-                if let Some(label) = label {
-                    format!("break {};", label)
-                } else {
-                    "break;".to_string()
-                }
-            }
+            HIRStatement::Break(label, value) => match (label, value) {
+                (Some(label), Some(value)) => format!("break '{} {};", label, ToString::to_string(value)),
+                (Some(label), None) => format!("break '{};", label),
+                (None, Some(value)) => format!("break {};", ToString::to_string(value)),
+                (None, None) => "break;".to_string(),
+            },
         }
     }
 }
@@ -307,7 +474,7 @@ mod tests {
     use kirl_parser::{CharacterPosition, KirlParser};
 
     use crate::syntax_tree_to_hir::{analysis_statements, SearchPaths};
-    use crate::{collect_top_level_item_with_imports, statements_to_string, HIRExpression, HIRStatement, HIRType, Immediate, KirlTopLevelItems, ReferenceAccess, Variable};
+    use crate::{collect_top_level_item_with_imports, statements_to_string, HIRExpression, HIRStatement, HIRType, IfLetKind, Immediate, KirlTopLevelItems, ReferenceAccess, Variable};
 
     #[test]
     fn test_analysis_statements() {
@@ -364,120 +531,146 @@ mod tests {
             statements,
             vec![
                 HIRStatement::Binding {
+                    position: CharacterPosition { line: 4, column: 33 }..CharacterPosition { line: 4, column: 35 },
                     variable_id: 0,
                     variable_type: HIRType::Infer,
                     expression: HIRExpression::Immediate(Immediate::Number(10.into())),
                 },
                 HIRStatement::Binding {
+                    position: CharacterPosition { line: 4, column: 12 }..CharacterPosition { line: 4, column: 36 },
                     variable_id: 1,
                     variable_type: HIRType::Named { path: vec!["Number".to_string()], generics_arguments: vec![] },
                     expression: HIRExpression::AccessVariable(Variable::Unnamed(0)),
                 },
                 HIRStatement::Binding {
+                    position: CharacterPosition { line: 5, column: 58 }..CharacterPosition { line: 5, column: 59 },
                     variable_id: 2,
                     variable_type: HIRType::Infer,
                     expression: HIRExpression::Immediate(Immediate::Number(1.into())),
                 },
                 HIRStatement::Binding {
+                    position: CharacterPosition { line: 5, column: 49 }..CharacterPosition { line: 5, column: 59 },
                     variable_id: 3,
                     variable_type: HIRType::Infer,
                     expression: HIRExpression::CallFunction {
                         function: Variable::Named(CharacterPosition { line: 5, column: 55 }..CharacterPosition { line: 5, column: 58 }, Vec::new(), SearchPaths(vec![vec!["_add".to_string()]])),
                         arguments: vec![Variable::Unnamed(1), Variable::Unnamed(2)],
+                        iterator_next: false,
                     },
                 },
                 HIRStatement::Binding {
+                    position: CharacterPosition { line: 5, column: 30 }..CharacterPosition { line: 5, column: 60 },
                     variable_id: 4,
                     variable_type: HIRType::Infer,
                     expression: HIRExpression::CallFunction {
                         function: Variable::Named(CharacterPosition { line: 5, column: 30 }..CharacterPosition { line: 5, column: 41 }, Vec::new(), SearchPaths(vec![vec!["array".to_string(), "fill".to_string()], vec!["std".to_string(), "array".to_string(), "fill".to_string()]])),
                         arguments: vec![Variable::Named(CharacterPosition { line: 5, column: 42 }..CharacterPosition { line: 5, column: 47 }, Vec::new(), SearchPaths(vec![vec!["false".to_string()]])), Variable::Unnamed(3)],
+                        iterator_next: false,
                     },
                 },
                 HIRStatement::Binding {
+                    position: CharacterPosition { line: 5, column: 12 }..CharacterPosition { line: 5, column: 61 },
                     variable_id: 5,
                     variable_type: HIRType::Array(Box::new(HIRType::Named { path: vec!["bool".to_string()], generics_arguments: vec![] })),
                     expression: HIRExpression::AccessVariable(Variable::Unnamed(4)),
                 },
                 HIRStatement::Binding {
+                    position: CharacterPosition { line: 6, column: 20 }..CharacterPosition { line: 6, column: 21 },
                     variable_id: 6,
                     variable_type: HIRType::Infer,
                     expression: HIRExpression::Immediate(Immediate::Number(4.into())),
                 },
                 HIRStatement::Binding {
+                    position: CharacterPosition { line: 6, column: 12 }..CharacterPosition { line: 6, column: 22 },
                     variable_id: 7,
                     variable_type: HIRType::Infer,
                     expression: HIRExpression::AccessVariable(Variable::Unnamed(6)),
                 },
                 HIRStatement::Binding {
+                    position: CharacterPosition { line: 7, column: 12 }..CharacterPosition { line: 10, column: 13 },
                     variable_id: 18,
                     variable_type: HIRType::Tuple(vec![]),
-                    expression: HIRExpression::Loop(vec![
+                    expression: HIRExpression::Loop(None, vec![
                         HIRStatement::Binding {
+                            position: CharacterPosition { line: 7, column: 31 }..CharacterPosition { line: 7, column: 32 },
                             variable_id: 8,
                             variable_type: HIRType::Infer,
                             expression: HIRExpression::Immediate(Immediate::Number(1.into())),
                         },
                         HIRStatement::Binding {
+                            position: CharacterPosition { line: 7, column: 22 }..CharacterPosition { line: 7, column: 32 },
                             variable_id: 9,
                             variable_type: HIRType::Infer,
                             expression: HIRExpression::CallFunction {
                                 function: Variable::Named(CharacterPosition { line: 7, column: 28 }..CharacterPosition { line: 7, column: 31 }, Vec::new(), SearchPaths(vec![vec!["_add".to_string()]])),
                                 arguments: vec![Variable::Unnamed(1), Variable::Unnamed(8)],
+                                iterator_next: false,
                             },
                         },
                         HIRStatement::Binding {
+                            position: CharacterPosition { line: 7, column: 18 }..CharacterPosition { line: 7, column: 32 },
                             variable_id: 10,
                             variable_type: HIRType::Infer,
                             expression: HIRExpression::CallFunction {
                                 function: Variable::Named(CharacterPosition { line: 7, column: 19 }..CharacterPosition { line: 7, column: 22 }, Vec::new(), SearchPaths(vec![vec!["_gt".to_string()]])),
                                 arguments: vec![Variable::Unnamed(9), Variable::Unnamed(7)],
+                                iterator_next: false,
                             },
                         },
                         HIRStatement::Binding {
+                            position: CharacterPosition { line: 7, column: 18 }..CharacterPosition { line: 7, column: 32 },
                             variable_id: 11,
                             variable_type: HIRType::Infer,
                             expression: HIRExpression::CallFunction {
                                 function: Variable::Named(CharacterPosition { line: 7, column: 18 }..CharacterPosition { line: 7, column: 32 }, Vec::new(), SearchPaths(vec![vec!["_not".to_string()]])),
                                 arguments: vec![Variable::Unnamed(10)],
+                                iterator_next: false,
                             },
                         },
                         HIRStatement::Binding {
+                            position: CharacterPosition { line: 7, column: 12 }..CharacterPosition { line: 10, column: 13 },
                             variable_id: 12,
                             variable_type: HIRType::Tuple(vec![]),
                             expression: HIRExpression::ConstructTuple(vec![]),
                         },
                         HIRStatement::Binding {
+                            position: CharacterPosition { line: 7, column: 12 }..CharacterPosition { line: 10, column: 13 },
                             variable_id: 13,
                             variable_type: HIRType::Tuple(vec![]),
                             expression: HIRExpression::If {
                                 condition: Variable::Unnamed(11),
-                                then: (vec![HIRStatement::Break(None)], Variable::Unnamed(12)),
+                                then: (vec![HIRStatement::Break(None, None)], Variable::Unnamed(12)),
                                 other: (vec![], Variable::Unnamed(12)),
                             },
                         },
                         HIRStatement::Binding {
+                            position: CharacterPosition { line: 8, column: 16 }..CharacterPosition { line: 8, column: 29 },
                             variable_id: 14,
                             variable_type: HIRType::Infer,
                             expression: HIRExpression::CallFunction {
                                 function: Variable::Named(CharacterPosition { line: 8, column: 20 }..CharacterPosition { line: 8, column: 21 }, Vec::new(), SearchPaths(vec![vec!["_set_item".to_string()]])),
                                 arguments: vec![Variable::Unnamed(5), Variable::Unnamed(7), Variable::Named(CharacterPosition { line: 8, column: 25 }..CharacterPosition { line: 8, column: 29 }, Vec::new(), SearchPaths(vec![vec!["true".to_string()]]))],
+                                iterator_next: false,
                             },
                         },
                         HIRStatement::Binding {
+                            position: CharacterPosition { line: 9, column: 24 }..CharacterPosition { line: 9, column: 25 },
                             variable_id: 15,
                             variable_type: HIRType::Infer,
                             expression: HIRExpression::Immediate(Immediate::Number(2.into())),
                         },
                         HIRStatement::Binding {
+                            position: CharacterPosition { line: 9, column: 20 }..CharacterPosition { line: 9, column: 25 },
                             variable_id: 16,
                             variable_type: HIRType::Infer,
                             expression: HIRExpression::CallFunction {
                                 function: Variable::Named(CharacterPosition { line: 9, column: 21 }..CharacterPosition { line: 9, column: 24 }, Vec::new(), SearchPaths(vec![vec!["_add".to_string()]])),
                                 arguments: vec![Variable::Unnamed(7), Variable::Unnamed(15)],
+                                iterator_next: false,
                             },
                         },
                         HIRStatement::Binding {
+                            position: CharacterPosition { line: 9, column: 16 }..CharacterPosition { line: 9, column: 25 },
                             variable_id: 17,
                             variable_type: HIRType::Infer,
                             expression: HIRExpression::Assign {
@@ -488,66 +681,80 @@ mod tests {
                     ]),
                 },
                 HIRStatement::Binding {
+                    position: CharacterPosition { line: 12, column: 20 }..CharacterPosition { line: 12, column: 21 },
                     variable_id: 19,
                     variable_type: HIRType::Infer,
                     expression: HIRExpression::Immediate(Immediate::Number(3.into())),
                 },
                 HIRStatement::Binding {
+                    position: CharacterPosition { line: 12, column: 12 }..CharacterPosition { line: 12, column: 22 },
                     variable_id: 20,
                     variable_type: HIRType::Infer,
                     expression: HIRExpression::AccessVariable(Variable::Unnamed(19)),
                 },
                 HIRStatement::Binding {
+                    position: CharacterPosition { line: 13, column: 12 }..CharacterPosition { line: 23, column: 13 },
                     variable_id: 45,
                     variable_type: HIRType::Tuple(vec![]),
-                    expression: HIRExpression::Loop(vec![
+                    expression: HIRExpression::Loop(None, vec![
                         HIRStatement::Binding {
+                            position: CharacterPosition { line: 13, column: 18 }..CharacterPosition { line: 13, column: 29 },
                             variable_id: 21,
                             variable_type: HIRType::Infer,
                             expression: HIRExpression::CallFunction {
                                 function: Variable::Named(CharacterPosition { line: 13, column: 19 }..CharacterPosition { line: 13, column: 23 }, Vec::new(), SearchPaths(vec![vec!["_gt".to_string()]])),
                                 arguments: vec![Variable::Unnamed(20), Variable::Unnamed(1)],
+                                iterator_next: false,
                             },
                         },
                         HIRStatement::Binding {
+                            position: CharacterPosition { line: 13, column: 18 }..CharacterPosition { line: 13, column: 29 },
                             variable_id: 22,
                             variable_type: HIRType::Infer,
                             expression: HIRExpression::CallFunction {
                                 function: Variable::Named(CharacterPosition { line: 13, column: 19 }..CharacterPosition { line: 13, column: 23 }, Vec::new(), SearchPaths(vec![vec!["_not".to_string()]])),
                                 arguments: vec![Variable::Unnamed(21)],
+                                iterator_next: false,
                             },
                         },
                         HIRStatement::Binding {
+                            position: CharacterPosition { line: 13, column: 18 }..CharacterPosition { line: 13, column: 29 },
                             variable_id: 23,
                             variable_type: HIRType::Infer,
                             expression: HIRExpression::CallFunction {
                                 function: Variable::Named(CharacterPosition { line: 13, column: 18 }..CharacterPosition { line: 13, column: 29 }, Vec::new(), SearchPaths(vec![vec!["_not".to_string()]])),
                                 arguments: vec![Variable::Unnamed(22)],
+                                iterator_next: false,
                             },
                         },
                         HIRStatement::Binding {
+                            position: CharacterPosition { line: 13, column: 12 }..CharacterPosition { line: 23, column: 13 },
                             variable_id: 24,
                             variable_type: HIRType::Tuple(vec![]),
                             expression: HIRExpression::ConstructTuple(vec![]),
                         },
                         HIRStatement::Binding {
+                            position: CharacterPosition { line: 13, column: 12 }..CharacterPosition { line: 23, column: 13 },
                             variable_id: 25,
                             variable_type: HIRType::Tuple(vec![]),
                             expression: HIRExpression::If {
                                 condition: Variable::Unnamed(23),
-                                then: (vec![HIRStatement::Break(None)], Variable::Unnamed(24)),
+                                then: (vec![HIRStatement::Break(None, None)], Variable::Unnamed(24)),
                                 other: (vec![], Variable::Unnamed(24)),
                             },
                         },
                         HIRStatement::Binding {
+                            position: CharacterPosition { line: 14, column: 19 }..CharacterPosition { line: 14, column: 25 },
                             variable_id: 26,
                             variable_type: HIRType::Infer,
                             expression: HIRExpression::CallFunction {
                                 function: Variable::Named(CharacterPosition { line: 14, column: 23 }..CharacterPosition { line: 14, column: 24 }, Vec::new(), SearchPaths(vec![vec!["_get_item".to_string()]])),
                                 arguments: vec![Variable::Unnamed(5), Variable::Unnamed(20)],
+                                iterator_next: false,
                             },
                         },
                         HIRStatement::Binding {
+                            position: CharacterPosition { line: 14, column: 16 }..CharacterPosition { line: 14, column: 39 },
                             variable_id: 29,
                             variable_type: HIRType::Infer,
                             expression: HIRExpression::If {
@@ -556,6 +763,7 @@ mod tests {
                                     vec![
                                         HIRStatement::Continue(None),
                                         HIRStatement::Binding {
+                                            position: CharacterPosition { line: 14, column: 26 }..CharacterPosition { line: 14, column: 39 },
                                             variable_id: 27,
                                             variable_type: HIRType::Tuple(vec![]),
                                             expression: HIRExpression::ConstructTuple(vec![]),
@@ -565,6 +773,7 @@ mod tests {
                                 ),
                                 other: (
                                     vec![HIRStatement::Binding {
+                                        position: CharacterPosition { line: 14, column: 16 }..CharacterPosition { line: 14, column: 39 },
                                         variable_id: 28,
                                         variable_type: HIRType::Tuple(vec![]),
                                         expression: HIRExpression::ConstructTuple(vec![]),
@@ -574,77 +783,94 @@ mod tests {
                             },
                         },
                         HIRStatement::Binding {
+                            position: CharacterPosition { line: 16, column: 28 }..CharacterPosition { line: 16, column: 33 },
                             variable_id: 30,
                             variable_type: HIRType::Infer,
                             expression: HIRExpression::CallFunction {
                                 function: Variable::Named(CharacterPosition { line: 16, column: 29 }..CharacterPosition { line: 16, column: 32 }, Vec::new(), SearchPaths(vec![vec!["_mul".to_string()]])),
                                 arguments: vec![Variable::Unnamed(20), Variable::Unnamed(20)],
+                                iterator_next: false,
                             },
                         },
                         HIRStatement::Binding {
+                            position: CharacterPosition { line: 16, column: 20 }..CharacterPosition { line: 16, column: 34 },
                             variable_id: 31,
                             variable_type: HIRType::Infer,
                             expression: HIRExpression::AccessVariable(Variable::Unnamed(30)),
                         },
                         HIRStatement::Binding {
+                            position: CharacterPosition { line: 17, column: 20 }..CharacterPosition { line: 20, column: 21 },
                             variable_id: 40,
                             variable_type: HIRType::Tuple(vec![]),
-                            expression: HIRExpression::Loop(vec![
+                            expression: HIRExpression::Loop(None, vec![
                                 HIRStatement::Binding {
+                                    position: CharacterPosition { line: 17, column: 26 }..CharacterPosition { line: 17, column: 37 },
                                     variable_id: 32,
                                     variable_type: HIRType::Infer,
                                     expression: HIRExpression::CallFunction {
                                         function: Variable::Named(CharacterPosition { line: 17, column: 27 }..CharacterPosition { line: 17, column: 31 }, Vec::new(), SearchPaths(vec![vec!["_gt".to_string()]])),
                                         arguments: vec![Variable::Unnamed(31), Variable::Unnamed(1)],
+                                        iterator_next: false,
                                     },
                                 },
                                 HIRStatement::Binding {
+                                    position: CharacterPosition { line: 17, column: 26 }..CharacterPosition { line: 17, column: 37 },
                                     variable_id: 33,
                                     variable_type: HIRType::Infer,
                                     expression: HIRExpression::CallFunction {
                                         function: Variable::Named(CharacterPosition { line: 17, column: 27 }..CharacterPosition { line: 17, column: 31 }, Vec::new(), SearchPaths(vec![vec!["_not".to_string()]])),
                                         arguments: vec![Variable::Unnamed(32)],
+                                        iterator_next: false,
                                     },
                                 },
                                 HIRStatement::Binding {
+                                    position: CharacterPosition { line: 17, column: 26 }..CharacterPosition { line: 17, column: 37 },
                                     variable_id: 34,
                                     variable_type: HIRType::Infer,
                                     expression: HIRExpression::CallFunction {
                                         function: Variable::Named(CharacterPosition { line: 17, column: 26 }..CharacterPosition { line: 17, column: 37 }, Vec::new(), SearchPaths(vec![vec!["_not".to_string()]])),
                                         arguments: vec![Variable::Unnamed(33)],
+                                        iterator_next: false,
                                     },
                                 },
                                 HIRStatement::Binding {
+                                    position: CharacterPosition { line: 17, column: 20 }..CharacterPosition { line: 20, column: 21 },
                                     variable_id: 35,
                                     variable_type: HIRType::Tuple(vec![]),
                                     expression: HIRExpression::ConstructTuple(vec![]),
                                 },
                                 HIRStatement::Binding {
+                                    position: CharacterPosition { line: 17, column: 20 }..CharacterPosition { line: 20, column: 21 },
                                     variable_id: 36,
                                     variable_type: HIRType::Tuple(vec![]),
                                     expression: HIRExpression::If {
                                         condition: Variable::Unnamed(34),
-                                        then: (vec![HIRStatement::Break(None)], Variable::Unnamed(35)),
+                                        then: (vec![HIRStatement::Break(None, None)], Variable::Unnamed(35)),
                                         other: (vec![], Variable::Unnamed(35)),
                                     },
                                 },
                                 HIRStatement::Binding {
+                                    position: CharacterPosition { line: 18, column: 24 }..CharacterPosition { line: 18, column: 37 },
                                     variable_id: 37,
                                     variable_type: HIRType::Infer,
                                     expression: HIRExpression::CallFunction {
                                         function: Variable::Named(CharacterPosition { line: 18, column: 28 }..CharacterPosition { line: 18, column: 29 }, Vec::new(), SearchPaths(vec![vec!["_set_item".to_string()]])),
                                         arguments: vec![Variable::Unnamed(5), Variable::Unnamed(31), Variable::Named(CharacterPosition { line: 18, column: 33 }..CharacterPosition { line: 18, column: 37 }, Vec::new(), SearchPaths(vec![vec!["true".to_string()]]))],
+                                        iterator_next: false,
                                     },
                                 },
                                 HIRStatement::Binding {
+                                    position: CharacterPosition { line: 19, column: 28 }..CharacterPosition { line: 19, column: 33 },
                                     variable_id: 38,
                                     variable_type: HIRType::Infer,
                                     expression: HIRExpression::CallFunction {
                                         function: Variable::Named(CharacterPosition { line: 19, column: 29 }..CharacterPosition { line: 19, column: 32 }, Vec::new(), SearchPaths(vec![vec!["_add".to_string()]])),
                                         arguments: vec![Variable::Unnamed(31), Variable::Unnamed(20)],
+                                        iterator_next: false,
                                     },
                                 },
                                 HIRStatement::Binding {
+                                    position: CharacterPosition { line: 19, column: 24 }..CharacterPosition { line: 19, column: 33 },
                                     variable_id: 39,
                                     variable_type: HIRType::Infer,
                                     expression: HIRExpression::Assign {
@@ -655,24 +881,29 @@ mod tests {
                             ]),
                         },
                         HIRStatement::Binding {
+                            position: CharacterPosition { line: 15, column: 16 }..CharacterPosition { line: 21, column: 17 },
                             variable_id: 41,
                             variable_type: HIRType::Tuple(vec![]),
                             expression: HIRExpression::ConstructTuple(vec![]),
                         },
                         HIRStatement::Binding {
+                            position: CharacterPosition { line: 22, column: 24 }..CharacterPosition { line: 22, column: 25 },
                             variable_id: 42,
                             variable_type: HIRType::Infer,
                             expression: HIRExpression::Immediate(Immediate::Number(2.into())),
                         },
                         HIRStatement::Binding {
+                            position: CharacterPosition { line: 22, column: 20 }..CharacterPosition { line: 22, column: 25 },
                             variable_id: 43,
                             variable_type: HIRType::Infer,
                             expression: HIRExpression::CallFunction {
                                 function: Variable::Named(CharacterPosition { line: 22, column: 21 }..CharacterPosition { line: 22, column: 24 }, Vec::new(), SearchPaths(vec![vec!["_add".to_string()]])),
                                 arguments: vec![Variable::Unnamed(20), Variable::Unnamed(42)],
+                                iterator_next: false,
                             },
                         },
                         HIRStatement::Binding {
+                            position: CharacterPosition { line: 22, column: 16 }..CharacterPosition { line: 22, column: 25 },
                             variable_id: 44,
                             variable_type: HIRType::Infer,
                             expression: HIRExpression::Assign {
@@ -683,22 +914,27 @@ mod tests {
                     ]),
                 },
                 HIRStatement::Binding {
+                    position: CharacterPosition { line: 25, column: 16 }..CharacterPosition { line: 25, column: 27 },
                     variable_id: 46,
                     variable_type: HIRType::Infer,
                     expression: HIRExpression::CallFunction {
                         function: Variable::Named(CharacterPosition { line: 25, column: 20 }..CharacterPosition { line: 25, column: 26 }, Vec::new(), SearchPaths(vec![vec!["_get_item".to_string()]])),
                         arguments: vec![Variable::Unnamed(5), Variable::Unnamed(1)],
+                        iterator_next: false,
                     },
                 },
                 HIRStatement::Binding {
+                    position: CharacterPosition { line: 25, column: 15 }..CharacterPosition { line: 25, column: 27 },
                     variable_id: 47,
                     variable_type: HIRType::Infer,
                     expression: HIRExpression::CallFunction {
                         function: Variable::Named(CharacterPosition { line: 25, column: 16 }..CharacterPosition { line: 25, column: 27 }, Vec::new(), SearchPaths(vec![vec!["_not".to_string()]])),
                         arguments: vec![Variable::Unnamed(46)],
+                        iterator_next: false,
                     },
                 },
                 HIRStatement::Binding {
+                    position: CharacterPosition { line: 25, column: 12 }..CharacterPosition { line: 29, column: 13 },
                     variable_id: 54,
                     variable_type: HIRType::Infer,
                     expression: HIRExpression::If {
@@ -706,19 +942,23 @@ mod tests {
                         then: (
                             vec![
                                 HIRStatement::Binding {
+                                    position: CharacterPosition { line: 26, column: 24 }..CharacterPosition { line: 26, column: 31 },
                                     variable_id: 48,
                                     variable_type: HIRType::Infer,
                                     expression: HIRExpression::Immediate(Immediate::String("prime".to_string())),
                                 },
                                 HIRStatement::Binding {
+                                    position: CharacterPosition { line: 26, column: 16 }..CharacterPosition { line: 26, column: 32 },
                                     variable_id: 49,
                                     variable_type: HIRType::Infer,
                                     expression: HIRExpression::CallFunction {
                                         function: Variable::Named(CharacterPosition { line: 26, column: 16 }..CharacterPosition { line: 26, column: 23 }, Vec::new(), SearchPaths(vec![vec!["println".to_string()], vec!["std".to_string(), "io".to_string(), "println".to_string()]])),
                                         arguments: vec![Variable::Unnamed(48)],
+                                        iterator_next: false,
                                     },
                                 },
                                 HIRStatement::Binding {
+                                    position: CharacterPosition { line: 25, column: 28 }..CharacterPosition { line: 27, column: 13 },
                                     variable_id: 50,
                                     variable_type: HIRType::Tuple(vec![]),
                                     expression: HIRExpression::ConstructTuple(vec![]),
@@ -729,19 +969,23 @@ mod tests {
                         other: (
                             vec![
                                 HIRStatement::Binding {
+                                    position: CharacterPosition { line: 28, column: 24 }..CharacterPosition { line: 28, column: 35 },
                                     variable_id: 51,
                                     variable_type: HIRType::Infer,
                                     expression: HIRExpression::Immediate(Immediate::String("not prime".to_string())),
                                 },
                                 HIRStatement::Binding {
+                                    position: CharacterPosition { line: 28, column: 16 }..CharacterPosition { line: 28, column: 36 },
                                     variable_id: 52,
                                     variable_type: HIRType::Infer,
                                     expression: HIRExpression::CallFunction {
                                         function: Variable::Named(CharacterPosition { line: 28, column: 16 }..CharacterPosition { line: 28, column: 23 }, Vec::new(), SearchPaths(vec![vec!["println".to_string()], vec!["std".to_string(), "io".to_string(), "println".to_string()]])),
                                         arguments: vec![Variable::Unnamed(51)],
+                                        iterator_next: false,
                                     },
                                 },
                                 HIRStatement::Binding {
+                                    position: CharacterPosition { line: 27, column: 19 }..CharacterPosition { line: 29, column: 13 },
                                     variable_id: 53,
                                     variable_type: HIRType::Tuple(vec![]),
                                     expression: HIRExpression::ConstructTuple(vec![]),
@@ -761,24 +1005,29 @@ mod tests {
             statements,
             vec![
                 HIRStatement::Binding {
+                    position: CharacterPosition { line: 3, column: 27 }..CharacterPosition { line: 3, column: 39 },
                     variable_id: 0,
                     variable_type: HIRType::Infer,
                     expression: HIRExpression::CallFunction {
                         function: Variable::Named(CharacterPosition { line: 3, column: 27 }..CharacterPosition { line: 3, column: 37 }, Vec::new(), SearchPaths(vec![vec!["graph".to_string(), "get".to_string()]])),
                         arguments: vec![],
+                        iterator_next: false,
                     },
                 },
                 HIRStatement::Binding {
+                    position: CharacterPosition { line: 3, column: 12 }..CharacterPosition { line: 3, column: 40 },
                     variable_id: 1,
                     variable_type: HIRType::AnonymousStruct(vec![("nodes".to_string(), HIRType::Infer)].into_iter().collect()),
                     expression: HIRExpression::AccessVariable(Variable::Unnamed(0)),
                 },
                 HIRStatement::Binding {
+                    position: CharacterPosition { line: 3, column: 12 }..CharacterPosition { line: 3, column: 40 },
                     variable_id: 2,
                     variable_type: HIRType::Infer,
                     expression: HIRExpression::AccessMember { variable: Variable::Unnamed(1), member: "nodes".to_string() },
                 },
                 HIRStatement::Binding {
+                    position: CharacterPosition { line: 4, column: 20 }..CharacterPosition { line: 4, column: 32 },
                     variable_id: 3,
                     variable_type: HIRType::Infer,
                     expression: HIRExpression::CallFunction {
@@ -788,19 +1037,23 @@ mod tests {
                             SearchPaths(vec![vec!["deque".to_string(), "new".to_string()], vec!["std".to_string(), "collections".to_string(), "deque".to_string(), "new".to_string()]]),
                         ),
                         arguments: vec![],
+                        iterator_next: false,
                     },
                 },
                 HIRStatement::Binding {
+                    position: CharacterPosition { line: 4, column: 12 }..CharacterPosition { line: 4, column: 33 },
                     variable_id: 4,
                     variable_type: HIRType::Infer,
                     expression: HIRExpression::AccessVariable(Variable::Unnamed(3)),
                 },
                 HIRStatement::Binding {
+                    position: CharacterPosition { line: 5, column: 31 }..CharacterPosition { line: 5, column: 32 },
                     variable_id: 5,
                     variable_type: HIRType::Infer,
                     expression: HIRExpression::Immediate(Immediate::Number(0.into())),
                 },
                 HIRStatement::Binding {
+                    position: CharacterPosition { line: 5, column: 12 }..CharacterPosition { line: 5, column: 33 },
                     variable_id: 6,
                     variable_type: HIRType::Infer,
                     expression: HIRExpression::CallFunction {
@@ -810,13 +1063,16 @@ mod tests {
                             SearchPaths(vec![vec!["deque".to_string(), "push_back".to_string()], vec!["std".to_string(), "collections".to_string(), "deque".to_string(), "push_back".to_string()]]),
                         ),
                         arguments: vec![Variable::Unnamed(4), Variable::Unnamed(5)],
+                        iterator_next: false,
                     },
                 },
                 HIRStatement::Binding {
+                    position: CharacterPosition { line: 6, column: 12 }..CharacterPosition { line: 11, column: 13 },
                     variable_id: 24,
                     variable_type: HIRType::Tuple(vec![]),
-                    expression: HIRExpression::Loop(vec![
+                    expression: HIRExpression::Loop(None, vec![
                         HIRStatement::Binding {
+                            position: CharacterPosition { line: 6, column: 45 }..CharacterPosition { line: 6, column: 65 },
                             variable_id: 7,
                             variable_type: HIRType::Infer,
                             expression: HIRExpression::CallFunction {
@@ -826,9 +1082,11 @@ mod tests {
                                     SearchPaths(vec![vec!["deque".to_string(), "pop_front".to_string()], vec!["std".to_string(), "collections".to_string(), "deque".to_string(), "pop_front".to_string()]]),
                                 ),
                                 arguments: vec![Variable::Unnamed(4)],
+                                iterator_next: false,
                             },
                         },
                         HIRStatement::Binding {
+                            position: CharacterPosition { line: 6, column: 12 }..CharacterPosition { line: 11, column: 13 },
                             variable_id: 10,
                             variable_type: HIRType::AnonymousStruct(vec![("value".to_string(), HIRType::Infer)].into_iter().collect()),
                             expression: HIRExpression::IfLet {
@@ -838,8 +1096,9 @@ mod tests {
                                 then: (vec![], Variable::Unnamed(8)),
                                 other: (
                                     vec![
-                                        HIRStatement::Break(None),
+                                        HIRStatement::Break(None, None),
                                         HIRStatement::Binding {
+                                            position: CharacterPosition { line: 6, column: 12 }..CharacterPosition { line: 11, column: 13 },
                                             variable_id: 9,
                                             variable_type: HIRType::Tuple(vec![]),
                                             expression: HIRExpression::ConstructTuple(vec![]),
@@ -847,66 +1106,82 @@ mod tests {
                                     ],
                                     Variable::Unnamed(9)
                                 ),
+                                kind: IfLetKind::IfLet,
                             },
                         },
                         HIRStatement::Binding {
+                            position: CharacterPosition { line: 6, column: 12 }..CharacterPosition { line: 11, column: 13 },
                             variable_id: 11,
                             variable_type: HIRType::Infer,
                             expression: HIRExpression::AccessMember { variable: Variable::Unnamed(10), member: "value".to_string() },
                         },
                         HIRStatement::Binding {
+                            position: CharacterPosition { line: 7, column: 24 }..CharacterPosition { line: 7, column: 41 },
                             variable_id: 12,
                             variable_type: HIRType::Infer,
                             expression: HIRExpression::CallFunction {
                                 function: Variable::Named(CharacterPosition { line: 7, column: 30 }..CharacterPosition { line: 7, column: 40 }, Vec::new(), SearchPaths(vec![vec!["_get_item".to_string()]])),
                                 arguments: vec![Variable::Unnamed(2), Variable::Unnamed(11)],
+                                iterator_next: false,
                             },
                         },
                         HIRStatement::Binding {
+                            position: CharacterPosition { line: 7, column: 16 }..CharacterPosition { line: 7, column: 42 },
                             variable_id: 13,
                             variable_type: HIRType::Infer,
                             expression: HIRExpression::CallFunction {
                                 function: Variable::Named(CharacterPosition { line: 7, column: 16 }..CharacterPosition { line: 7, column: 23 }, Vec::new(), SearchPaths(vec![vec!["println".to_string()], vec!["std".to_string(), "io".to_string(), "println".to_string()]])),
                                 arguments: vec![Variable::Unnamed(12)],
+                                iterator_next: false,
                             },
                         },
                         HIRStatement::Binding {
+                            position: CharacterPosition { line: 8, column: 29 }..CharacterPosition { line: 8, column: 46 },
                             variable_id: 14,
                             variable_type: HIRType::Infer,
                             expression: HIRExpression::CallFunction {
                                 function: Variable::Named(CharacterPosition { line: 8, column: 35 }..CharacterPosition { line: 8, column: 45 }, Vec::new(), SearchPaths(vec![vec!["_get_item".to_string()]])),
                                 arguments: vec![Variable::Unnamed(2), Variable::Unnamed(11)],
+                                iterator_next: false,
                             },
                         },
                         HIRStatement::Binding {
+                            position: CharacterPosition { line: 8, column: 29 }..CharacterPosition { line: 8, column: 64 },
                             variable_id: 15,
                             variable_type: HIRType::Infer,
                             expression: HIRExpression::CallFunction {
                                 function: Variable::Named(CharacterPosition { line: 8, column: 47 }..CharacterPosition { line: 8, column: 62 }, Vec::new(), SearchPaths(vec![vec!["graph".to_string(), "children".to_string()]])),
                                 arguments: vec![Variable::Unnamed(14)],
+                                iterator_next: false,
                             },
                         },
                         HIRStatement::Binding {
+                            position: CharacterPosition { line: 8, column: 29 }..CharacterPosition { line: 8, column: 64 },
                             variable_id: 16,
                             variable_type: HIRType::Infer,
                             expression: HIRExpression::CallFunction {
                                 function: Variable::Named(CharacterPosition { line: 8, column: 29 }..CharacterPosition { line: 8, column: 64 }, Vec::new(), SearchPaths(vec![vec!["_iterator".to_string()]])),
                                 arguments: vec![Variable::Unnamed(15)],
+                                iterator_next: false,
                             },
                         },
                         HIRStatement::Binding {
+                            position: CharacterPosition { line: 8, column: 16 }..CharacterPosition { line: 10, column: 17 },
                             variable_id: 23,
                             variable_type: HIRType::Tuple(vec![]),
-                            expression: HIRExpression::Loop(vec![
+                            expression: HIRExpression::Loop(None, vec![
                                 HIRStatement::Binding {
+                                    position: CharacterPosition { line: 8, column: 29 }..CharacterPosition { line: 8, column: 64 },
                                     variable_id: 17,
                                     variable_type: HIRType::Or(vec![HIRType::Tuple(vec![]), HIRType::AnonymousStruct(vec![("value".to_string(), HIRType::Infer)].into_iter().collect())]),
                                     expression: HIRExpression::CallFunction {
                                         function: Variable::Named(CharacterPosition { line: 8, column: 29 }..CharacterPosition { line: 8, column: 64 }, Vec::new(), SearchPaths(vec![vec!["_next".to_string()]])),
                                         arguments: vec![Variable::Unnamed(16)],
+                                        iterator_next: true,
                                     },
                                 },
                                 HIRStatement::Binding {
+                                    position: CharacterPosition { line: 8, column: 16 }..CharacterPosition { line: 10, column: 17 },
                                     variable_id: 21,
                                     variable_type: HIRType::Infer,
                                     expression: HIRExpression::IfLet {
@@ -915,6 +1190,7 @@ mod tests {
                                         condition: Variable::Unnamed(17),
                                         then: (
                                             vec![HIRStatement::Binding {
+                                                position: CharacterPosition { line: 8, column: 16 }..CharacterPosition { line: 10, column: 17 },
                                                 variable_id: 19,
                                                 variable_type: HIRType::Infer,
                                                 expression: HIRExpression::AccessMember { variable: Variable::Unnamed(18), member: "value".to_string() },
@@ -923,8 +1199,9 @@ mod tests {
                                         ),
                                         other: (
                                             vec![
-                                                HIRStatement::Break(None),
+                                                HIRStatement::Break(None, None),
                                                 HIRStatement::Binding {
+                                                    position: CharacterPosition { line: 8, column: 16 }..CharacterPosition { line: 10, column: 17 },
                                                     variable_id: 20,
                                                     variable_type: HIRType::Tuple(vec![]),
                                                     expression: HIRExpression::ConstructTuple(vec![]),
@@ -932,9 +1209,11 @@ mod tests {
                                             ],
                                             Variable::Unnamed(20)
                                         ),
+                                        kind: IfLetKind::IfLet,
                                     },
                                 },
                                 HIRStatement::Binding {
+                                    position: CharacterPosition { line: 9, column: 20 }..CharacterPosition { line: 9, column: 45 },
                                     variable_id: 22,
                                     variable_type: HIRType::Infer,
                                     expression: HIRExpression::CallFunction {
@@ -944,6 +1223,7 @@ mod tests {
                                             SearchPaths(vec![vec!["deque".to_string(), "push_back".to_string()], vec!["std".to_string(), "collections".to_string(), "deque".to_string(), "push_back".to_string()]]),
                                         ),
                                         arguments: vec![Variable::Unnamed(4), Variable::Unnamed(21)],
+                                        iterator_next: false,
                                     },
                                 },
                             ]),
@@ -1,22 +1,58 @@
 use std::collections::{BTreeMap, HashMap};
+use std::iter;
 
 use kirl_common::typing::HIRType;
 use uuid::Uuid;
 
 use crate::syntax_tree_to_hir::SearchPaths;
-use crate::{HIRExpression, HIRStatement, ReferenceAccess, Variable};
+use crate::type_checker::{DecisionTypeError, DecisionTypeResult};
+use crate::{HIRExpression, HIRGenericsBound, HIRStatement, ReferenceAccess, Variable};
 
 #[derive(Debug, Clone, Ord, PartialOrd, Eq, PartialEq, Default)]
 pub struct ResolvedItems(pub(crate) SearchPaths, pub(crate) Vec<(Vec<String>, Uuid, HIRType)>);
 
+impl ResolvedItems {
+    // 名前解決で試したパスの候補
+    pub fn search_paths(&self) -> &[Vec<String>] {
+        &self.0 .0
+    }
+
+    pub fn candidates(&self) -> &[(Vec<String>, Uuid, HIRType)] {
+        &self.1
+    }
+}
+
 pub trait KirlNameResolver {
     fn resolve(&mut self, full_path: &[String]) -> Vec<(Uuid, HIRType)>;
+    fn resolve_type(&mut self, _full_path: &[String]) -> Option<Vec<String>> {
+        None
+    }
+    // 別の型に展開される名前(列挙型など)を、型引数の数と展開後の型として返す
+    fn resolve_type_alias(&mut self, _full_path: &[String]) -> Option<(usize, HIRType)> {
+        None
+    }
+    // 1要素の名前を、importせずに見えるものから探す(見つかったもののパスも返す)
+    fn resolve_prelude(&mut self, _name: &str) -> Vec<(Vec<String>, Uuid, HIRType)> {
+        Vec::new()
+    }
 }
 
 impl<R: ?Sized + KirlNameResolver> KirlNameResolver for &mut R {
     fn resolve(&mut self, full_path: &[String]) -> Vec<(Uuid, HIRType)> {
         (*self).resolve(full_path)
     }
+
+    fn resolve_type(&mut self, full_path: &[String]) -> Option<Vec<String>> {
+        (*self).resolve_type(full_path)
+    }
+
+    fn resolve_type_alias(&mut self, full_path: &[String]) -> Option<(usize, HIRType)> {
+        (*self).resolve_type_alias(full_path)
+    }
+
+    fn resolve_prelude(&mut self, name: &str) -> Vec<(Vec<String>, Uuid, HIRType)> {
+        (*self).resolve_prelude(name)
+    }
 }
 
 impl<R1> KirlNameResolver for (R1,)
@@ -27,6 +63,21 @@ where
         let (r1,) = self;
         r1.resolve(full_path)
     }
+
+    fn resolve_type(&mut self, full_path: &[String]) -> Option<Vec<String>> {
+        let (r1,) = self;
+        r1.resolve_type(full_path)
+    }
+
+    fn resolve_type_alias(&mut self, full_path: &[String]) -> Option<(usize, HIRType)> {
+        let (r1,) = self;
+        r1.resolve_type_alias(full_path)
+    }
+
+    fn resolve_prelude(&mut self, name: &str) -> Vec<(Vec<String>, Uuid, HIRType)> {
+        let (r1,) = self;
+        r1.resolve_prelude(name)
+    }
 }
 
 impl<R1, R2> KirlNameResolver for (R1, R2)
@@ -40,6 +91,23 @@ where
         result.extend(r2.resolve(full_path));
         result
     }
+
+    fn resolve_type(&mut self, full_path: &[String]) -> Option<Vec<String>> {
+        let (r1, r2) = self;
+        r1.resolve_type(full_path).or_else(|| r2.resolve_type(full_path))
+    }
+
+    fn resolve_type_alias(&mut self, full_path: &[String]) -> Option<(usize, HIRType)> {
+        let (r1, r2) = self;
+        r1.resolve_type_alias(full_path).or_else(|| r2.resolve_type_alias(full_path))
+    }
+
+    fn resolve_prelude(&mut self, name: &str) -> Vec<(Vec<String>, Uuid, HIRType)> {
+        let (r1, r2) = self;
+        let mut result = r1.resolve_prelude(name);
+        result.extend(r2.resolve_prelude(name));
+        result
+    }
 }
 
 impl<R1, R2, R3> KirlNameResolver for (R1, R2, R3)
@@ -55,6 +123,24 @@ where
         result.extend(r3.resolve(full_path));
         result
     }
+
+    fn resolve_type(&mut self, full_path: &[String]) -> Option<Vec<String>> {
+        let (r1, r2, r3) = self;
+        r1.resolve_type(full_path).or_else(|| r2.resolve_type(full_path)).or_else(|| r3.resolve_type(full_path))
+    }
+
+    fn resolve_type_alias(&mut self, full_path: &[String]) -> Option<(usize, HIRType)> {
+        let (r1, r2, r3) = self;
+        r1.resolve_type_alias(full_path).or_else(|| r2.resolve_type_alias(full_path)).or_else(|| r3.resolve_type_alias(full_path))
+    }
+
+    fn resolve_prelude(&mut self, name: &str) -> Vec<(Vec<String>, Uuid, HIRType)> {
+        let (r1, r2, r3) = self;
+        let mut result = r1.resolve_prelude(name);
+        result.extend(r2.resolve_prelude(name));
+        result.extend(r3.resolve_prelude(name));
+        result
+    }
 }
 
 impl<R1, R2, R3, R4> KirlNameResolver for (R1, R2, R3, R4)
@@ -72,6 +158,25 @@ where
         result.extend(r4.resolve(full_path));
         result
     }
+
+    fn resolve_type(&mut self, full_path: &[String]) -> Option<Vec<String>> {
+        let (r1, r2, r3, r4) = self;
+        r1.resolve_type(full_path).or_else(|| r2.resolve_type(full_path)).or_else(|| r3.resolve_type(full_path)).or_else(|| r4.resolve_type(full_path))
+    }
+
+    fn resolve_type_alias(&mut self, full_path: &[String]) -> Option<(usize, HIRType)> {
+        let (r1, r2, r3, r4) = self;
+        r1.resolve_type_alias(full_path).or_else(|| r2.resolve_type_alias(full_path)).or_else(|| r3.resolve_type_alias(full_path)).or_else(|| r4.resolve_type_alias(full_path))
+    }
+
+    fn resolve_prelude(&mut self, name: &str) -> Vec<(Vec<String>, Uuid, HIRType)> {
+        let (r1, r2, r3, r4) = self;
+        let mut result = r1.resolve_prelude(name);
+        result.extend(r2.resolve_prelude(name));
+        result.extend(r3.resolve_prelude(name));
+        result.extend(r4.resolve_prelude(name));
+        result
+    }
 }
 
 impl<R1, R2, R3, R4, R5> KirlNameResolver for (R1, R2, R3, R4, R5)
@@ -91,35 +196,87 @@ where
         result.extend(r5.resolve(full_path));
         result
     }
+
+    fn resolve_type(&mut self, full_path: &[String]) -> Option<Vec<String>> {
+        let (r1, r2, r3, r4, r5) = self;
+        r1.resolve_type(full_path).or_else(|| r2.resolve_type(full_path)).or_else(|| r3.resolve_type(full_path)).or_else(|| r4.resolve_type(full_path)).or_else(|| r5.resolve_type(full_path))
+    }
+
+    fn resolve_type_alias(&mut self, full_path: &[String]) -> Option<(usize, HIRType)> {
+        let (r1, r2, r3, r4, r5) = self;
+        r1.resolve_type_alias(full_path).or_else(|| r2.resolve_type_alias(full_path)).or_else(|| r3.resolve_type_alias(full_path)).or_else(|| r4.resolve_type_alias(full_path)).or_else(|| r5.resolve_type_alias(full_path))
+    }
+
+    fn resolve_prelude(&mut self, name: &str) -> Vec<(Vec<String>, Uuid, HIRType)> {
+        let (r1, r2, r3, r4, r5) = self;
+        let mut result = r1.resolve_prelude(name);
+        result.extend(r2.resolve_prelude(name));
+        result.extend(r3.resolve_prelude(name));
+        result.extend(r4.resolve_prelude(name));
+        result.extend(r5.resolve_prelude(name));
+        result
+    }
 }
 
 impl<R: KirlNameResolver> KirlNameResolver for [R] {
     fn resolve(&mut self, full_path: &[String]) -> Vec<(Uuid, HIRType)> {
         self.iter_mut().flat_map(|resolver| resolver.resolve(full_path)).collect()
     }
+
+    fn resolve_type(&mut self, full_path: &[String]) -> Option<Vec<String>> {
+        self.iter_mut().find_map(|resolver| resolver.resolve_type(full_path))
+    }
+
+    fn resolve_type_alias(&mut self, full_path: &[String]) -> Option<(usize, HIRType)> {
+        self.iter_mut().find_map(|resolver| resolver.resolve_type_alias(full_path))
+    }
+
+    fn resolve_prelude(&mut self, name: &str) -> Vec<(Vec<String>, Uuid, HIRType)> {
+        self.iter_mut().flat_map(|resolver| resolver.resolve_prelude(name)).collect()
+    }
 }
 
 impl<R: KirlNameResolver> KirlNameResolver for HashMap<String, R> {
     fn resolve(&mut self, full_path: &[String]) -> Vec<(Uuid, HIRType)> {
         full_path.first().and_then(|key| self.get_mut(key)).map(|resolver| resolver.resolve(&full_path[1..])).unwrap_or_default()
     }
+
+    fn resolve_type(&mut self, full_path: &[String]) -> Option<Vec<String>> {
+        let (key, path) = full_path.split_first()?;
+        let path = self.get_mut(key)?.resolve_type(path)?;
+        Some(iter::once(key.clone()).chain(path).collect())
+    }
+
+    fn resolve_prelude(&mut self, name: &str) -> Vec<(Vec<String>, Uuid, HIRType)> {
+        self.iter_mut().flat_map(|(key, resolver)| resolver.resolve_prelude(name).into_iter().map(move |(path, id, ty)| (iter::once(key.clone()).chain(path).collect(), id, ty))).collect()
+    }
 }
 
 impl<R: KirlNameResolver> KirlNameResolver for BTreeMap<String, R> {
     fn resolve(&mut self, full_path: &[String]) -> Vec<(Uuid, HIRType)> {
         full_path.first().and_then(|key| self.get_mut(key)).map(|resolver| resolver.resolve(&full_path[1..])).unwrap_or_default()
     }
+
+    fn resolve_type(&mut self, full_path: &[String]) -> Option<Vec<String>> {
+        let (key, path) = full_path.split_first()?;
+        let path = self.get_mut(key)?.resolve_type(path)?;
+        Some(iter::once(key.clone()).chain(path).collect())
+    }
+
+    fn resolve_prelude(&mut self, name: &str) -> Vec<(Vec<String>, Uuid, HIRType)> {
+        self.iter_mut().flat_map(|(key, resolver)| resolver.resolve_prelude(name).into_iter().map(move |(path, id, ty)| (iter::once(key.clone()).chain(path).collect(), id, ty))).collect()
+    }
 }
 
 trait Resolvable {
     type ResolveResult;
-    fn resolve(self, resolver: &mut impl KirlNameResolver) -> Self::ResolveResult;
+    fn resolve(self, resolver: &mut impl KirlNameResolver) -> DecisionTypeResult<Self::ResolveResult>;
     fn all_reference(&self) -> Vec<&[String]>;
 }
 
 impl<T: Resolvable> Resolvable for Vec<T> {
     type ResolveResult = Vec<T::ResolveResult>;
-    fn resolve(self, resolver: &mut impl KirlNameResolver) -> Self::ResolveResult {
+    fn resolve(self, resolver: &mut impl KirlNameResolver) -> DecisionTypeResult<Self::ResolveResult> {
         self.into_iter().map(|v| v.resolve(resolver)).collect()
     }
 
@@ -130,9 +287,9 @@ impl<T: Resolvable> Resolvable for Vec<T> {
 
 impl<T: Resolvable, U: Resolvable> Resolvable for (T, U) {
     type ResolveResult = (T::ResolveResult, U::ResolveResult);
-    fn resolve(self, resolver: &mut impl KirlNameResolver) -> Self::ResolveResult {
+    fn resolve(self, resolver: &mut impl KirlNameResolver) -> DecisionTypeResult<Self::ResolveResult> {
         let (t, u) = self;
-        (t.resolve(resolver), u.resolve(resolver))
+        Ok((t.resolve(resolver)?, u.resolve(resolver)?))
     }
 
     fn all_reference(&self) -> Vec<&[String]> {
@@ -143,21 +300,110 @@ impl<T: Resolvable, U: Resolvable> Resolvable for (T, U) {
     }
 }
 
+// 定義やimportなしで使える組み込みの型の名前
+const BUILTIN_TYPE_NAMES: &[&str] = &["Number", "String", "Bool"];
+
+fn is_builtin_type(path: &[String]) -> bool {
+    matches!(path, [name] if BUILTIN_TYPE_NAMES.contains(&name.as_str()))
+}
+
+impl Resolvable for HIRType {
+    type ResolveResult = HIRType;
+    fn resolve(self, resolver: &mut impl KirlNameResolver) -> DecisionTypeResult<Self::ResolveResult> {
+        Ok(match self {
+            ty @ (HIRType::Infer | HIRType::Unreachable | HIRType::GenericsTypeArgument(_)) => ty,
+            HIRType::Named { path, generics_arguments } => {
+                let mut generics_arguments = generics_arguments.resolve(resolver)?;
+                match resolver.resolve_type_alias(&path) {
+                    Some((generics_argument_count, ty)) => {
+                        // 型引数の足りない分は推論に任せる
+                        generics_arguments.resize(generics_argument_count, HIRType::Infer);
+                        ty.apply_generics_type_argument(&generics_arguments).expect("all generics arguments are given")
+                    }
+                    None => match resolver.resolve_type(&path) {
+                        Some(path) => HIRType::Named { path, generics_arguments },
+                        None if is_builtin_type(&path) => HIRType::Named { path, generics_arguments },
+                        None => return Err(DecisionTypeError::UnknownStruct { path }),
+                    },
+                }
+            }
+            HIRType::Tuple(items) => HIRType::Tuple(items.resolve(resolver)?),
+            HIRType::Array(item) => HIRType::Array(Box::new(item.resolve(resolver)?)),
+            HIRType::Function { arguments, result } => HIRType::Function {
+                arguments: arguments.resolve(resolver)?,
+                result: Box::new(result.resolve(resolver)?),
+            },
+            HIRType::AnonymousStruct(members) => HIRType::AnonymousStruct(members.into_iter().map(|(k, ty)| Ok((k, ty.resolve(resolver)?))).collect::<DecisionTypeResult<_>>()?),
+            HIRType::Or(items) => HIRType::Or(items.resolve(resolver)?),
+            // 再帰的な型は型の別名の展開で作られ、すでに解決済みである
+            ty @ (HIRType::Recursive { .. } | HIRType::RecursiveReference(_)) => ty,
+        })
+    }
+
+    fn all_reference(&self) -> Vec<&[String]> {
+        match self {
+            HIRType::Infer | HIRType::Unreachable | HIRType::GenericsTypeArgument(_) | HIRType::RecursiveReference(_) => Vec::new(),
+            HIRType::Named { path, generics_arguments } => {
+                let mut result = vec![path.as_slice()];
+                result.extend(generics_arguments.all_reference());
+                result
+            }
+            HIRType::Tuple(items) | HIRType::Or(items) => items.all_reference(),
+            HIRType::Array(item) => item.all_reference(),
+            HIRType::Function { arguments, result } => {
+                let mut references = arguments.all_reference();
+                references.extend(result.all_reference());
+                references
+            }
+            HIRType::AnonymousStruct(members) => members.values().flat_map(Resolvable::all_reference).collect(),
+            HIRType::Recursive { body, .. } => body.all_reference(),
+        }
+    }
+}
+
+// 関数は引数の型が同じものだけ、関数以外は名前が同じなら上書きする
+fn shadows(ty: &HIRType, prelude_type: &HIRType) -> bool {
+    match (ty, prelude_type) {
+        (HIRType::Function { arguments, .. }, HIRType::Function { arguments: prelude_arguments, .. }) => arguments == prelude_arguments,
+        _ => true,
+    }
+}
+
 impl Resolvable for Variable<SearchPaths> {
     type ResolveResult = Variable<ResolvedItems>;
-    fn resolve(self, resolver: &mut impl KirlNameResolver) -> Self::ResolveResult {
-        match self {
+    fn resolve(self, resolver: &mut impl KirlNameResolver) -> DecisionTypeResult<Self::ResolveResult> {
+        Ok(match self {
             Variable::Named(range, types, paths) => {
-                let reference = ResolvedItems(paths.clone(), paths.0.into_iter().flat_map(|path| [path.clone()].into_iter().cycle().zip(resolver.resolve(&path)).map(|(path, (id, ty))| (path, id, ty))).collect());
-                Variable::Named(range, types, reference)
+                let mut candidates: Vec<(Vec<String>, Uuid, HIRType)> = Vec::new();
+                for path in &paths.0 {
+                    for (id, ty) in resolver.resolve(path) {
+                        // 同じものに複数のパスから辿り着くことがあるので重複は除く
+                        if candidates.iter().all(|(_, candidate_id, _)| *candidate_id != id) {
+                            candidates.push((path.clone(), id, ty));
+                        }
+                    }
+                }
+                // preludeの候補は、importや定義で同じ引数の型のものが見えていなければ一緒にオーバーロードの候補にする
+                if let Some([name]) = paths.0.first().map(Vec::as_slice) {
+                    for (path, id, ty) in resolver.resolve_prelude(name) {
+                        if candidates.iter().all(|(_, candidate_id, candidate_type)| *candidate_id != id && !shadows(candidate_type, &ty)) {
+                            candidates.push((path, id, ty));
+                        }
+                    }
+                }
+                Variable::Named(range, types.resolve(resolver)?, ResolvedItems(paths, candidates))
             }
             Variable::Unnamed(id) => Variable::Unnamed(id),
-        }
+        })
     }
 
     fn all_reference(&self) -> Vec<&[String]> {
         match self {
-            Variable::Named(_, _, SearchPaths(paths)) => paths.iter().map(Vec::as_slice).collect(),
+            Variable::Named(_, types, SearchPaths(paths)) => {
+                let mut result: Vec<_> = paths.iter().map(Vec::as_slice).collect();
+                result.extend(types.all_reference());
+                result
+            }
             Variable::Unnamed(_) => Vec::new(),
         }
     }
@@ -165,12 +411,12 @@ impl Resolvable for Variable<SearchPaths> {
 
 impl Resolvable for ReferenceAccess<SearchPaths> {
     type ResolveResult = ReferenceAccess<ResolvedItems>;
-    fn resolve(self, resolver: &mut impl KirlNameResolver) -> Self::ResolveResult {
-        match self {
-            ReferenceAccess::Variable(variable) => ReferenceAccess::Variable(variable.resolve(resolver)),
-            ReferenceAccess::TupleItem(variable, index) => ReferenceAccess::TupleItem(variable.resolve(resolver), index),
-            ReferenceAccess::Member(variable, member) => ReferenceAccess::Member(variable.resolve(resolver), member),
-        }
+    fn resolve(self, resolver: &mut impl KirlNameResolver) -> DecisionTypeResult<Self::ResolveResult> {
+        Ok(match self {
+            ReferenceAccess::Variable(variable) => ReferenceAccess::Variable(variable.resolve(resolver)?),
+            ReferenceAccess::TupleItem(variable, index) => ReferenceAccess::TupleItem(variable.resolve(resolver)?, index),
+            ReferenceAccess::Member(variable, member) => ReferenceAccess::Member(variable.resolve(resolver)?, member),
+        })
     }
 
     fn all_reference(&self) -> Vec<&[String]> {
@@ -184,40 +430,53 @@ impl Resolvable for ReferenceAccess<SearchPaths> {
 
 impl Resolvable for HIRExpression<SearchPaths> {
     type ResolveResult = HIRExpression<ResolvedItems>;
-    fn resolve(self, resolver: &mut impl KirlNameResolver) -> Self::ResolveResult {
-        match self {
+    fn resolve(self, resolver: &mut impl KirlNameResolver) -> DecisionTypeResult<Self::ResolveResult> {
+        Ok(match self {
             HIRExpression::Immediate(value) => HIRExpression::Immediate(value),
-            HIRExpression::CallFunction { function, arguments } => HIRExpression::CallFunction {
-                function: function.resolve(resolver),
-                arguments: arguments.resolve(resolver),
+            HIRExpression::CallFunction { function, arguments, iterator_next } => HIRExpression::CallFunction {
+                function: function.resolve(resolver)?,
+                arguments: arguments.resolve(resolver)?,
+                iterator_next,
             },
-            HIRExpression::AccessVariable(variable) => HIRExpression::AccessVariable(variable.resolve(resolver)),
-            HIRExpression::AccessTupleItem { variable, index } => HIRExpression::AccessTupleItem { variable: variable.resolve(resolver), index },
-            HIRExpression::AccessMember { variable, member } => HIRExpression::AccessMember { variable: variable.resolve(resolver), member },
+            HIRExpression::AccessVariable(variable) => HIRExpression::AccessVariable(variable.resolve(resolver)?),
+            HIRExpression::AccessTupleItem { variable, index } => HIRExpression::AccessTupleItem { variable: variable.resolve(resolver)?, index },
+            HIRExpression::AccessMember { variable, member } => HIRExpression::AccessMember { variable: variable.resolve(resolver)?, member },
             HIRExpression::If { condition, then, other } => HIRExpression::If {
-                condition: condition.resolve(resolver),
-                then: then.resolve(resolver),
-                other: other.resolve(resolver),
+                condition: condition.resolve(resolver)?,
+                then: then.resolve(resolver)?,
+                other: other.resolve(resolver)?,
             },
-            HIRExpression::IfLet { condition_binding, pattern_type, condition, then, other } => HIRExpression::IfLet {
+            HIRExpression::IfLet { condition_binding, pattern_type, condition, then, other, kind } => HIRExpression::IfLet {
                 condition_binding,
-                pattern_type,
-                condition: condition.resolve(resolver),
-                then: then.resolve(resolver),
-                other: other.resolve(resolver),
+                pattern_type: pattern_type.resolve(resolver)?,
+                condition: condition.resolve(resolver)?,
+                then: then.resolve(resolver)?,
+                other: other.resolve(resolver)?,
+                kind,
             },
-            HIRExpression::Loop(statements) => HIRExpression::Loop(statements.resolve(resolver)),
-            HIRExpression::Assign { variable, value } => HIRExpression::Assign { variable: variable.resolve(resolver), value: value.resolve(resolver) },
-            HIRExpression::ConstructStruct(members) => HIRExpression::ConstructStruct(members.into_iter().map(|(k, v)| (k, v.resolve(resolver))).collect()),
-            HIRExpression::ConstructTuple(members) => HIRExpression::ConstructTuple(members.resolve(resolver)),
-            HIRExpression::ConstructArray(members) => HIRExpression::ConstructArray(members.resolve(resolver)),
-        }
+            HIRExpression::Loop(label, statements) => HIRExpression::Loop(label, statements.resolve(resolver)?),
+            HIRExpression::Assign { variable, value } => HIRExpression::Assign { variable: variable.resolve(resolver)?, value: value.resolve(resolver)? },
+            HIRExpression::ConstructClosure { captures, arguments, return_type, body } => HIRExpression::ConstructClosure {
+                captures: captures.into_iter().map(|(id, variable)| Ok((id, variable.resolve(resolver)?))).collect::<DecisionTypeResult<_>>()?,
+                arguments: arguments.resolve(resolver)?,
+                return_type: return_type.resolve(resolver)?,
+                body: body.resolve(resolver)?,
+            },
+            HIRExpression::ConstructGenerator { item_type, body } => HIRExpression::ConstructGenerator { item_type: item_type.resolve(resolver)?, body: body.resolve(resolver)? },
+            HIRExpression::ConstructStruct(members) => HIRExpression::ConstructStruct(members.into_iter().map(|(k, v)| Ok((k, v.resolve(resolver)?))).collect::<DecisionTypeResult<_>>()?),
+            HIRExpression::ConstructNamedStruct { struct_type, members } => HIRExpression::ConstructNamedStruct {
+                struct_type: struct_type.resolve(resolver)?,
+                members: members.into_iter().map(|(k, v)| Ok((k, v.resolve(resolver)?))).collect::<DecisionTypeResult<_>>()?,
+            },
+            HIRExpression::ConstructTuple(members) => HIRExpression::ConstructTuple(members.resolve(resolver)?),
+            HIRExpression::ConstructArray(members) => HIRExpression::ConstructArray(members.resolve(resolver)?),
+        })
     }
 
     fn all_reference(&self) -> Vec<&[String]> {
         match self {
             HIRExpression::Immediate(_) => Vec::new(),
-            HIRExpression::CallFunction { function, arguments } => {
+            HIRExpression::CallFunction { function, arguments, .. } => {
                 let mut result = function.all_reference();
                 result.extend(arguments.all_reference());
                 result
@@ -231,19 +490,37 @@ impl Resolvable for HIRExpression<SearchPaths> {
                 result.extend(other.all_reference());
                 result
             }
-            HIRExpression::IfLet { condition, then, other, .. } => {
-                let mut result = condition.all_reference();
+            HIRExpression::IfLet { pattern_type, condition, then, other, .. } => {
+                let mut result = pattern_type.all_reference();
+                result.extend(condition.all_reference());
                 result.extend(then.all_reference());
                 result.extend(other.all_reference());
                 result
             }
-            HIRExpression::Loop(statements) => statements.all_reference(),
+            HIRExpression::Loop(_, statements) => statements.all_reference(),
             HIRExpression::Assign { variable, value } => {
                 let mut result = variable.all_reference();
                 result.extend(value.all_reference());
                 result
             }
+            HIRExpression::ConstructClosure { captures, arguments, return_type, body } => {
+                let mut result: Vec<_> = captures.iter().flat_map(|(_, variable)| variable.all_reference()).collect();
+                result.extend(arguments.all_reference());
+                result.extend(return_type.all_reference());
+                result.extend(body.all_reference());
+                result
+            }
+            HIRExpression::ConstructGenerator { item_type, body } => {
+                let mut result = item_type.all_reference();
+                result.extend(body.all_reference());
+                result
+            }
             HIRExpression::ConstructStruct(members) => members.values().flat_map(Resolvable::all_reference).collect(),
+            HIRExpression::ConstructNamedStruct { struct_type, members } => {
+                let mut result = struct_type.all_reference();
+                result.extend(members.values().flat_map(Resolvable::all_reference));
+                result
+            }
             HIRExpression::ConstructTuple(items) => items.all_reference(),
             HIRExpression::ConstructArray(items) => items.all_reference(),
         }
@@ -252,31 +529,70 @@ impl Resolvable for HIRExpression<SearchPaths> {
 
 impl Resolvable for HIRStatement<SearchPaths> {
     type ResolveResult = HIRStatement<ResolvedItems>;
-    fn resolve(self, resolver: &mut impl KirlNameResolver) -> Self::ResolveResult {
-        match self {
-            HIRStatement::Binding { variable_id, variable_type, expression } => HIRStatement::Binding { variable_id, variable_type, expression: expression.resolve(resolver) },
+    fn resolve(self, resolver: &mut impl KirlNameResolver) -> DecisionTypeResult<Self::ResolveResult> {
+        Ok(match self {
+            HIRStatement::Binding { position, variable_id, variable_type, expression } => HIRStatement::Binding {
+                position,
+                variable_id,
+                variable_type: variable_type.resolve(resolver)?,
+                expression: expression.resolve(resolver)?,
+            },
             HIRStatement::Unreachable => HIRStatement::Unreachable,
-            HIRStatement::Return(value) => HIRStatement::Return(value.resolve(resolver)),
+            HIRStatement::Return(position, value) => HIRStatement::Return(position, value.resolve(resolver)?),
+            HIRStatement::Yield(value) => HIRStatement::Yield(value.resolve(resolver)?),
             HIRStatement::Continue(label) => HIRStatement::Continue(label),
-            HIRStatement::Break(label) => HIRStatement::Break(label),
-        }
+            HIRStatement::Break(label, value) => HIRStatement::Break(label, value.map(|value| value.resolve(resolver)).transpose()?),
+        })
     }
 
     fn all_reference(&self) -> Vec<&[String]> {
         match self {
-            HIRStatement::Binding { expression, .. } => expression.all_reference(),
+            HIRStatement::Binding { variable_type, expression, .. } => {
+                let mut result = variable_type.all_reference();
+                result.extend(expression.all_reference());
+                result
+            }
             HIRStatement::Unreachable => Vec::new(),
-            HIRStatement::Return(value) => value.all_reference(),
+            HIRStatement::Return(_, value) => value.all_reference(),
+            HIRStatement::Yield(value) => value.all_reference(),
             HIRStatement::Continue(_) => Vec::new(),
-            HIRStatement::Break(_) => Vec::new(),
+            HIRStatement::Break(_, value) => value.as_ref().map_or_else(Vec::new, Resolvable::all_reference),
         }
     }
 }
 
-pub fn resolve_statements(statements: Vec<HIRStatement<SearchPaths>>, resolver: &mut impl KirlNameResolver) -> Vec<HIRStatement<ResolvedItems>> {
+impl Resolvable for HIRGenericsBound<SearchPaths> {
+    type ResolveResult = HIRGenericsBound<ResolvedItems>;
+    fn resolve(self, resolver: &mut impl KirlNameResolver) -> DecisionTypeResult<Self::ResolveResult> {
+        let HIRGenericsBound { generics_argument, bound, function, argument_count } = self;
+        Ok(HIRGenericsBound { generics_argument, bound, function: function.resolve(resolver)?, argument_count })
+    }
+
+    fn all_reference(&self) -> Vec<&[String]> {
+        self.function.all_reference()
+    }
+}
+
+pub fn resolve_statements(statements: Vec<HIRStatement<SearchPaths>>, resolver: &mut impl KirlNameResolver) -> DecisionTypeResult<Vec<HIRStatement<ResolvedItems>>> {
     statements.resolve(resolver)
 }
 
 pub fn statement_references(statements: &Vec<HIRStatement<SearchPaths>>) -> Vec<&[String]> {
     statements.all_reference()
 }
+
+pub fn resolve_type(ty: HIRType, resolver: &mut impl KirlNameResolver) -> DecisionTypeResult<HIRType> {
+    ty.resolve(resolver)
+}
+
+pub fn type_references(ty: &HIRType) -> Vec<&[String]> {
+    ty.all_reference()
+}
+
+pub fn resolve_generics_bounds(bounds: Vec<HIRGenericsBound<SearchPaths>>, resolver: &mut impl KirlNameResolver) -> DecisionTypeResult<Vec<HIRGenericsBound<ResolvedItems>>> {
+    bounds.resolve(resolver)
+}
+
+pub fn generics_bound_references(bounds: &Vec<HIRGenericsBound<SearchPaths>>) -> Vec<&[String]> {
+    bounds.all_reference()
+}
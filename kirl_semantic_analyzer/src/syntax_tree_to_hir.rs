@@ -1,12 +1,19 @@
 use kirl_common::typing::HIRType;
-use std::collections::{BTreeMap, HashMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::convert::{TryFrom, TryInto};
 use std::error::Error;
 use std::fmt::{Display, Formatter};
+use std::ops::Range;
 
-use kirl_parser::kirl_parser::{Block, Condition, ConstructStruct, Expression, ExpressionItem, ForStatement, Function, FunctionReference, HIRTypeConvertError, If, ImportPath, LetBinding, Path, Pattern, Statement, StatementItem, WhileStatement};
+use kirl_parser::kirl_parser::{Block, Closure, Condition, ConstructStruct, Enum, EnumVariant, Expression, ExpressionItem, ForStatement, FormatStringItem, Function, FunctionReference, HIRTypeConvertError, If, ImportPath, LetBinding, Loop, Match, NamedType, Path, Pattern, Statement, StatementItem, Struct, StructName, TypeAlias, WhileStatement};
+use kirl_parser::CharacterPosition;
 
-use crate::{HIRExpression, HIRStatement, Immediate, ReferenceAccess, StatementReachable, Variable, WithImport};
+use crate::{HIREnumDefinition, HIREnumVariant, HIRExpression, HIRGenericsBound, HIRStatement, HIRStructDefinition, IfLetKind, Immediate, ReferenceAccess, StatementReachable, Variable, WithImport};
+
+// ?演算子で早期リターンされるエラーの型
+pub fn error_type() -> HIRType {
+    HIRType::AnonymousStruct(BTreeMap::from([("error".to_string(), HIRType::Named { path: vec!["String".to_string()], generics_arguments: Vec::new() })]))
+}
 
 #[derive(Debug, Clone, Ord, PartialOrd, Eq, PartialEq, Default)]
 pub struct SearchPaths(pub(crate) Vec<Vec<String>>);
@@ -18,6 +25,12 @@ pub enum AnalysisStatementError {
     DuplicatedMember(String),
     CollisionArgumentName(usize, usize),
     CollisionTypeArgumentName(usize, usize),
+    UnguardedRecursiveTypeAlias(String),
+    IrregularRecursiveTypeAlias(String),
+    UnknownGenericsBound(String),
+    InvalidGeneratorReturnType(HIRType),
+    AssignToCapturedVariable(String),
+    InvalidAssignmentTarget(Range<CharacterPosition>),
 }
 
 impl From<HIRTypeConvertError> for AnalysisStatementError {
@@ -34,6 +47,12 @@ impl Display for AnalysisStatementError {
             AnalysisStatementError::DuplicatedMember(name) => write!(f, "member {:?} is duplicated", name),
             AnalysisStatementError::CollisionArgumentName(a, b) => write!(f, "{}th and {}th argument have same name.", a, b),
             AnalysisStatementError::CollisionTypeArgumentName(a, b) => write!(f, "{}th and {}th type argument have same name.", a, b),
+            AnalysisStatementError::UnguardedRecursiveTypeAlias(name) => write!(f, "type alias {:?} refers to itself without being wrapped in a tuple, array, struct or function.", name),
+            AnalysisStatementError::IrregularRecursiveTypeAlias(name) => write!(f, "type alias {:?} refers to itself with different type arguments.", name),
+            AnalysisStatementError::UnknownGenericsBound(name) => write!(f, "bound {:?} is not defined.", name),
+            AnalysisStatementError::InvalidGeneratorReturnType(ty) => write!(f, "generator function must return std::iter::Iterator::<T> but declared {}.", ty.to_string()),
+            AnalysisStatementError::AssignToCapturedVariable(name) => write!(f, "variable {:?} is captured by a closure and cannot be assigned in it.", name),
+            AnalysisStatementError::InvalidAssignmentTarget(position) => write!(f, "invalid assignment target at {} - {}. only a variable, a member, a tuple item or an indexer can be assigned.", position.start, position.end),
         }
     }
 }
@@ -49,59 +68,192 @@ impl Error for AnalysisStatementError {
 
 type AnalysisStatementResult<T> = Result<T, AnalysisStatementError>;
 
+// 型引数の制約の名前と、制約を満たす型の値を引数に取れなければならない演算子の関数と引数の数
+// 制約は演算子の関数が定義されていることだけを要求する固定の一覧で、利用者が新しい制約を定義することはできない
+const GENERICS_BOUNDS: &[(&str, &[(&str, usize)])] = &[
+    ("Comparable", &[("_gt", 2)]),
+    ("Equatable", &[("_eq", 2)]),
+    ("Add", &[("_add", 2)]),
+    ("Sub", &[("_sub", 2)]),
+    ("Mul", &[("_mul", 2)]),
+    ("Div", &[("_div", 2)]),
+    ("Rem", &[("_rem", 2)]),
+    ("Neg", &[("_neg", 1)]),
+];
+
 fn get_candidate_paths(path: Vec<String>, imports: &BTreeMap<String, HashSet<Vec<String>>>) -> SearchPaths {
     SearchPaths(Some(path.clone()).into_iter().chain(imports.get(path.first().unwrap()).into_iter().flatten().map(|base| base.iter().chain(path.iter().skip(1)).cloned().collect())).collect())
 }
 
-fn apply_generics_type_argument(ty: HIRType, map: &HashMap<&str, usize>) -> HIRType {
+// 型引数の名前をGenericsTypeArgumentに、importされた名前をフルパスに置き換える
+fn resolve_type_names(ty: HIRType, generics_argument_names: &HashMap<&str, usize>, imports: &BTreeMap<String, HashSet<Vec<String>>>) -> HIRType {
     match ty {
-        ty @ (HIRType::Infer | HIRType::Unreachable | HIRType::GenericsTypeArgument(_)) => ty,
+        ty @ (HIRType::Infer | HIRType::Unreachable | HIRType::GenericsTypeArgument(_) | HIRType::Recursive { .. } | HIRType::RecursiveReference(_)) => ty,
         HIRType::Named { path, generics_arguments } => {
             if generics_arguments.is_empty() {
-                if let Some(index) = path.as_slice().try_into().ok().and_then(|[name]: &[_; 1]| map.get(name.as_str())) {
+                if let Some(index) = path.as_slice().try_into().ok().and_then(|[name]: &[_; 1]| generics_argument_names.get(name.as_str())) {
                     return HIRType::GenericsTypeArgument(*index);
                 }
             }
-            HIRType::Named { path, generics_arguments }
+            let path = match imports.get(path.first().unwrap()) {
+                Some(bases) if bases.len() == 1 => bases.iter().next().unwrap().iter().chain(path.iter().skip(1)).cloned().collect(),
+                _ => path,
+            };
+            HIRType::Named {
+                path,
+                generics_arguments: generics_arguments.into_iter().map(|ty| resolve_type_names(ty, generics_argument_names, imports)).collect(),
+            }
         }
-        HIRType::Tuple(items) => HIRType::Tuple(items.into_iter().map(|ty| apply_generics_type_argument(ty, map)).collect()),
-        HIRType::Array(item) => HIRType::Array(Box::new(apply_generics_type_argument(*item, map))),
+        HIRType::Tuple(items) => HIRType::Tuple(items.into_iter().map(|ty| resolve_type_names(ty, generics_argument_names, imports)).collect()),
+        HIRType::Array(item) => HIRType::Array(Box::new(resolve_type_names(*item, generics_argument_names, imports))),
         HIRType::Function { arguments, result } => HIRType::Function {
-            arguments: arguments.into_iter().map(|ty| apply_generics_type_argument(ty, map)).collect(),
-            result: Box::new(apply_generics_type_argument(*result, map)),
+            arguments: arguments.into_iter().map(|ty| resolve_type_names(ty, generics_argument_names, imports)).collect(),
+            result: Box::new(resolve_type_names(*result, generics_argument_names, imports)),
         },
-        HIRType::AnonymousStruct(members) => HIRType::AnonymousStruct(members.into_iter().map(|(k, ty)| (k, apply_generics_type_argument(ty, map))).collect()),
-        HIRType::Or(items) => HIRType::Or(items.into_iter().map(|ty| apply_generics_type_argument(ty, map)).collect()),
+        HIRType::AnonymousStruct(members) => HIRType::AnonymousStruct(members.into_iter().map(|(k, ty)| (k, resolve_type_names(ty, generics_argument_names, imports))).collect()),
+        HIRType::Or(items) => HIRType::Or(items.into_iter().map(|ty| resolve_type_names(ty, generics_argument_names, imports)).collect()),
     }
 }
 
-pub fn analysis_function(WithImport { import, item }: WithImport<Function>) -> AnalysisStatementResult<(Vec<HIRStatement<SearchPaths>>, Vec<HIRType>, HIRType)> {
-    let Function { position, arguments, return_type, expression, generics_arguments, .. } = item;
+fn collect_generics_argument_names(generics_arguments: &[String]) -> AnalysisStatementResult<HashMap<&str, usize>> {
     let mut generics_argument_names = HashMap::<&str, _>::new();
     for (i, name) in generics_arguments.iter().enumerate() {
         if let Some(j) = generics_argument_names.insert(name, i) {
             return Err(AnalysisStatementError::CollisionTypeArgumentName(j, i));
         }
     }
+    Ok(generics_argument_names)
+}
+
+fn collect_imports(import_paths: ImportPath) -> BTreeMap<String, HashSet<Vec<String>>> {
+    let mut imports: BTreeMap<String, HashSet<_>> = BTreeMap::new();
+    for path in collect_import_path(import_paths, Vec::new()).0 {
+        imports.entry(path.last().unwrap().clone()).or_default().insert(path);
+    }
+    imports
+}
+
+pub fn analysis_struct(WithImport { import, item }: WithImport<Struct>) -> AnalysisStatementResult<HIRStructDefinition> {
+    let Struct { generics_arguments, members, .. } = item;
+    let generics_argument_names = collect_generics_argument_names(&generics_arguments)?;
+    let imports = collect_imports(import);
+    let mut result_members = BTreeMap::new();
+    for (name, ty) in members {
+        if result_members.contains_key(&name) {
+            return Err(AnalysisStatementError::DuplicatedMember(name));
+        }
+        result_members.insert(name, resolve_type_names(ty.try_into()?, &generics_argument_names, &imports));
+    }
+    Ok(HIRStructDefinition { generics_argument_count: generics_arguments.len(), members: result_members })
+}
+
+pub fn analysis_enum(WithImport { import, item }: WithImport<Enum>) -> AnalysisStatementResult<HIREnumDefinition> {
+    let Enum { generics_arguments, variants, .. } = item;
+    let generics_argument_names = collect_generics_argument_names(&generics_arguments)?;
+    let imports = collect_imports(import);
+    let mut result_variants: Vec<(String, HIREnumVariant)> = Vec::new();
+    for (name, variant) in variants {
+        if result_variants.iter().any(|(variant_name, _)| *variant_name == name) {
+            return Err(AnalysisStatementError::DuplicatedMember(name));
+        }
+        let variant = match variant {
+            EnumVariant::Tuple(items) => HIREnumVariant::Tuple(items.into_iter().map(|ty| Ok(resolve_type_names(ty.try_into()?, &generics_argument_names, &imports))).collect::<AnalysisStatementResult<_>>()?),
+            EnumVariant::Struct(members) => {
+                let mut result_members = BTreeMap::new();
+                for (name, ty) in members {
+                    if result_members.contains_key(&name) {
+                        return Err(AnalysisStatementError::DuplicatedMember(name));
+                    }
+                    result_members.insert(name, resolve_type_names(ty.try_into()?, &generics_argument_names, &imports));
+                }
+                HIREnumVariant::Struct(result_members)
+            }
+        };
+        result_variants.push((name, variant));
+    }
+    Ok(HIREnumDefinition { generics_argument_count: generics_arguments.len(), variants: result_variants })
+}
+
+pub fn analysis_type_alias(WithImport { import, item }: WithImport<TypeAlias>) -> AnalysisStatementResult<(usize, HIRType)> {
+    let TypeAlias { generics_arguments, aliased_type, .. } = item;
+    let generics_argument_names = collect_generics_argument_names(&generics_arguments)?;
+    let imports = collect_imports(import);
+    Ok((generics_arguments.len(), resolve_type_names(aliased_type.try_into()?, &generics_argument_names, &imports)))
+}
+
+pub fn analysis_function(WithImport { import, item }: WithImport<Function>) -> AnalysisStatementResult<(Vec<HIRStatement<SearchPaths>>, Vec<HIRType>, HIRType, Vec<HIRGenericsBound<SearchPaths>>)> {
+    let Function { position, arguments, return_type, expression, generics_arguments, generator, .. } = item;
+    let (generics_argument_names, generics_bounds): (Vec<_>, Vec<_>) = generics_arguments.into_iter().unzip();
+    let generics_argument_names = collect_generics_argument_names(&generics_argument_names)?;
+    let imports = collect_imports(import.clone());
+    let mut bounds = Vec::new();
+    for (generics_argument, bound_names) in generics_bounds.into_iter().enumerate() {
+        for bound in bound_names {
+            let (_, functions) = GENERICS_BOUNDS.iter().find(|(name, _)| *name == bound).ok_or_else(|| AnalysisStatementError::UnknownGenericsBound(bound.clone()))?;
+            for (function, argument_count) in functions.iter() {
+                bounds.push(HIRGenericsBound {
+                    generics_argument,
+                    bound: bound.clone(),
+                    function: Variable::Named(position.clone(), Vec::new(), get_candidate_paths(vec![function.to_string()], &imports)),
+                    argument_count: *argument_count,
+                });
+            }
+        }
+    }
     let (names, types): (Vec<_>, Vec<_>) = arguments.into_iter().unzip();
-    Ok((
-        analysis(vec![Statement { position, statement: StatementItem::Return(Some(expression)) }], names, import, &generics_argument_names)?,
-        types.into_iter().map(|ty| apply_generics_type_argument(ty.try_into().expect(""), &generics_argument_names)).collect(),
-        apply_generics_type_argument(return_type.try_into().expect(""), &generics_argument_names),
-    ))
+    let return_type = resolve_type_names(return_type.try_into().expect(""), &generics_argument_names, &imports);
+    let statements = if generator {
+        // ジェネレータ関数は本体を実行せずに、本体から作ったIteratorを返す
+        let item_type = match &return_type {
+            HIRType::Named { path, generics_arguments } if path == &["std", "iter", "Iterator"] && generics_arguments.len() == 1 => generics_arguments[0].clone(),
+            ty => return Err(AnalysisStatementError::InvalidGeneratorReturnType(ty.clone())),
+        };
+        let (body, _, variable_sequence) = analysis(vec![Statement { position: position.clone(), statement: StatementItem::Expression(expression) }], names, import, &generics_argument_names)?;
+        vec![
+            HIRStatement::Binding {
+                position: position.clone(),
+                variable_id: variable_sequence,
+                variable_type: HIRType::Infer,
+                expression: HIRExpression::ConstructGenerator { item_type, body },
+            },
+            HIRStatement::Return(position.clone(), Variable::Unnamed(variable_sequence)),
+        ]
+    } else {
+        analysis(vec![Statement { position, statement: StatementItem::Return(Some(expression)) }], names, import, &generics_argument_names)?.0
+    };
+    Ok((statements, types.into_iter().map(|ty| resolve_type_names(ty.try_into().expect(""), &generics_argument_names, &imports)).collect(), return_type, bounds))
 }
 
 pub fn analysis_statements(code: Vec<Statement>) -> AnalysisStatementResult<Vec<HIRStatement<SearchPaths>>> {
-    analysis(code, Vec::new(), ImportPath::List(Vec::new()), &HashMap::new())
+    analysis(code, Vec::new(), ImportPath::List(Vec::new()), &HashMap::new()).map(|(statements, ..)| statements)
 }
 
-fn analysis(code: Vec<Statement>, argument_patterns: Vec<Pattern>, import_paths: ImportPath, generics_argument_names: &HashMap<&str, usize>) -> AnalysisStatementResult<Vec<HIRStatement<SearchPaths>>> {
+// トップレベルの文と、最後までスコープに残る変数(グローバル変数になる)の名前とID、そのうちpub varで宣言された名前を返す
+pub fn analysis_module_statements(code: Vec<Statement>) -> AnalysisStatementResult<(Vec<HIRStatement<SearchPaths>>, BTreeMap<String, usize>, BTreeSet<String>)> {
+    fn collect_pattern_names(pattern: &Pattern, result: &mut BTreeSet<String>) {
+        match pattern {
+            Pattern::Variable(name) => {
+                result.insert(name.clone());
+            }
+            Pattern::Tuple(patterns) => patterns.iter().for_each(|pattern| collect_pattern_names(pattern, result)),
+            Pattern::Struct(_, patterns) => patterns.iter().for_each(|(_, pattern)| collect_pattern_names(pattern, result)),
+        }
+    }
+    let mut public_variables = BTreeSet::new();
+    for statement in &code {
+        if let StatementItem::LetBinding(LetBinding { pattern, public: true, .. }) = &statement.statement {
+            collect_pattern_names(pattern, &mut public_variables);
+        }
+    }
+    let (statements, variables, _) = analysis(code, Vec::new(), ImportPath::List(Vec::new()), &HashMap::new())?;
+    Ok((statements, variables, public_variables))
+}
+
+// 文と、最後までスコープに残る変数の名前とID、次に使える変数IDを返す
+fn analysis(code: Vec<Statement>, argument_patterns: Vec<Pattern>, import_paths: ImportPath, generics_argument_names: &HashMap<&str, usize>) -> AnalysisStatementResult<(Vec<HIRStatement<SearchPaths>>, BTreeMap<String, usize>, usize)> {
     let mut result = Vec::new();
     let mut variables = BTreeMap::new();
-    let mut imports: BTreeMap<String, HashSet<_>> = BTreeMap::new();
-    for path in collect_import_path(import_paths, Vec::new()).0 {
-        imports.entry(path.last().unwrap().clone()).or_default().insert(path);
-    }
+    let mut imports = collect_imports(import_paths);
     let mut deconstruct_argument = Vec::new();
     let mut variable_sequence = argument_patterns.len();
     for (id, pattern) in argument_patterns.into_iter().enumerate() {
@@ -116,6 +268,7 @@ fn analysis(code: Vec<Statement>, argument_patterns: Vec<Pattern>, import_paths:
                     position: Default::default(),
                     expression: ExpressionItem::AccessVariable(Path { position: Default::default(), path: vec![argument_name.clone()] }, Vec::new()),
                 }),
+                public: false,
             }),
         });
         variables.insert(argument_name, id).map_or(Ok(()), |old| Err(AnalysisStatementError::CollisionArgumentName(old, id)))?;
@@ -125,14 +278,15 @@ fn analysis(code: Vec<Statement>, argument_patterns: Vec<Pattern>, import_paths:
             break;
         }
     }
-    Ok(result)
+    Ok((result, variables, variable_sequence))
 }
 
-fn push_deconstruct_pattern(pattern: Pattern, begin: Variable<SearchPaths>, result: &mut Vec<HIRStatement<SearchPaths>>, variables: &mut BTreeMap<String, usize>, variable_sequence: &mut usize, imports: &mut BTreeMap<String, HashSet<Vec<String>>>) -> AnalysisStatementResult<()> {
+fn push_deconstruct_pattern(position: &Range<CharacterPosition>, pattern: Pattern, begin: Variable<SearchPaths>, result: &mut Vec<HIRStatement<SearchPaths>>, variables: &mut BTreeMap<String, usize>, variable_sequence: &mut usize, imports: &mut BTreeMap<String, HashSet<Vec<String>>>) -> AnalysisStatementResult<()> {
     match pattern {
         Pattern::Variable(name) => match begin {
             begin @ Variable::Named { .. } => {
                 result.push(HIRStatement::Binding {
+                    position: position.clone(),
                     variable_id: *variable_sequence,
                     variable_type: HIRType::Infer,
                     expression: HIRExpression::AccessVariable(begin),
@@ -147,31 +301,124 @@ fn push_deconstruct_pattern(pattern: Pattern, begin: Variable<SearchPaths>, resu
         Pattern::Tuple(patterns) => {
             for (index, pattern) in patterns.into_iter().enumerate() {
                 result.push(HIRStatement::Binding {
+                    position: position.clone(),
                     variable_id: *variable_sequence,
                     variable_type: HIRType::Infer,
                     expression: HIRExpression::AccessTupleItem { variable: begin.clone(), index },
                 });
                 let current_variable = Variable::Unnamed(*variable_sequence);
                 *variable_sequence += 1;
-                push_deconstruct_pattern(pattern, current_variable, result, variables, variable_sequence, imports)?;
+                push_deconstruct_pattern(position, pattern, current_variable, result, variables, variable_sequence, imports)?;
             }
         }
         Pattern::Struct(_, patterns) => {
             for (member, pattern) in patterns {
                 result.push(HIRStatement::Binding {
+                    position: position.clone(),
                     variable_id: *variable_sequence,
                     variable_type: HIRType::Infer,
                     expression: HIRExpression::AccessMember { variable: begin.clone(), member },
                 });
                 let current_variable = Variable::Unnamed(*variable_sequence);
                 *variable_sequence += 1;
-                push_deconstruct_pattern(pattern, current_variable, result, variables, variable_sequence, imports)?;
+                push_deconstruct_pattern(position, pattern, current_variable, result, variables, variable_sequence, imports)?;
             }
         }
     }
     Ok(())
 }
 
+fn collect_referenced_variables(statements: &[HIRStatement<SearchPaths>], result: &mut BTreeSet<usize>) {
+    fn add(variable: &Variable<SearchPaths>, result: &mut BTreeSet<usize>) {
+        if let Variable::Unnamed(id) = variable {
+            result.insert(*id);
+        }
+    }
+    for statement in statements {
+        match statement {
+            HIRStatement::Binding { expression, .. } => match expression {
+                HIRExpression::Immediate(_) => {}
+                HIRExpression::CallFunction { function, arguments, .. } => {
+                    add(function, result);
+                    arguments.iter().for_each(|argument| add(argument, result));
+                }
+                HIRExpression::AccessVariable(variable) | HIRExpression::AccessMember { variable, .. } | HIRExpression::AccessTupleItem { variable, .. } => add(variable, result),
+                HIRExpression::If { condition, then, other } | HIRExpression::IfLet { condition, then, other, .. } => {
+                    add(condition, result);
+                    collect_referenced_variables(&then.0, result);
+                    add(&then.1, result);
+                    collect_referenced_variables(&other.0, result);
+                    add(&other.1, result);
+                }
+                HIRExpression::Loop(_, statements) => collect_referenced_variables(statements, result),
+                HIRExpression::Assign { variable, value } => {
+                    match variable {
+                        ReferenceAccess::Variable(variable) | ReferenceAccess::TupleItem(variable, _) | ReferenceAccess::Member(variable, _) => add(variable, result),
+                    }
+                    add(value, result);
+                }
+                // 内側のクロージャの本体は別の変数番号を使っているので、キャプチャだけを見る
+                HIRExpression::ConstructClosure { captures, .. } => captures.iter().for_each(|(_, variable)| add(variable, result)),
+                // ジェネレータの本体は外側と同じ変数番号を使う
+                HIRExpression::ConstructGenerator { body, .. } => collect_referenced_variables(body, result),
+                HIRExpression::ConstructStruct(members) | HIRExpression::ConstructNamedStruct { members, .. } => members.values().for_each(|member| add(member, result)),
+                HIRExpression::ConstructTuple(items) | HIRExpression::ConstructArray(items) => items.iter().for_each(|item| add(item, result)),
+            },
+            HIRStatement::Return(_, variable) | HIRStatement::Yield(variable) | HIRStatement::Break(_, Some(variable)) => add(variable, result),
+            HIRStatement::Unreachable | HIRStatement::Continue(_) | HIRStatement::Break(_, None) => {}
+        }
+    }
+}
+
+// 代入先になっている名前のない変数(メンバやタプルの要素への代入は含まない)
+fn collect_assigned_variables(statements: &[HIRStatement<SearchPaths>], result: &mut BTreeSet<usize>) {
+    for statement in statements {
+        if let HIRStatement::Binding { expression, .. } = statement {
+            match expression {
+                HIRExpression::If { then, other, .. } | HIRExpression::IfLet { then, other, .. } => {
+                    collect_assigned_variables(&then.0, result);
+                    collect_assigned_variables(&other.0, result);
+                }
+                HIRExpression::Loop(_, statements) | HIRExpression::ConstructGenerator { body: statements, .. } => collect_assigned_variables(statements, result),
+                HIRExpression::Assign { variable: ReferenceAccess::Variable(Variable::Unnamed(id)), .. } => {
+                    result.insert(*id);
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+// `import std::no_prelude;` を含むファイルではpreludeを使わない
+pub fn uses_prelude(statements: &[Statement]) -> bool {
+    !statements.iter().any(|statement| match &statement.statement {
+        StatementItem::Import { path, .. } => collect_import_path(path.clone(), Vec::new()).0.iter().any(|path| *path == ["std", "no_prelude"]),
+        _ => false,
+    })
+}
+
+// トップレベルのimportの位置とパスの一覧
+pub fn import_paths(statements: &[Statement]) -> Vec<(Range<CharacterPosition>, Vec<String>)> {
+    statements
+        .iter()
+        .flat_map(|statement| match &statement.statement {
+            StatementItem::Import { path, .. } => collect_import_path(path.clone(), Vec::new()).0.into_iter().map(|path| (statement.position.clone(), path)).collect(),
+            _ => Vec::new(),
+        })
+        .collect()
+}
+
+// `pub import` で他のファイルへ再公開するパスの一覧
+pub fn public_imports(statements: &[Statement]) -> Vec<Vec<String>> {
+    statements
+        .iter()
+        .flat_map(|statement| match &statement.statement {
+            StatementItem::Import { public: true, path } => collect_import_path(path.clone(), Vec::new()).0,
+            _ => Vec::new(),
+        })
+        .collect()
+}
+
 fn collect_import_path(path: ImportPath, mut base: Vec<String>) -> SearchPaths {
     match path {
         ImportPath::Item(name) => {
@@ -186,9 +433,19 @@ fn collect_import_path(path: ImportPath, mut base: Vec<String>) -> SearchPaths {
     }
 }
 
-fn push_statement(Statement { statement, .. }: Statement, result: &mut Vec<HIRStatement<SearchPaths>>, variables: &mut BTreeMap<String, usize>, variable_sequence: &mut usize, imports: &mut BTreeMap<String, HashSet<Vec<String>>>, generics_argument_names: &HashMap<&str, usize>) -> AnalysisStatementResult<StatementReachable> {
+// ループの本体は到達不能になった文以降を捨てる(ループ自体はbreakで抜けるまで続く)
+fn push_loop_body(statements: Vec<Statement>, body: &mut Vec<HIRStatement<SearchPaths>>, variables: &mut BTreeMap<String, usize>, variable_sequence: &mut usize, imports: &mut BTreeMap<String, HashSet<Vec<String>>>, generics_argument_names: &HashMap<&str, usize>) -> AnalysisStatementResult<()> {
+    for stmt in statements {
+        if push_statement(stmt, body, variables, variable_sequence, imports, generics_argument_names)? != StatementReachable::Reachable {
+            break;
+        }
+    }
+    Ok(())
+}
+
+fn push_statement(Statement { position, statement }: Statement, result: &mut Vec<HIRStatement<SearchPaths>>, variables: &mut BTreeMap<String, usize>, variable_sequence: &mut usize, imports: &mut BTreeMap<String, HashSet<Vec<String>>>, generics_argument_names: &HashMap<&str, usize>) -> AnalysisStatementResult<StatementReachable> {
     match statement {
-        StatementItem::Import(path) => {
+        StatementItem::Import { path, .. } => {
             for path in collect_import_path(path, Vec::new()).0 {
                 imports.entry(path.last().unwrap().clone()).or_default().insert(path);
             }
@@ -201,13 +458,14 @@ fn push_statement(Statement { statement, .. }: Statement, result: &mut Vec<HIRSt
                 return Ok(never);
             }
             result.push(HIRStatement::Binding {
+                position: position.clone(),
                 variable_id: *variable_sequence,
-                variable_type: apply_generics_type_argument(type_hint.map_or_else(|| HIRType::try_from(&pattern), TryInto::try_into)?, generics_argument_names),
+                variable_type: resolve_type_names(type_hint.map_or_else(|| HIRType::try_from(&pattern), TryInto::try_into)?, generics_argument_names, imports),
                 expression: HIRExpression::AccessVariable(variable),
             });
             let current_variable = Variable::Unnamed(*variable_sequence);
             *variable_sequence += 1;
-            push_deconstruct_pattern(pattern, current_variable, result, variables, variable_sequence, imports)?;
+            push_deconstruct_pattern(&position, pattern, current_variable, result, variables, variable_sequence, imports)?;
             Ok(StatementReachable::Reachable)
         }
         StatementItem::Return(expression) => {
@@ -216,8 +474,8 @@ fn push_statement(Statement { statement, .. }: Statement, result: &mut Vec<HIRSt
             } else {
                 push_expression(
                     Expression {
+                        position: position.clone(),
                         expression: ExpressionItem::ConstructTuple(Vec::new()),
-                        ..Default::default()
                     },
                     result,
                     variables,
@@ -227,15 +485,32 @@ fn push_statement(Statement { statement, .. }: Statement, result: &mut Vec<HIRSt
                 )?
                 .1
             };
-            result.push(HIRStatement::Return(return_item));
+            result.push(HIRStatement::Return(position, return_item));
             Ok(StatementReachable::UnreachableByReturn)
         }
+        StatementItem::Yield(expression) => {
+            let (reachable, value) = push_expression(expression, result, variables, variable_sequence, imports, generics_argument_names)?;
+            if reachable != StatementReachable::Reachable {
+                return Ok(reachable);
+            }
+            result.push(HIRStatement::Yield(value));
+            Ok(StatementReachable::Reachable)
+        }
         StatementItem::Continue(label) => {
             result.push(HIRStatement::Continue(label.clone()));
             Ok(StatementReachable::UnreachableByBreak(label))
         }
-        StatementItem::Break(label) => {
-            result.push(HIRStatement::Break(label.clone()));
+        StatementItem::Break(label, value) => {
+            let value = if let Some(value) = value {
+                let (reachable, value) = push_expression(value, result, variables, variable_sequence, imports, generics_argument_names)?;
+                if reachable != StatementReachable::Reachable {
+                    return Ok(reachable);
+                }
+                Some(value)
+            } else {
+                None
+            };
+            result.push(HIRStatement::Break(label.clone(), value));
             Ok(StatementReachable::UnreachableByBreak(label))
         }
         StatementItem::For(ForStatement {
@@ -248,11 +523,13 @@ fn push_statement(Statement { statement, .. }: Statement, result: &mut Vec<HIRSt
                 return Ok(reachable);
             }
             result.push(HIRStatement::Binding {
+                position: iter_position.clone(),
                 variable_id: *variable_sequence,
                 variable_type: HIRType::Infer,
                 expression: HIRExpression::CallFunction {
                     function: Variable::Named(iter_position.clone(), Vec::new(), get_candidate_paths(vec!["_iterator".to_string()], imports)),
                     arguments: vec![iterable],
+                    iterator_next: false,
                 },
             });
             let iterator = Variable::Unnamed(*variable_sequence);
@@ -261,16 +538,19 @@ fn push_statement(Statement { statement, .. }: Statement, result: &mut Vec<HIRSt
             let mut variables = variables.clone();
             let mut imports = imports.clone();
             body.push(HIRStatement::Binding {
+                position: iter_position.clone(),
                 variable_id: *variable_sequence,
                 variable_type: HIRType::Or(vec![HIRType::Tuple(Vec::new()), HIRType::AnonymousStruct([("value".to_string(), HIRType::Infer)].into_iter().collect())]),
                 expression: HIRExpression::CallFunction {
                     function: Variable::Named(iter_position, Vec::new(), get_candidate_paths(vec!["_next".to_string()], &imports)),
                     arguments: vec![iterator],
+                    iterator_next: true,
                 },
             });
             let condition = Variable::Unnamed(*variable_sequence);
             *variable_sequence += 1;
             body.push(HIRStatement::Binding {
+                position: position.clone(),
                 variable_id: *variable_sequence + 3,
                 variable_type: HIRType::Infer,
                 expression: HIRExpression::IfLet {
@@ -279,6 +559,7 @@ fn push_statement(Statement { statement, .. }: Statement, result: &mut Vec<HIRSt
                     condition,
                     then: (
                         vec![HIRStatement::Binding {
+                            position: position.clone(),
                             variable_id: *variable_sequence + 1,
                             variable_type: HIRType::Infer,
                             expression: HIRExpression::AccessMember {
@@ -290,8 +571,9 @@ fn push_statement(Statement { statement, .. }: Statement, result: &mut Vec<HIRSt
                     ),
                     other: (
                         vec![
-                            HIRStatement::Break(None),
+                            HIRStatement::Break(None, None),
                             HIRStatement::Binding {
+                                position: position.clone(),
                                 variable_id: *variable_sequence + 2,
                                 variable_type: HIRType::Tuple(Vec::new()),
                                 expression: HIRExpression::ConstructTuple(Vec::new()),
@@ -299,25 +581,18 @@ fn push_statement(Statement { statement, .. }: Statement, result: &mut Vec<HIRSt
                         ],
                         Variable::Unnamed(*variable_sequence + 2),
                     ),
+                    kind: IfLetKind::IfLet,
                 },
             });
             let iterator_variable = Variable::Unnamed(*variable_sequence + 3);
             *variable_sequence += 4;
-            push_deconstruct_pattern(pattern, iterator_variable, &mut body, &mut variables, variable_sequence, &mut imports)?;
-            for stmt in statements {
-                let reachable = push_statement(stmt, &mut body, &mut variables, variable_sequence, &mut imports, generics_argument_names)?;
-                match reachable {
-                    StatementReachable::UnreachableByReturn => return Ok(reachable),
-                    StatementReachable::UnreachableByBreak(None) => return Ok(StatementReachable::Reachable),
-                    StatementReachable::UnreachableByBreak(break_label) if break_label == label => return Ok(StatementReachable::Reachable),
-                    StatementReachable::UnreachableByBreak(break_label) => return Ok(StatementReachable::UnreachableByBreak(break_label)),
-                    _ => {}
-                }
-            }
+            push_deconstruct_pattern(&position, pattern, iterator_variable, &mut body, &mut variables, variable_sequence, &mut imports)?;
+            push_loop_body(statements, &mut body, &mut variables, variable_sequence, &mut imports, generics_argument_names)?;
             result.push(HIRStatement::Binding {
+                position: position.clone(),
                 variable_id: *variable_sequence,
                 variable_type: HIRType::Tuple(Vec::new()),
-                expression: HIRExpression::Loop(body),
+                expression: HIRExpression::Loop(label, body),
             });
             *variable_sequence += 1;
             Ok(StatementReachable::Reachable)
@@ -334,7 +609,7 @@ fn push_statement(Statement { statement, .. }: Statement, result: &mut Vec<HIRSt
             let mut imports = imports.clone();
             let (_, condition) = push_expression(
                 Expression {
-                    position: Default::default(),
+                    position: expression.position.clone(),
                     expression: ExpressionItem::Not(Box::new(expression)),
                 },
                 &mut body,
@@ -344,34 +619,28 @@ fn push_statement(Statement { statement, .. }: Statement, result: &mut Vec<HIRSt
                 generics_argument_names,
             )?;
             body.push(HIRStatement::Binding {
+                position: position.clone(),
                 variable_id: *variable_sequence,
                 variable_type: HIRType::Tuple(Vec::new()),
                 expression: HIRExpression::ConstructTuple(Vec::new()),
             });
             body.push(HIRStatement::Binding {
+                position: position.clone(),
                 variable_id: *variable_sequence + 1,
                 variable_type: HIRType::Tuple(Vec::new()),
                 expression: HIRExpression::If {
                     condition,
-                    then: (vec![HIRStatement::Break(None)], Variable::Unnamed(*variable_sequence)),
+                    then: (vec![HIRStatement::Break(None, None)], Variable::Unnamed(*variable_sequence)),
                     other: (Vec::new(), Variable::Unnamed(*variable_sequence)),
                 },
             });
             *variable_sequence += 2;
-            for stmt in statements {
-                let reachable = push_statement(stmt, &mut body, &mut variables, variable_sequence, &mut imports, generics_argument_names)?;
-                match reachable {
-                    StatementReachable::UnreachableByReturn => return Ok(reachable),
-                    StatementReachable::UnreachableByBreak(None) => return Ok(StatementReachable::Reachable),
-                    StatementReachable::UnreachableByBreak(break_label) if break_label == label => return Ok(StatementReachable::Reachable),
-                    StatementReachable::UnreachableByBreak(break_label) => return Ok(StatementReachable::UnreachableByBreak(break_label)),
-                    _ => {}
-                }
-            }
+            push_loop_body(statements, &mut body, &mut variables, variable_sequence, &mut imports, generics_argument_names)?;
             result.push(HIRStatement::Binding {
+                position: position.clone(),
                 variable_id: *variable_sequence,
                 variable_type: HIRType::Tuple(Vec::new()),
-                expression: HIRExpression::Loop(body),
+                expression: HIRExpression::Loop(label, body),
             });
             *variable_sequence += 1;
             Ok(StatementReachable::Reachable)
@@ -387,8 +656,9 @@ fn push_statement(Statement { statement, .. }: Statement, result: &mut Vec<HIRSt
             let mut variables = variables.clone();
             let mut imports = imports.clone();
             let (_, condition) = push_expression(*expression, &mut body, &mut variables, variable_sequence, &mut imports, generics_argument_names)?;
-            let condition_type = type_hint.map(HIRType::try_from).unwrap_or_else(|| HIRType::try_from(&pattern))?;
+            let condition_type = resolve_type_names(type_hint.map(HIRType::try_from).unwrap_or_else(|| HIRType::try_from(&pattern))?, generics_argument_names, &imports);
             body.push(HIRStatement::Binding {
+                position: position.clone(),
                 variable_id: *variable_sequence + 2,
                 variable_type: condition_type.clone(),
                 expression: HIRExpression::IfLet {
@@ -398,8 +668,9 @@ fn push_statement(Statement { statement, .. }: Statement, result: &mut Vec<HIRSt
                     then: (vec![], Variable::Unnamed(*variable_sequence)),
                     other: (
                         vec![
-                            HIRStatement::Break(None),
+                            HIRStatement::Break(None, None),
                             HIRStatement::Binding {
+                                position: position.clone(),
                                 variable_id: *variable_sequence + 1,
                                 variable_type: HIRType::Tuple(Vec::new()),
                                 expression: HIRExpression::ConstructTuple(Vec::new()),
@@ -407,25 +678,18 @@ fn push_statement(Statement { statement, .. }: Statement, result: &mut Vec<HIRSt
                         ],
                         Variable::Unnamed(*variable_sequence + 1),
                     ),
+                    kind: IfLetKind::IfLet,
                 },
             });
             let condition = Variable::Unnamed(*variable_sequence + 2);
             *variable_sequence += 3;
-            push_deconstruct_pattern(pattern, condition, &mut body, &mut variables, variable_sequence, &mut imports)?;
-            for stmt in statements {
-                let reachable = push_statement(stmt, &mut body, &mut variables, variable_sequence, &mut imports, generics_argument_names)?;
-                match reachable {
-                    StatementReachable::UnreachableByReturn => return Ok(reachable),
-                    StatementReachable::UnreachableByBreak(None) => return Ok(StatementReachable::Reachable),
-                    StatementReachable::UnreachableByBreak(break_label) if break_label == label => return Ok(StatementReachable::Reachable),
-                    StatementReachable::UnreachableByBreak(break_label) => return Ok(StatementReachable::UnreachableByBreak(break_label)),
-                    _ => {}
-                }
-            }
+            push_deconstruct_pattern(&position, pattern, condition, &mut body, &mut variables, variable_sequence, &mut imports)?;
+            push_loop_body(statements, &mut body, &mut variables, variable_sequence, &mut imports, generics_argument_names)?;
             result.push(HIRStatement::Binding {
+                position: position.clone(),
                 variable_id: *variable_sequence,
                 variable_type: HIRType::Tuple(Vec::new()),
-                expression: HIRExpression::Loop(body),
+                expression: HIRExpression::Loop(label, body),
             });
             *variable_sequence += 1;
             Ok(StatementReachable::Reachable)
@@ -445,7 +709,187 @@ pub(crate) trait TryMapCollect: Sized + IntoIterator {
 
 impl<I: Sized + IntoIterator> TryMapCollect for I {}
 
-fn push_expression(Expression { expression, .. }: Expression, result: &mut Vec<HIRStatement<SearchPaths>>, variables: &mut BTreeMap<String, usize>, variable_sequence: &mut usize, imports: &mut BTreeMap<String, HashSet<Vec<String>>>, generics_argument_names: &HashMap<&str, usize>) -> AnalysisStatementResult<(StatementReachable, Variable<SearchPaths>)> {
+// 型の絞り込みは名前のない変数にしか効かないので、名前による参照は一度束縛しておく
+fn bind_unnamed(position: &Range<CharacterPosition>, variable: Variable<SearchPaths>, result: &mut Vec<HIRStatement<SearchPaths>>, variable_sequence: &mut usize) -> Variable<SearchPaths> {
+    match variable {
+        variable @ Variable::Unnamed(_) => variable,
+        variable @ Variable::Named(..) => {
+            result.push(HIRStatement::Binding {
+                position: position.clone(),
+                variable_id: *variable_sequence,
+                variable_type: HIRType::Infer,
+                expression: HIRExpression::AccessVariable(variable),
+            });
+            *variable_sequence += 1;
+            Variable::Unnamed(*variable_sequence - 1)
+        }
+    }
+}
+
+// a && b は if a { b } else { a }、a || b は if a { a } else { b } として扱う
+#[allow(clippy::too_many_arguments)]
+fn push_short_circuit_expression(is_and: bool, expression1: Expression, expression2: Expression, result: &mut Vec<HIRStatement<SearchPaths>>, variables: &mut BTreeMap<String, usize>, variable_sequence: &mut usize, imports: &mut BTreeMap<String, HashSet<Vec<String>>>, generics_argument_names: &HashMap<&str, usize>) -> AnalysisStatementResult<(StatementReachable, Variable<SearchPaths>)> {
+    let position = expression1.position.start..expression2.position.end;
+    let (reachable, condition) = push_expression(expression1, result, variables, variable_sequence, imports, generics_argument_names)?;
+    if reachable != StatementReachable::Reachable {
+        return Ok((reachable, condition));
+    }
+    let mut evaluate_statements = Vec::new();
+    let mut evaluate_variables = variables.clone();
+    let evaluate_position = expression2.position.clone();
+    let (mut reachable, evaluate_result) = push_expression(expression2, &mut evaluate_statements, &mut evaluate_variables, variable_sequence, imports, generics_argument_names)?;
+    let evaluate_result = if reachable == StatementReachable::Reachable {
+        // 右辺もBoolでなければならないので型を指定して束縛する
+        evaluate_statements.push(HIRStatement::Binding {
+            position: evaluate_position,
+            variable_id: *variable_sequence,
+            variable_type: HIRType::Named { path: vec!["Bool".to_string()], generics_arguments: Vec::new() },
+            expression: HIRExpression::AccessVariable(evaluate_result),
+        });
+        *variable_sequence += 1;
+        Variable::Unnamed(*variable_sequence - 1)
+    } else {
+        evaluate_result
+    };
+    // 右辺を評価しない側は常に到達可能
+    reachable.combine(StatementReachable::Reachable);
+    let evaluate = (evaluate_statements, evaluate_result);
+    let skip = (Vec::new(), condition.clone());
+    let (then, other) = if is_and { (evaluate, skip) } else { (skip, evaluate) };
+    result.push(HIRStatement::Binding {
+        position: position.clone(),
+        variable_id: *variable_sequence,
+        variable_type: HIRType::Infer,
+        expression: HIRExpression::If { condition, then, other },
+    });
+    let result = Variable::Unnamed(*variable_sequence);
+    *variable_sequence += 1;
+    Ok((reachable, result))
+}
+
+// a[i] += b は base, index を一度だけ評価し、_set_item(base, index, _add(_get_item(base, index), b)) として扱う
+#[allow(clippy::too_many_arguments)]
+fn push_compound_assign_expression(operator_function: &str, reference_expression: Expression, value_expression: Expression, result: &mut Vec<HIRStatement<SearchPaths>>, variables: &mut BTreeMap<String, usize>, variable_sequence: &mut usize, imports: &mut BTreeMap<String, HashSet<Vec<String>>>, generics_argument_names: &HashMap<&str, usize>) -> AnalysisStatementResult<(StatementReachable, Variable<SearchPaths>)> {
+    let position = reference_expression.position.start..value_expression.position.end;
+    let operator_position = reference_expression.position.end..value_expression.position.start;
+    let target_position = reference_expression.position.clone();
+    let (place, current) = match reference_expression.expression {
+        ExpressionItem::AccessVariable(Path { position, path }, _) => {
+            let variable_reference = if let [name] = AsRef::<[String]>::as_ref(&path) { variables.get(name) } else { None };
+            let variable = variable_reference.copied().map(Variable::Unnamed).unwrap_or_else(|| Variable::Named(position, Vec::new(), get_candidate_paths(path, imports)));
+            (ReferenceAccess::Variable(variable.clone()), variable)
+        }
+        ExpressionItem::AccessTupleItem(base, index) => {
+            let (reachable, base) = push_expression(*base, result, variables, variable_sequence, imports, generics_argument_names)?;
+            if reachable != StatementReachable::Reachable {
+                return Ok((reachable, base));
+            }
+            result.push(HIRStatement::Binding {
+                position: position.clone(),
+                variable_id: *variable_sequence,
+                variable_type: HIRType::Infer,
+                expression: HIRExpression::AccessTupleItem { variable: base.clone(), index },
+            });
+            let current = Variable::Unnamed(*variable_sequence);
+            *variable_sequence += 1;
+            (ReferenceAccess::TupleItem(base, index), current)
+        }
+        ExpressionItem::AccessMember(base, member) => {
+            let (reachable, base) = push_expression(*base, result, variables, variable_sequence, imports, generics_argument_names)?;
+            if reachable != StatementReachable::Reachable {
+                return Ok((reachable, base));
+            }
+            result.push(HIRStatement::Binding {
+                position: position.clone(),
+                variable_id: *variable_sequence,
+                variable_type: HIRType::Infer,
+                expression: HIRExpression::AccessMember { variable: base.clone(), member: member.clone() },
+            });
+            let current = Variable::Unnamed(*variable_sequence);
+            *variable_sequence += 1;
+            (ReferenceAccess::Member(base, member), current)
+        }
+        ExpressionItem::Indexer(base, index) => {
+            let (reachable, base) = push_expression(*base, result, variables, variable_sequence, imports, generics_argument_names)?;
+            if reachable != StatementReachable::Reachable {
+                return Ok((reachable, base));
+            }
+            let index_position = index.position.clone();
+            let (reachable, index) = push_expression(*index, result, variables, variable_sequence, imports, generics_argument_names)?;
+            if reachable != StatementReachable::Reachable {
+                return Ok((reachable, index));
+            }
+            result.push(HIRStatement::Binding {
+                position: position.clone(),
+                variable_id: *variable_sequence,
+                variable_type: HIRType::Infer,
+                expression: HIRExpression::CallFunction {
+                    function: Variable::Named(index_position.clone(), Vec::new(), get_candidate_paths(vec!["_get_item".to_string()], imports)),
+                    arguments: vec![base.clone(), index.clone()],
+                    iterator_next: false,
+                },
+            });
+            let current = Variable::Unnamed(*variable_sequence);
+            *variable_sequence += 1;
+            let (reachable, value) = push_expression(value_expression, result, variables, variable_sequence, imports, generics_argument_names)?;
+            if reachable != StatementReachable::Reachable {
+                return Ok((reachable, value));
+            }
+            result.push(HIRStatement::Binding {
+                position: position.clone(),
+                variable_id: *variable_sequence,
+                variable_type: HIRType::Infer,
+                expression: HIRExpression::CallFunction {
+                    function: Variable::Named(operator_position, Vec::new(), get_candidate_paths(vec![operator_function.to_string()], imports)),
+                    arguments: vec![current, value],
+                    iterator_next: false,
+                },
+            });
+            let value = Variable::Unnamed(*variable_sequence);
+            *variable_sequence += 1;
+            result.push(HIRStatement::Binding {
+                position: position.clone(),
+                variable_id: *variable_sequence,
+                variable_type: HIRType::Infer,
+                expression: HIRExpression::CallFunction {
+                    function: Variable::Named(index_position, Vec::new(), get_candidate_paths(vec!["_set_item".to_string()], imports)),
+                    arguments: vec![base, index, value.clone()],
+                    iterator_next: false,
+                },
+            });
+            *variable_sequence += 1;
+            return Ok((StatementReachable::Reachable, value));
+        }
+        _ => return Err(AnalysisStatementError::InvalidAssignmentTarget(target_position)),
+    };
+    let (reachable, value) = push_expression(value_expression, result, variables, variable_sequence, imports, generics_argument_names)?;
+    if reachable != StatementReachable::Reachable {
+        return Ok((reachable, value));
+    }
+    result.push(HIRStatement::Binding {
+        position: position.clone(),
+        variable_id: *variable_sequence,
+        variable_type: HIRType::Infer,
+        expression: HIRExpression::CallFunction {
+            function: Variable::Named(operator_position, Vec::new(), get_candidate_paths(vec![operator_function.to_string()], imports)),
+            arguments: vec![current, value],
+            iterator_next: false,
+        },
+    });
+    let value = Variable::Unnamed(*variable_sequence);
+    *variable_sequence += 1;
+    result.push(HIRStatement::Binding {
+        position: position.clone(),
+        variable_id: *variable_sequence,
+        variable_type: HIRType::Infer,
+        expression: HIRExpression::Assign { variable: place, value },
+    });
+    let result_variable = Variable::Unnamed(*variable_sequence);
+    *variable_sequence += 1;
+    Ok((StatementReachable::Reachable, result_variable))
+}
+
+fn push_expression(Expression { position, expression }: Expression, result: &mut Vec<HIRStatement<SearchPaths>>, variables: &mut BTreeMap<String, usize>, variable_sequence: &mut usize, imports: &mut BTreeMap<String, HashSet<Vec<String>>>, generics_argument_names: &HashMap<&str, usize>) -> AnalysisStatementResult<(StatementReachable, Variable<SearchPaths>)> {
     match expression {
         ExpressionItem::AccessVariable(Path { path, position }, types) => {
             assert!(!path.is_empty());
@@ -458,11 +902,12 @@ fn push_expression(Expression { expression, .. }: Expression, result: &mut Vec<H
             } else {
                 None
             };
-            let types = types.into_iter().try_map_collect(HIRType::try_from)?;
+            let types = types.into_iter().try_map_collect(|ty| HIRType::try_from(ty).map(|ty| resolve_type_names(ty, generics_argument_names, imports)))?;
             Ok((StatementReachable::Reachable, variable.unwrap_or_else(|| Variable::Named(position, types, get_candidate_paths(path, imports)))))
         }
         ExpressionItem::StringImmediate(value) => {
             result.push(HIRStatement::Binding {
+                position: position.clone(),
                 variable_id: *variable_sequence,
                 variable_type: HIRType::Infer,
                 expression: HIRExpression::Immediate(Immediate::String(value)),
@@ -473,6 +918,7 @@ fn push_expression(Expression { expression, .. }: Expression, result: &mut Vec<H
         }
         ExpressionItem::NumberImmediate(value) => {
             result.push(HIRStatement::Binding {
+                position: position.clone(),
                 variable_id: *variable_sequence,
                 variable_type: HIRType::Infer,
                 expression: HIRExpression::Immediate(Immediate::Number(value)),
@@ -481,12 +927,69 @@ fn push_expression(Expression { expression, .. }: Expression, result: &mut Vec<H
             *variable_sequence += 1;
             Ok((StatementReachable::Reachable, variable))
         }
+        ExpressionItem::FormatString(items) => {
+            let mut concatenated: Option<Variable<SearchPaths>> = None;
+            let mut item_position = position.clone();
+            for item in items {
+                let variable = match item {
+                    FormatStringItem::Text(text) if text.is_empty() => continue,
+                    FormatStringItem::Text(text) => {
+                        result.push(HIRStatement::Binding {
+                            position: position.clone(),
+                            variable_id: *variable_sequence,
+                            variable_type: HIRType::Infer,
+                            expression: HIRExpression::Immediate(Immediate::String(text)),
+                        });
+                        *variable_sequence += 1;
+                        Variable::Unnamed(*variable_sequence - 1)
+                    }
+                    FormatStringItem::Expression(expression) => {
+                        item_position = expression.position.clone();
+                        let (reachable, variable) = push_expression(expression, result, variables, variable_sequence, imports, generics_argument_names)?;
+                        if reachable != StatementReachable::Reachable {
+                            return Ok((reachable, variable));
+                        }
+                        result.push(HIRStatement::Binding {
+                            position: position.clone(),
+                            variable_id: *variable_sequence,
+                            variable_type: HIRType::Infer,
+                            expression: HIRExpression::CallFunction {
+                                function: Variable::Named(item_position.clone(), Vec::new(), get_candidate_paths(vec!["to_string".to_string()], imports)),
+                                arguments: vec![variable],
+                                iterator_next: false,
+                            },
+                        });
+                        *variable_sequence += 1;
+                        Variable::Unnamed(*variable_sequence - 1)
+                    }
+                };
+                concatenated = Some(match concatenated {
+                    None => variable,
+                    Some(concatenated) => {
+                        result.push(HIRStatement::Binding {
+                            position: position.clone(),
+                            variable_id: *variable_sequence,
+                            variable_type: HIRType::Infer,
+                            expression: HIRExpression::CallFunction {
+                                function: Variable::Named(item_position.clone(), Vec::new(), get_candidate_paths(vec!["_add".to_string()], imports)),
+                                arguments: vec![concatenated, variable],
+                                iterator_next: false,
+                            },
+                        });
+                        *variable_sequence += 1;
+                        Variable::Unnamed(*variable_sequence - 1)
+                    }
+                });
+            }
+            Ok((StatementReachable::Reachable, concatenated.expect("FormatStringは式を1つ以上含む")))
+        }
         ExpressionItem::AccessTupleItem(expression, index) => {
             let (reachable, variable) = push_expression(*expression, result, variables, variable_sequence, imports, generics_argument_names)?;
             if reachable != StatementReachable::Reachable {
                 return Ok((reachable, variable));
             }
             result.push(HIRStatement::Binding {
+                position: position.clone(),
                 variable_id: *variable_sequence,
                 variable_type: HIRType::Infer,
                 expression: HIRExpression::AccessTupleItem { variable, index },
@@ -501,6 +1004,7 @@ fn push_expression(Expression { expression, .. }: Expression, result: &mut Vec<H
                 return Ok((reachable, variable));
             }
             result.push(HIRStatement::Binding {
+                position: position.clone(),
                 variable_id: *variable_sequence,
                 variable_type: HIRType::Infer,
                 expression: HIRExpression::AccessMember { variable, member },
@@ -518,7 +1022,16 @@ fn push_expression(Expression { expression, .. }: Expression, result: &mut Vec<H
                     }
                     function_variable
                 }
-                FunctionReference::Static { path, generics_type_arguments } => Variable::Named(path.position, generics_type_arguments.into_iter().try_map_collect(HIRType::try_from)?, get_candidate_paths(path.path, imports)),
+                FunctionReference::Static { path, generics_type_arguments } => {
+                    let local_variable = match AsRef::<[String]>::as_ref(&path.path) {
+                        [name] if generics_type_arguments.is_empty() => variables.get(name).map(|id| Variable::Unnamed(*id)),
+                        _ => None,
+                    };
+                    match local_variable {
+                        Some(variable) => variable,
+                        None => Variable::Named(path.position, generics_type_arguments.into_iter().try_map_collect(|ty| HIRType::try_from(ty).map(|ty| resolve_type_names(ty, generics_argument_names, imports)))?, get_candidate_paths(path.path, imports)),
+                    }
+                }
             };
             let mut function_arguments = Vec::with_capacity(arguments.len());
             for argument_expression in arguments {
@@ -529,9 +1042,10 @@ fn push_expression(Expression { expression, .. }: Expression, result: &mut Vec<H
                 function_arguments.push(argument_variable);
             }
             result.push(HIRStatement::Binding {
+                position: position.clone(),
                 variable_id: *variable_sequence,
                 variable_type: HIRType::Infer,
-                expression: HIRExpression::CallFunction { function, arguments: function_arguments },
+                expression: HIRExpression::CallFunction { function, arguments: function_arguments, iterator_next: false },
             });
             let result_variable = Variable::Unnamed(*variable_sequence);
             *variable_sequence += 1;
@@ -548,11 +1062,13 @@ fn push_expression(Expression { expression, .. }: Expression, result: &mut Vec<H
                 return Ok((reachable, index_variable));
             }
             result.push(HIRStatement::Binding {
+                position: position.clone(),
                 variable_id: *variable_sequence,
                 variable_type: HIRType::Infer,
                 expression: HIRExpression::CallFunction {
                     function: Variable::Named(index_expression_position, Vec::new(), get_candidate_paths(vec!["_get_item".to_string()], imports)),
                     arguments: vec![base_variable, index_variable],
+                    iterator_next: false,
                 },
             });
             let result_variable = Variable::Unnamed(*variable_sequence);
@@ -569,6 +1085,7 @@ fn push_expression(Expression { expression, .. }: Expression, result: &mut Vec<H
                 members.push(variable);
             }
             result.push(HIRStatement::Binding {
+                position: position.clone(),
                 variable_id: *variable_sequence,
                 variable_type: HIRType::Infer,
                 expression: HIRExpression::ConstructTuple(members),
@@ -587,6 +1104,7 @@ fn push_expression(Expression { expression, .. }: Expression, result: &mut Vec<H
                 members.push(variable);
             }
             result.push(HIRStatement::Binding {
+                position: position.clone(),
                 variable_id: *variable_sequence,
                 variable_type: HIRType::Infer,
                 expression: HIRExpression::ConstructArray(members),
@@ -595,22 +1113,31 @@ fn push_expression(Expression { expression, .. }: Expression, result: &mut Vec<H
             *variable_sequence += 1;
             Ok((StatementReachable::Reachable, result_variable))
         }
-        ExpressionItem::ConstructStruct(ConstructStruct { items, .. }) => {
+        ExpressionItem::ConstructStruct(ConstructStruct { name, items }) => {
             let mut members = BTreeMap::new();
-            for (name, expression) in items {
-                if members.contains_key(&name) {
-                    return Err(AnalysisStatementError::DuplicatedMember(name));
+            for (member_name, expression) in items {
+                if members.contains_key(&member_name) {
+                    return Err(AnalysisStatementError::DuplicatedMember(member_name));
                 }
                 let (reachable, variable) = push_expression(expression, result, variables, variable_sequence, imports, generics_argument_names)?;
                 if reachable != StatementReachable::Reachable {
                     return Ok((reachable, variable));
                 }
-                members.insert(name, variable);
+                members.insert(member_name, variable);
             }
+            let expression = match name {
+                StructName::Anonymous => HIRExpression::ConstructStruct(members),
+                StructName::Named(NamedType { path, generics_arguments, .. }) => {
+                    let generics_arguments = generics_arguments.into_iter().try_map_collect(HIRType::try_from)?;
+                    let struct_type = resolve_type_names(HIRType::Named { path, generics_arguments }, generics_argument_names, imports);
+                    HIRExpression::ConstructNamedStruct { struct_type, members }
+                }
+            };
             result.push(HIRStatement::Binding {
+                position: position.clone(),
                 variable_id: *variable_sequence,
                 variable_type: HIRType::Infer,
-                expression: HIRExpression::ConstructStruct(members),
+                expression,
             });
             let result_variable = Variable::Unnamed(*variable_sequence);
             *variable_sequence += 1;
@@ -623,6 +1150,7 @@ fn push_expression(Expression { expression, .. }: Expression, result: &mut Vec<H
                 let reachable = push_statement(statement, result, &mut variables, variable_sequence, &mut imports, generics_argument_names)?;
                 if reachable != StatementReachable::Reachable {
                     result.push(HIRStatement::Binding {
+                        position: position.clone(),
                         variable_id: *variable_sequence,
                         variable_type: HIRType::Tuple(Vec::new()),
                         expression: HIRExpression::ConstructTuple(Vec::new()),
@@ -635,6 +1163,7 @@ fn push_expression(Expression { expression, .. }: Expression, result: &mut Vec<H
                 push_expression(*last_expression, result, &mut variables, variable_sequence, &mut imports, generics_argument_names)
             } else {
                 result.push(HIRStatement::Binding {
+                    position: position.clone(),
                     variable_id: *variable_sequence,
                     variable_type: HIRType::Tuple(Vec::new()),
                     expression: HIRExpression::ConstructTuple(Vec::new()),
@@ -650,11 +1179,13 @@ fn push_expression(Expression { expression, .. }: Expression, result: &mut Vec<H
                 return Ok((reachable, variable));
             }
             result.push(HIRStatement::Binding {
+                position: position.clone(),
                 variable_id: *variable_sequence,
                 variable_type: HIRType::Infer,
                 expression: HIRExpression::CallFunction {
                     function: Variable::Named(expression_position, Vec::new(), get_candidate_paths(vec!["_neg".to_string()], imports)),
                     arguments: vec![variable],
+                    iterator_next: false,
                 },
             });
             let result_variable = Variable::Unnamed(*variable_sequence);
@@ -668,11 +1199,13 @@ fn push_expression(Expression { expression, .. }: Expression, result: &mut Vec<H
                 return Ok((reachable, variable));
             }
             result.push(HIRStatement::Binding {
+                position: position.clone(),
                 variable_id: *variable_sequence,
                 variable_type: HIRType::Infer,
                 expression: HIRExpression::CallFunction {
                     function: Variable::Named(expression_position, Vec::new(), get_candidate_paths(vec!["_not".to_string()], imports)),
                     arguments: vec![variable],
+                    iterator_next: false,
                 },
             });
             let result_variable = Variable::Unnamed(*variable_sequence);
@@ -690,11 +1223,13 @@ fn push_expression(Expression { expression, .. }: Expression, result: &mut Vec<H
                 return Ok((reachable, variable2));
             }
             result.push(HIRStatement::Binding {
+                position: position.clone(),
                 variable_id: *variable_sequence,
                 variable_type: HIRType::Infer,
                 expression: HIRExpression::CallFunction {
                     function: Variable::Named(expression_position, Vec::new(), get_candidate_paths(vec!["_mul".to_string()], imports)),
                     arguments: vec![variable1, variable2],
+                    iterator_next: false,
                 },
             });
             let result_variable = Variable::Unnamed(*variable_sequence);
@@ -712,11 +1247,13 @@ fn push_expression(Expression { expression, .. }: Expression, result: &mut Vec<H
                 return Ok((reachable, variable2));
             }
             result.push(HIRStatement::Binding {
+                position: position.clone(),
                 variable_id: *variable_sequence,
                 variable_type: HIRType::Infer,
                 expression: HIRExpression::CallFunction {
                     function: Variable::Named(expression_position, Vec::new(), get_candidate_paths(vec!["_div".to_string()], imports)),
                     arguments: vec![variable1, variable2],
+                    iterator_next: false,
                 },
             });
             let result_variable = Variable::Unnamed(*variable_sequence);
@@ -734,11 +1271,13 @@ fn push_expression(Expression { expression, .. }: Expression, result: &mut Vec<H
                 return Ok((reachable, variable2));
             }
             result.push(HIRStatement::Binding {
+                position: position.clone(),
                 variable_id: *variable_sequence,
                 variable_type: HIRType::Infer,
                 expression: HIRExpression::CallFunction {
                     function: Variable::Named(expression_position, Vec::new(), get_candidate_paths(vec!["_rem".to_string()], imports)),
                     arguments: vec![variable1, variable2],
+                    iterator_next: false,
                 },
             });
             let result_variable = Variable::Unnamed(*variable_sequence);
@@ -756,11 +1295,13 @@ fn push_expression(Expression { expression, .. }: Expression, result: &mut Vec<H
                 return Ok((reachable, variable2));
             }
             result.push(HIRStatement::Binding {
+                position: position.clone(),
                 variable_id: *variable_sequence,
                 variable_type: HIRType::Infer,
                 expression: HIRExpression::CallFunction {
                     function: Variable::Named(expression_position, Vec::new(), get_candidate_paths(vec!["_add".to_string()], imports)),
                     arguments: vec![variable1, variable2],
+                    iterator_next: false,
                 },
             });
             let result_variable = Variable::Unnamed(*variable_sequence);
@@ -778,11 +1319,13 @@ fn push_expression(Expression { expression, .. }: Expression, result: &mut Vec<H
                 return Ok((reachable, variable2));
             }
             result.push(HIRStatement::Binding {
+                position: position.clone(),
                 variable_id: *variable_sequence,
                 variable_type: HIRType::Infer,
                 expression: HIRExpression::CallFunction {
                     function: Variable::Named(expression_position, Vec::new(), get_candidate_paths(vec!["_sub".to_string()], imports)),
                     arguments: vec![variable1, variable2],
+                    iterator_next: false,
                 },
             });
             let result_variable = Variable::Unnamed(*variable_sequence);
@@ -800,11 +1343,13 @@ fn push_expression(Expression { expression, .. }: Expression, result: &mut Vec<H
                 return Ok((reachable, variable2));
             }
             result.push(HIRStatement::Binding {
+                position: position.clone(),
                 variable_id: *variable_sequence,
                 variable_type: HIRType::Infer,
                 expression: HIRExpression::CallFunction {
                     function: Variable::Named(expression_position, Vec::new(), get_candidate_paths(vec!["_gt".to_string()], imports)),
                     arguments: vec![variable1, variable2],
+                    iterator_next: false,
                 },
             });
             let result_variable = Variable::Unnamed(*variable_sequence);
@@ -822,11 +1367,13 @@ fn push_expression(Expression { expression, .. }: Expression, result: &mut Vec<H
                 return Ok((reachable, variable2));
             }
             result.push(HIRStatement::Binding {
+                position: position.clone(),
                 variable_id: *variable_sequence,
                 variable_type: HIRType::Infer,
                 expression: HIRExpression::CallFunction {
                     function: Variable::Named(expression_position, Vec::new(), get_candidate_paths(vec!["_gt".to_string()], imports)),
                     arguments: vec![variable2, variable1],
+                    iterator_next: false,
                 },
             });
             let result_variable = Variable::Unnamed(*variable_sequence);
@@ -844,21 +1391,25 @@ fn push_expression(Expression { expression, .. }: Expression, result: &mut Vec<H
                 return Ok((reachable, variable2));
             }
             result.push(HIRStatement::Binding {
+                position: position.clone(),
                 variable_id: *variable_sequence,
                 variable_type: HIRType::Infer,
                 expression: HIRExpression::CallFunction {
                     function: Variable::Named(expression_position.clone(), Vec::new(), get_candidate_paths(vec!["_gt".to_string()], imports)),
                     arguments: vec![variable2, variable1],
+                    iterator_next: false,
                 },
             });
             let tmp_variable = Variable::Unnamed(*variable_sequence);
             *variable_sequence += 1;
             result.push(HIRStatement::Binding {
+                position: position.clone(),
                 variable_id: *variable_sequence,
                 variable_type: HIRType::Infer,
                 expression: HIRExpression::CallFunction {
                     function: Variable::Named(expression_position, Vec::new(), get_candidate_paths(vec!["_not".to_string()], imports)),
                     arguments: vec![tmp_variable],
+                    iterator_next: false,
                 },
             });
             let result_variable = Variable::Unnamed(*variable_sequence);
@@ -876,21 +1427,25 @@ fn push_expression(Expression { expression, .. }: Expression, result: &mut Vec<H
                 return Ok((reachable, variable2));
             }
             result.push(HIRStatement::Binding {
+                position: position.clone(),
                 variable_id: *variable_sequence,
                 variable_type: HIRType::Infer,
                 expression: HIRExpression::CallFunction {
                     function: Variable::Named(expression_position.clone(), Vec::new(), get_candidate_paths(vec!["_gt".to_string()], imports)),
                     arguments: vec![variable1, variable2],
+                    iterator_next: false,
                 },
             });
             let tmp_variable = Variable::Unnamed(*variable_sequence);
             *variable_sequence += 1;
             result.push(HIRStatement::Binding {
+                position: position.clone(),
                 variable_id: *variable_sequence,
                 variable_type: HIRType::Infer,
                 expression: HIRExpression::CallFunction {
                     function: Variable::Named(expression_position, Vec::new(), get_candidate_paths(vec!["_not".to_string()], imports)),
                     arguments: vec![tmp_variable],
+                    iterator_next: false,
                 },
             });
             let result_variable = Variable::Unnamed(*variable_sequence);
@@ -908,11 +1463,13 @@ fn push_expression(Expression { expression, .. }: Expression, result: &mut Vec<H
                 return Ok((reachable, variable2));
             }
             result.push(HIRStatement::Binding {
+                position: position.clone(),
                 variable_id: *variable_sequence,
                 variable_type: HIRType::Infer,
                 expression: HIRExpression::CallFunction {
                     function: Variable::Named(expression_position, Vec::new(), get_candidate_paths(vec!["_eq".to_string()], imports)),
                     arguments: vec![variable1, variable2],
+                    iterator_next: false,
                 },
             });
             let result_variable = Variable::Unnamed(*variable_sequence);
@@ -930,21 +1487,25 @@ fn push_expression(Expression { expression, .. }: Expression, result: &mut Vec<H
                 return Ok((reachable, variable2));
             }
             result.push(HIRStatement::Binding {
+                position: position.clone(),
                 variable_id: *variable_sequence,
                 variable_type: HIRType::Infer,
                 expression: HIRExpression::CallFunction {
                     function: Variable::Named(expression_position.clone(), Vec::new(), get_candidate_paths(vec!["_eq".to_string()], imports)),
                     arguments: vec![variable1, variable2],
+                    iterator_next: false,
                 },
             });
             let tmp_variable = Variable::Unnamed(*variable_sequence);
             *variable_sequence += 1;
             result.push(HIRStatement::Binding {
+                position: position.clone(),
                 variable_id: *variable_sequence,
                 variable_type: HIRType::Infer,
                 expression: HIRExpression::CallFunction {
                     function: Variable::Named(expression_position, Vec::new(), get_candidate_paths(vec!["_not".to_string()], imports)),
                     arguments: vec![tmp_variable],
+                    iterator_next: false,
                 },
             });
             let result_variable = Variable::Unnamed(*variable_sequence);
@@ -962,11 +1523,13 @@ fn push_expression(Expression { expression, .. }: Expression, result: &mut Vec<H
                 return Ok((reachable, variable2));
             }
             result.push(HIRStatement::Binding {
+                position: position.clone(),
                 variable_id: *variable_sequence,
                 variable_type: HIRType::Infer,
                 expression: HIRExpression::CallFunction {
                     function: Variable::Named(expression_position, Vec::new(), get_candidate_paths(vec!["_and".to_string()], imports)),
                     arguments: vec![variable1, variable2],
+                    iterator_next: false,
                 },
             });
             let result_variable = Variable::Unnamed(*variable_sequence);
@@ -984,11 +1547,13 @@ fn push_expression(Expression { expression, .. }: Expression, result: &mut Vec<H
                 return Ok((reachable, variable2));
             }
             result.push(HIRStatement::Binding {
+                position: position.clone(),
                 variable_id: *variable_sequence,
                 variable_type: HIRType::Infer,
                 expression: HIRExpression::CallFunction {
                     function: Variable::Named(expression_position, Vec::new(), get_candidate_paths(vec!["_xor".to_string()], imports)),
                     arguments: vec![variable1, variable2],
+                    iterator_next: false,
                 },
             });
             let result_variable = Variable::Unnamed(*variable_sequence);
@@ -1006,22 +1571,74 @@ fn push_expression(Expression { expression, .. }: Expression, result: &mut Vec<H
                 return Ok((reachable, variable2));
             }
             result.push(HIRStatement::Binding {
+                position: position.clone(),
                 variable_id: *variable_sequence,
                 variable_type: HIRType::Infer,
                 expression: HIRExpression::CallFunction {
                     function: Variable::Named(expression_position, Vec::new(), get_candidate_paths(vec!["_or".to_string()], imports)),
                     arguments: vec![variable1, variable2],
+                    iterator_next: false,
+                },
+            });
+            let result_variable = Variable::Unnamed(*variable_sequence);
+            *variable_sequence += 1;
+            Ok((StatementReachable::Reachable, result_variable))
+        }
+        ExpressionItem::Range(expression1, expression2) => {
+            let expression_position = expression1.position.end..expression2.position.start;
+            let (reachable, variable1) = push_expression(*expression1, result, variables, variable_sequence, imports, generics_argument_names)?;
+            if reachable != StatementReachable::Reachable {
+                return Ok((reachable, variable1));
+            }
+            let (reachable, variable2) = push_expression(*expression2, result, variables, variable_sequence, imports, generics_argument_names)?;
+            if reachable != StatementReachable::Reachable {
+                return Ok((reachable, variable2));
+            }
+            result.push(HIRStatement::Binding {
+                position: position.clone(),
+                variable_id: *variable_sequence,
+                variable_type: HIRType::Infer,
+                expression: HIRExpression::CallFunction {
+                    function: Variable::Named(expression_position, Vec::new(), get_candidate_paths(vec!["_range".to_string()], imports)),
+                    arguments: vec![variable1, variable2],
+                    iterator_next: false,
+                },
+            });
+            let result_variable = Variable::Unnamed(*variable_sequence);
+            *variable_sequence += 1;
+            Ok((StatementReachable::Reachable, result_variable))
+        }
+        ExpressionItem::RangeInclusive(expression1, expression2) => {
+            let expression_position = expression1.position.end..expression2.position.start;
+            let (reachable, variable1) = push_expression(*expression1, result, variables, variable_sequence, imports, generics_argument_names)?;
+            if reachable != StatementReachable::Reachable {
+                return Ok((reachable, variable1));
+            }
+            let (reachable, variable2) = push_expression(*expression2, result, variables, variable_sequence, imports, generics_argument_names)?;
+            if reachable != StatementReachable::Reachable {
+                return Ok((reachable, variable2));
+            }
+            result.push(HIRStatement::Binding {
+                position: position.clone(),
+                variable_id: *variable_sequence,
+                variable_type: HIRType::Infer,
+                expression: HIRExpression::CallFunction {
+                    function: Variable::Named(expression_position, Vec::new(), get_candidate_paths(vec!["_range_inclusive".to_string()], imports)),
+                    arguments: vec![variable1, variable2],
+                    iterator_next: false,
                 },
             });
             let result_variable = Variable::Unnamed(*variable_sequence);
             *variable_sequence += 1;
             Ok((StatementReachable::Reachable, result_variable))
         }
+        ExpressionItem::LogicalAnd(expression1, expression2) => push_short_circuit_expression(true, *expression1, *expression2, result, variables, variable_sequence, imports, generics_argument_names),
+        ExpressionItem::LogicalOr(expression1, expression2) => push_short_circuit_expression(false, *expression1, *expression2, result, variables, variable_sequence, imports, generics_argument_names),
         ExpressionItem::Assign(reference_expression, value_expression) => {
             let value_expression = *value_expression;
             match *reference_expression {
                 Expression {
-                    expression: ExpressionItem::AccessVariable(Path { position, path }, _), ..
+                    expression: ExpressionItem::AccessVariable(Path { position: path_position, path }, _), ..
                 } => {
                     let (reachable, value_variable) = push_expression(value_expression, result, variables, variable_sequence, imports, generics_argument_names)?;
                     if reachable != StatementReachable::Reachable {
@@ -1029,10 +1646,11 @@ fn push_expression(Expression { expression, .. }: Expression, result: &mut Vec<H
                     }
                     let variable_reference = if let [name] = AsRef::<[String]>::as_ref(&path) { variables.get(name) } else { None };
                     result.push(HIRStatement::Binding {
+                        position: position.clone(),
                         variable_id: *variable_sequence,
                         variable_type: HIRType::Infer,
                         expression: HIRExpression::Assign {
-                            variable: ReferenceAccess::Variable(variable_reference.copied().map(Variable::Unnamed).unwrap_or_else(|| Variable::Named(position, Vec::new(), get_candidate_paths(path, imports)))),
+                            variable: ReferenceAccess::Variable(variable_reference.copied().map(Variable::Unnamed).unwrap_or_else(|| Variable::Named(path_position, Vec::new(), get_candidate_paths(path, imports)))),
                             value: value_variable,
                         },
                     });
@@ -1050,6 +1668,7 @@ fn push_expression(Expression { expression, .. }: Expression, result: &mut Vec<H
                         return Ok((reachable, value));
                     }
                     result.push(HIRStatement::Binding {
+                        position: position.clone(),
                         variable_id: *variable_sequence,
                         variable_type: HIRType::Infer,
                         expression: HIRExpression::Assign { variable: ReferenceAccess::TupleItem(base, index), value },
@@ -1068,6 +1687,7 @@ fn push_expression(Expression { expression, .. }: Expression, result: &mut Vec<H
                         return Ok((reachable, value));
                     }
                     result.push(HIRStatement::Binding {
+                        position: position.clone(),
                         variable_id: *variable_sequence,
                         variable_type: HIRType::Infer,
                         expression: HIRExpression::Assign { variable: ReferenceAccess::Member(base, member), value },
@@ -1091,20 +1711,87 @@ fn push_expression(Expression { expression, .. }: Expression, result: &mut Vec<H
                         return Ok((reachable, value));
                     }
                     result.push(HIRStatement::Binding {
+                        position: position.clone(),
                         variable_id: *variable_sequence,
                         variable_type: HIRType::Infer,
                         expression: HIRExpression::CallFunction {
                             function: Variable::Named(index_position, Vec::new(), get_candidate_paths(vec!["_set_item".to_string()], imports)),
                             arguments: vec![base, index, value.clone()],
+                            iterator_next: false,
                         },
                     });
                     *variable_sequence += 1;
                     Ok((StatementReachable::Reachable, value))
                 }
-                _ => unreachable!(),
+                Expression { position, .. } => Err(AnalysisStatementError::InvalidAssignmentTarget(position)),
             }
         }
-        ExpressionItem::Closure(_) => Err(AnalysisStatementError::UnImplementedFeature("ConstructClosure")),
+        ExpressionItem::AddAssign(reference_expression, value_expression) => push_compound_assign_expression("_add", *reference_expression, *value_expression, result, variables, variable_sequence, imports, generics_argument_names),
+        ExpressionItem::SubAssign(reference_expression, value_expression) => push_compound_assign_expression("_sub", *reference_expression, *value_expression, result, variables, variable_sequence, imports, generics_argument_names),
+        ExpressionItem::MulAssign(reference_expression, value_expression) => push_compound_assign_expression("_mul", *reference_expression, *value_expression, result, variables, variable_sequence, imports, generics_argument_names),
+        ExpressionItem::DivAssign(reference_expression, value_expression) => push_compound_assign_expression("_div", *reference_expression, *value_expression, result, variables, variable_sequence, imports, generics_argument_names),
+        ExpressionItem::RemAssign(reference_expression, value_expression) => push_compound_assign_expression("_rem", *reference_expression, *value_expression, result, variables, variable_sequence, imports, generics_argument_names),
+        ExpressionItem::Closure(Closure { arguments, expression, .. }) => {
+            // クロージャ内の変数は0から振りなおし、外側の変数は引数の直後に割り当てる
+            let mut closure_variables = BTreeMap::new();
+            let mut closure_variable_sequence = arguments.len();
+            let mut outer_variables = BTreeMap::new();
+            for (name, id) in variables.iter() {
+                let inner_id = *outer_variables.entry(*id).or_insert_with(|| {
+                    closure_variable_sequence += 1;
+                    closure_variable_sequence - 1
+                });
+                closure_variables.insert(name.clone(), inner_id);
+            }
+            let mut argument_types = Vec::with_capacity(arguments.len());
+            let mut deconstruct_argument = Vec::with_capacity(arguments.len());
+            for (id, (pattern, type_hint)) in arguments.into_iter().enumerate() {
+                let argument_type = type_hint.map_or_else(|| HIRType::try_from(&pattern), HIRType::try_from)?;
+                argument_types.push(resolve_type_names(argument_type, generics_argument_names, imports));
+                let argument_name = format!("__argument_{}", id);
+                deconstruct_argument.push(Statement {
+                    position: Default::default(),
+                    statement: StatementItem::LetBinding(LetBinding {
+                        position: Default::default(),
+                        pattern,
+                        type_hint: None,
+                        expression: Box::new(Expression {
+                            position: Default::default(),
+                            expression: ExpressionItem::AccessVariable(Path { position: Default::default(), path: vec![argument_name.clone()] }, Vec::new()),
+                        }),
+                        public: false,
+                    }),
+                });
+                closure_variables.insert(argument_name, id);
+            }
+            let body_position = expression.position.clone();
+            let mut body = Vec::new();
+            let mut closure_imports = imports.clone();
+            for stmt in deconstruct_argument.into_iter().chain(Some(Statement { position: body_position, statement: StatementItem::Return(Some(*expression)) })) {
+                if push_statement(stmt, &mut body, &mut closure_variables, &mut closure_variable_sequence, &mut closure_imports, generics_argument_names)? != StatementReachable::Reachable {
+                    break;
+                }
+            }
+            let mut referenced_variables = BTreeSet::new();
+            collect_referenced_variables(&body, &mut referenced_variables);
+            // キャプチャは値のコピーなので、クロージャ内での代入は外側の変数に反映されない
+            let mut assigned_variables = BTreeSet::new();
+            collect_assigned_variables(&body, &mut assigned_variables);
+            if let Some((outer_id, _)) = outer_variables.iter().find(|(_, inner_id)| assigned_variables.contains(inner_id)) {
+                let name = variables.iter().find(|(_, id)| *id == outer_id).map(|(name, _)| name.clone()).unwrap_or_default();
+                return Err(AnalysisStatementError::AssignToCapturedVariable(name));
+            }
+            let captures = outer_variables.into_iter().filter(|(_, inner_id)| referenced_variables.contains(inner_id)).map(|(outer_id, inner_id)| (inner_id, Variable::Unnamed(outer_id))).collect();
+            result.push(HIRStatement::Binding {
+                position: position.clone(),
+                variable_id: *variable_sequence,
+                variable_type: HIRType::Infer,
+                expression: HIRExpression::ConstructClosure { captures, arguments: argument_types, return_type: HIRType::Infer, body },
+            });
+            let variable = Variable::Unnamed(*variable_sequence);
+            *variable_sequence += 1;
+            Ok((StatementReachable::Reachable, variable))
+        }
         ExpressionItem::If(If { condition, then, other, .. }) => match *condition {
             Condition::BoolExpression(condition) => {
                 let (reachable, condition) = push_expression(condition, result, variables, variable_sequence, imports, generics_argument_names)?;
@@ -1126,6 +1813,7 @@ fn push_expression(Expression { expression, .. }: Expression, result: &mut Vec<H
                     *variable_sequence += 1;
                     (
                         vec![HIRStatement::Binding {
+                            position: position.clone(),
                             variable_id: *variable_sequence - 1,
                             variable_type: HIRType::Tuple(Vec::new()),
                             expression: HIRExpression::ConstructTuple(Vec::new()),
@@ -1134,6 +1822,7 @@ fn push_expression(Expression { expression, .. }: Expression, result: &mut Vec<H
                     )
                 };
                 result.push(HIRStatement::Binding {
+                    position: position.clone(),
                     variable_id: *variable_sequence,
                     variable_type: HIRType::Infer,
                     expression: HIRExpression::If { condition, then, other },
@@ -1149,10 +1838,10 @@ fn push_expression(Expression { expression, .. }: Expression, result: &mut Vec<H
                 }
                 let condition_binding = *variable_sequence;
                 *variable_sequence += 1;
-                let pattern_type = apply_generics_type_argument(type_hint.map_or_else(|| HIRType::try_from(&pattern), HIRType::try_from)?, generics_argument_names);
+                let pattern_type = resolve_type_names(type_hint.map_or_else(|| HIRType::try_from(&pattern), HIRType::try_from)?, generics_argument_names, imports);
                 let mut then_statements = Vec::new();
                 let mut then_variables = variables.clone();
-                push_deconstruct_pattern(pattern, Variable::Unnamed(condition_binding), &mut then_statements, &mut then_variables, variable_sequence, imports)?;
+                push_deconstruct_pattern(&position, pattern, Variable::Unnamed(condition_binding), &mut then_statements, &mut then_variables, variable_sequence, imports)?;
                 let (mut reachable, then_result) = push_expression(*then, &mut then_statements, &mut then_variables, variable_sequence, imports, generics_argument_names)?;
                 let then = (then_statements, then_result);
                 let other = if let Some(other) = other {
@@ -1166,6 +1855,7 @@ fn push_expression(Expression { expression, .. }: Expression, result: &mut Vec<H
                     *variable_sequence += 1;
                     (
                         vec![HIRStatement::Binding {
+                            position: position.clone(),
                             variable_id: *variable_sequence - 1,
                             variable_type: HIRType::Tuple(Vec::new()),
                             expression: HIRExpression::ConstructTuple(Vec::new()),
@@ -1174,6 +1864,7 @@ fn push_expression(Expression { expression, .. }: Expression, result: &mut Vec<H
                     )
                 };
                 result.push(HIRStatement::Binding {
+                    position: position.clone(),
                     variable_id: *variable_sequence,
                     variable_type: HIRType::Infer,
                     expression: HIRExpression::IfLet {
@@ -1182,6 +1873,7 @@ fn push_expression(Expression { expression, .. }: Expression, result: &mut Vec<H
                         condition: condition_variable,
                         then,
                         other,
+                        kind: IfLetKind::IfLet,
                     },
                 });
                 let result = Variable::Unnamed(*variable_sequence);
@@ -1189,6 +1881,94 @@ fn push_expression(Expression { expression, .. }: Expression, result: &mut Vec<H
                 Ok((reachable, result))
             }
         },
-        ExpressionItem::Match(_) => Err(AnalysisStatementError::UnImplementedFeature("MatchExpression")),
+        ExpressionItem::Loop(Loop { label, block: Block { statements, last_expression, .. }, .. }) => {
+            assert!(last_expression.is_none(), "loopのブロックは最後の式を持たないはず");
+            let mut body = Vec::new();
+            let mut variables = variables.clone();
+            let mut imports = imports.clone();
+            push_loop_body(statements, &mut body, &mut variables, variable_sequence, &mut imports, generics_argument_names)?;
+            result.push(HIRStatement::Binding {
+                position: position.clone(),
+                variable_id: *variable_sequence,
+                variable_type: HIRType::Infer,
+                expression: HIRExpression::Loop(label, body),
+            });
+            let result = Variable::Unnamed(*variable_sequence);
+            *variable_sequence += 1;
+            Ok((StatementReachable::Reachable, result))
+        }
+        ExpressionItem::Match(Match { condition, items, .. }) => {
+            let (reachable, condition_variable) = push_expression(*condition, result, variables, variable_sequence, imports, generics_argument_names)?;
+            if reachable != StatementReachable::Reachable {
+                return Ok((reachable, condition_variable));
+            }
+            let condition_variable = bind_unnamed(&position, condition_variable, result, variable_sequence);
+            let mut arms = Vec::with_capacity(items.len());
+            let mut reachable: Option<StatementReachable> = None;
+            for (pattern, expression) in items {
+                let condition_binding = *variable_sequence;
+                *variable_sequence += 1;
+                let pattern_type = resolve_type_names(HIRType::try_from(&pattern)?, generics_argument_names, imports);
+                let mut arm_statements = Vec::new();
+                let mut arm_variables = variables.clone();
+                push_deconstruct_pattern(&position, pattern, Variable::Unnamed(condition_binding), &mut arm_statements, &mut arm_variables, variable_sequence, imports)?;
+                let (arm_reachable, arm_result) = push_expression(expression, &mut arm_statements, &mut arm_variables, variable_sequence, imports, generics_argument_names)?;
+                match &mut reachable {
+                    None => reachable = Some(arm_reachable),
+                    Some(reachable) => reachable.combine(arm_reachable),
+                }
+                arms.push((condition_binding, pattern_type, arm_statements, arm_result));
+            }
+            // 最後の腕から順にIfLetを入れ子にしていく(最後のelseには到達しないことを型検査で確かめる)
+            let mut other = (vec![HIRStatement::Unreachable], condition_variable.clone());
+            for (condition_binding, pattern_type, arm_statements, arm_result) in arms.into_iter().rev() {
+                other = (
+                    vec![HIRStatement::Binding {
+                        position: position.clone(),
+                        variable_id: *variable_sequence,
+                        variable_type: HIRType::Infer,
+                        expression: HIRExpression::IfLet {
+                            condition_binding,
+                            pattern_type,
+                            condition: condition_variable.clone(),
+                            then: (arm_statements, arm_result),
+                            other,
+                            kind: IfLetKind::MatchArm,
+                        },
+                    }],
+                    Variable::Unnamed(*variable_sequence),
+                );
+                *variable_sequence += 1;
+            }
+            let (mut statements, result_variable) = other;
+            result.append(&mut statements);
+            Ok((reachable.unwrap_or(StatementReachable::Reachable), result_variable))
+        }
+        ExpressionItem::Try(expression) => {
+            let (reachable, variable) = push_expression(*expression, result, variables, variable_sequence, imports, generics_argument_names)?;
+            if reachable != StatementReachable::Reachable {
+                return Ok((reachable, variable));
+            }
+            let condition_variable = bind_unnamed(&position, variable, result, variable_sequence);
+            let condition_binding = *variable_sequence;
+            *variable_sequence += 1;
+            // match x { e: #{error: String} => return e, x => x } と同じ扱いにする
+            result.push(HIRStatement::Binding {
+                position: position.clone(),
+                variable_id: *variable_sequence,
+                variable_type: HIRType::Infer,
+                expression: HIRExpression::IfLet {
+                    condition_binding,
+                    pattern_type: error_type(),
+                    condition: condition_variable.clone(),
+                    then: (vec![HIRStatement::Return(position.clone(), Variable::Unnamed(condition_binding))], Variable::Unnamed(condition_binding)),
+                    other: (Vec::new(), condition_variable),
+                    kind: IfLetKind::Try,
+                },
+            });
+            let result_variable = Variable::Unnamed(*variable_sequence);
+            *variable_sequence += 1;
+            Ok((StatementReachable::Reachable, result_variable))
+        }
     }
 }
@@ -1,7 +1,8 @@
-use std::collections::{BTreeMap, HashSet};
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap};
 use std::error::Error;
 use std::fmt::{Display, Formatter};
-use std::mem;
+use std::{iter, mem};
 use std::ops::{Deref, Range};
 
 use kirl_common::typing::HIRType;
@@ -11,7 +12,7 @@ use kirl_parser::CharacterPosition;
 
 use crate::name_resolver::ResolvedItems;
 use crate::syntax_tree_to_hir::SearchPaths;
-use crate::{HIRExpression, HIRStatement, Immediate, ReferenceAccess, Variable};
+use crate::{HIRExpression, HIRGenericsBound, HIRStatement, HIRStructDefinition, IfLetKind, Immediate, ReferenceAccess, Variable};
 
 #[derive(Debug)]
 pub enum DecisionTypeError {
@@ -19,6 +20,14 @@ pub enum DecisionTypeError {
     ConflictUuid(Uuid),
     TypeMismatched { expected: HIRType, actual: HIRType },
     NamedReferenceIsNotUnique { position: Range<CharacterPosition>, found: ResolvedItems },
+    UnreachableMatchArm { pattern: HIRType, condition: HIRType },
+    NonExhaustiveMatch { remaining: HIRType },
+    UnknownStruct { path: Vec<String> },
+    WrongGenericsArgumentCount { struct_type: HIRType, expected: usize },
+    UnsatisfiedGenericsBound { position: Range<CharacterPosition>, function: Vec<String>, bound: String, required: String, actual: HIRType },
+    InvalidIteratorNext { position: Range<CharacterPosition>, iterator: HIRType, actual: HIRType },
+    InfallibleTry { position: Range<CharacterPosition>, actual: HIRType },
+    YieldOutsideGenerator,
 }
 
 impl Display for DecisionTypeError {
@@ -27,6 +36,14 @@ impl Display for DecisionTypeError {
             DecisionTypeError::UnImplementedFeature(feature) => write!(f, "A feature {:?} is not implemented yet.", feature),
             DecisionTypeError::ConflictUuid(id) => write!(f, "Reference id {} is conflicted.", id),
             DecisionTypeError::TypeMismatched { expected, actual } => write!(f, "Expected type {} but found {}.", expected.to_string(), actual.to_string()),
+            DecisionTypeError::UnreachableMatchArm { pattern, condition } => write!(f, "A match arm with pattern {} never matches a value of type {}.", pattern.to_string(), condition.to_string()),
+            DecisionTypeError::NonExhaustiveMatch { remaining } => write!(f, "A match is not exhaustive. Values of type {} are not covered.", remaining.to_string()),
+            DecisionTypeError::UnknownStruct { path } => write!(f, "A struct {} is not found.", path.join("::")),
+            DecisionTypeError::WrongGenericsArgumentCount { struct_type, expected } => write!(f, "A struct type {} requires {} generic arguments.", struct_type.to_string(), expected),
+            DecisionTypeError::UnsatisfiedGenericsBound { position, function, bound, required, actual } => write!(f, "A type {} does not satisfy the bound {} of {} at {} - {} because {} is not defined for it.", actual.to_string(), bound, function.join("::"), position.start, position.end, required),
+            DecisionTypeError::InvalidIteratorNext { position, iterator, actual } => write!(f, "A function _next for {} at {} - {} must return () | #{{value: T}} but returns {}.", iterator.to_string(), position.start, position.end, actual.to_string()),
+            DecisionTypeError::InfallibleTry { position, actual } => write!(f, "An operand of ? at {} - {} has type {} which never contains an error #{{error: String}}.", position.start, position.end, actual.to_string()),
+            DecisionTypeError::YieldOutsideGenerator => write!(f, "A yield statement is only allowed in a generator function (fn*)."),
             DecisionTypeError::NamedReferenceIsNotUnique { position, found: ResolvedItems(SearchPaths(paths), candidates) } => {
                 let (path_last, path_other) = paths.split_last().expect("少なくともコードに書いた名前があるのでパスの候補は一つ以上あるはず");
                 let paths = {
@@ -72,9 +89,33 @@ impl Display for DecisionTypeError {
 
 impl Error for DecisionTypeError {}
 
-type DecisionTypeResult<T> = Result<T, DecisionTypeError>;
+impl DecisionTypeError {
+    // エラーに含まれる型を表示用に書き換える
+    pub fn map_types(self, mut f: impl FnMut(HIRType) -> HIRType) -> Self {
+        match self {
+            DecisionTypeError::TypeMismatched { expected, actual } => DecisionTypeError::TypeMismatched { expected: f(expected), actual: f(actual) },
+            DecisionTypeError::NamedReferenceIsNotUnique { position, found: ResolvedItems(paths, candidates) } => DecisionTypeError::NamedReferenceIsNotUnique {
+                position,
+                found: ResolvedItems(paths, candidates.into_iter().map(|(path, id, ty)| (path, id, f(ty))).collect()),
+            },
+            DecisionTypeError::UnreachableMatchArm { pattern, condition } => DecisionTypeError::UnreachableMatchArm { pattern: f(pattern), condition: f(condition) },
+            DecisionTypeError::NonExhaustiveMatch { remaining } => DecisionTypeError::NonExhaustiveMatch { remaining: f(remaining) },
+            DecisionTypeError::WrongGenericsArgumentCount { struct_type, expected } => DecisionTypeError::WrongGenericsArgumentCount { struct_type: f(struct_type), expected },
+            DecisionTypeError::UnsatisfiedGenericsBound { position, function, bound, required, actual } => DecisionTypeError::UnsatisfiedGenericsBound { position, function, bound, required, actual: f(actual) },
+            DecisionTypeError::InvalidIteratorNext { position, iterator, actual } => DecisionTypeError::InvalidIteratorNext { position, iterator: f(iterator), actual: f(actual) },
+            DecisionTypeError::InfallibleTry { position, actual } => DecisionTypeError::InfallibleTry { position, actual: f(actual) },
+            error @ (DecisionTypeError::UnImplementedFeature(_) | DecisionTypeError::ConflictUuid(_) | DecisionTypeError::UnknownStruct { .. } | DecisionTypeError::YieldOutsideGenerator) => error,
+        }
+    }
+}
+
+pub(crate) type DecisionTypeResult<T> = Result<T, DecisionTypeError>;
 
 pub fn is_a_with_generics(lhs: &HIRType, rhs: &HIRType, generics_type_arguments: &mut Vec<HIRType>) -> bool {
+    is_a_with_generics_inner(lhs, rhs, generics_type_arguments, &mut Vec::new())
+}
+
+fn is_a_with_generics_inner(lhs: &HIRType, rhs: &HIRType, generics_type_arguments: &mut Vec<HIRType>, assumptions: &mut Vec<(HIRType, HIRType)>) -> bool {
     match (lhs, rhs) {
         (lhs, HIRType::GenericsTypeArgument(i)) => match generics_type_arguments.get(*i) {
             None | Some(HIRType::Infer) => {
@@ -90,31 +131,188 @@ pub fn is_a_with_generics(lhs: &HIRType, rhs: &HIRType, generics_type_arguments:
         (_, HIRType::Infer) => true,
         (HIRType::Unreachable, _) => true,
         (_, HIRType::Unreachable) => false,
-        (HIRType::Named { path: path1, generics_arguments: arg1 }, HIRType::Named { path: path2, generics_arguments: arg2 }) => path1 == path2 && arg1.len() == arg2.len() && arg1.iter().zip(arg2).all(|(ty1, ty2)| is_a_with_generics(ty1, ty2, generics_type_arguments)),
-        (HIRType::Tuple(items1), HIRType::Tuple(items2)) => items1.len() >= items2.len() && items1.iter().zip(items2).all(|(ty1, ty2)| is_a_with_generics(ty1, ty2, generics_type_arguments)),
-        (HIRType::Array(t1), HIRType::Array(t2)) => is_a_with_generics(t1, t2, generics_type_arguments),
-        (HIRType::Function { arguments: arg1, result: res1 }, HIRType::Function { arguments: arg2, result: res2 }) => arg1.len() == arg2.len() && arg2.iter().zip(arg1).all(|(ty1, ty2)| is_a_with_generics(ty1, ty2, generics_type_arguments)) && is_a_with_generics(res1, res2, generics_type_arguments),
-        (HIRType::AnonymousStruct(members1), HIRType::AnonymousStruct(members2)) => members2.iter().all(|(k, v2)| members1.get(k).map_or(false, |v1| is_a_with_generics(v1, v2, generics_type_arguments))),
-        (HIRType::Or(items1), ty2) => items1.iter().all(|ty1| is_a_with_generics(ty1, ty2, generics_type_arguments)),
-        (ty1, HIRType::Or(items2)) => items2.iter().any(|ty2| is_a_with_generics(ty1, ty2, generics_type_arguments)),
+        // 再帰的な型は比較中の組を仮定として真とみなして展開する
+        (ty1 @ HIRType::Recursive { .. }, ty2) | (ty1, ty2 @ HIRType::Recursive { .. }) => {
+            if assumptions.iter().any(|(assumed1, assumed2)| assumed1 == ty1 && assumed2 == ty2) {
+                return true;
+            }
+            assumptions.push((ty1.clone(), ty2.clone()));
+            let result = is_a_with_generics_inner(&ty1.unfold_recursive(), &ty2.unfold_recursive(), generics_type_arguments, assumptions);
+            assumptions.pop();
+            result
+        }
+        (HIRType::Named { path: path1, generics_arguments: arg1 }, HIRType::Named { path: path2, generics_arguments: arg2 }) => path1 == path2 && arg1.len() == arg2.len() && arg1.iter().zip(arg2).all(|(ty1, ty2)| is_a_with_generics_inner(ty1, ty2, generics_type_arguments, assumptions)),
+        (HIRType::Tuple(items1), HIRType::Tuple(items2)) => items1.len() >= items2.len() && items1.iter().zip(items2).all(|(ty1, ty2)| is_a_with_generics_inner(ty1, ty2, generics_type_arguments, assumptions)),
+        (HIRType::Array(t1), HIRType::Array(t2)) => is_a_with_generics_inner(t1, t2, generics_type_arguments, assumptions),
+        (HIRType::Function { arguments: arg1, result: res1 }, HIRType::Function { arguments: arg2, result: res2 }) => arg1.len() == arg2.len() && arg2.iter().zip(arg1).all(|(ty1, ty2)| is_a_with_generics_inner(ty1, ty2, generics_type_arguments, assumptions)) && is_a_with_generics_inner(res1, res2, generics_type_arguments, assumptions),
+        (HIRType::AnonymousStruct(members1), HIRType::AnonymousStruct(members2)) => members2.iter().all(|(k, v2)| members1.get(k).map_or(false, |v1| is_a_with_generics_inner(v1, v2, generics_type_arguments, assumptions))),
+        (HIRType::Or(items1), ty2) => items1.iter().all(|ty1| is_a_with_generics_inner(ty1, ty2, generics_type_arguments, assumptions)),
+        (ty1, HIRType::Or(items2)) => items2.iter().any(|ty2| is_a_with_generics_inner(ty1, ty2, generics_type_arguments, assumptions)),
         _ => false,
     }
 }
 
-pub fn decision_type(mut statements: Vec<HIRStatement<ResolvedItems>>, argument_types: Vec<HIRType>, return_type: HIRType) -> DecisionTypeResult<Vec<HIRStatement<(Uuid, HIRType)>>> {
+// 名前付き構造体のメンバの型は定義から求める
+fn member_type(ty: &HIRType, member: &str, struct_definitions: &HashMap<Vec<String>, HIRStructDefinition>) -> Option<HIRType> {
+    match ty {
+        HIRType::Named { path, generics_arguments } => {
+            let HIRStructDefinition { generics_argument_count, members } = struct_definitions.get(path)?;
+            if generics_arguments.is_empty() {
+                members.get(member)?.apply_generics_type_argument(&vec![HIRType::Infer; *generics_argument_count])
+            } else {
+                members.get(member)?.apply_generics_type_argument(generics_arguments)
+            }
+        }
+        HIRType::Or(items) => items.iter().map(|ty| member_type(ty, member, struct_definitions)).collect::<Option<Vec<_>>>().map(HIRType::Or),
+        ty @ HIRType::Recursive { .. } => member_type(&ty.unfold_recursive(), member, struct_definitions),
+        ty => ty.member_type(member).map(Cow::into_owned),
+    }
+}
+
+// 名前付き構造体の型には定義と同じ数の型引数を書かなければならない(パターンと構築を除く)
+pub fn check_generics_argument_count(ty: &HIRType, struct_definitions: &HashMap<Vec<String>, HIRStructDefinition>) -> DecisionTypeResult<()> {
+    match ty {
+        HIRType::Named { path, generics_arguments } => {
+            if let Some(HIRStructDefinition { generics_argument_count, .. }) = struct_definitions.get(path) {
+                if generics_arguments.len() != *generics_argument_count {
+                    return Err(DecisionTypeError::WrongGenericsArgumentCount { struct_type: ty.clone(), expected: *generics_argument_count });
+                }
+            }
+            generics_arguments.iter().try_for_each(|ty| check_generics_argument_count(ty, struct_definitions))
+        }
+        HIRType::Tuple(items) | HIRType::Or(items) => items.iter().try_for_each(|ty| check_generics_argument_count(ty, struct_definitions)),
+        HIRType::Array(item) => check_generics_argument_count(item, struct_definitions),
+        HIRType::Function { arguments, result } => arguments.iter().chain(iter::once(&**result)).try_for_each(|ty| check_generics_argument_count(ty, struct_definitions)),
+        HIRType::AnonymousStruct(members) => members.values().try_for_each(|ty| check_generics_argument_count(ty, struct_definitions)),
+        HIRType::Recursive { body, .. } => check_generics_argument_count(body, struct_definitions),
+        HIRType::Infer | HIRType::Unreachable | HIRType::GenericsTypeArgument(_) | HIRType::RecursiveReference(_) => Ok(()),
+    }
+}
+
+// パターンで型引数を省略した名前付き構造体は、どの型引数のものにも一致するように型引数を推論に任せる
+fn fill_omitted_generics_arguments(ty: &mut HIRType, struct_definitions: &HashMap<Vec<String>, HIRStructDefinition>) {
+    match ty {
+        HIRType::Named { path, generics_arguments } if generics_arguments.is_empty() => {
+            if let Some(HIRStructDefinition { generics_argument_count, .. }) = struct_definitions.get(path) {
+                generics_arguments.resize(*generics_argument_count, HIRType::Infer);
+            }
+        }
+        HIRType::Named { generics_arguments: items, .. } | HIRType::Tuple(items) | HIRType::Or(items) => items.iter_mut().for_each(|ty| fill_omitted_generics_arguments(ty, struct_definitions)),
+        HIRType::Array(item) => fill_omitted_generics_arguments(item, struct_definitions),
+        HIRType::AnonymousStruct(members) => members.values_mut().for_each(|ty| fill_omitted_generics_arguments(ty, struct_definitions)),
+        _ => {}
+    }
+}
+
+// 型引数の制約のうち、与えられた型引数で呼び出せる関数が見つからないものを返す
+// 呼び出し元の型引数が渡された場合は、呼び出し元でも同じ制約が宣言されていなければならない
+fn unsatisfied_generics_bound<'a>(bounds: &'a [HIRGenericsBound<ResolvedItems>], generics_arguments: &[HIRType], own_bounds: &[HIRGenericsBound<ResolvedItems>]) -> Option<(&'a HIRGenericsBound<ResolvedItems>, HIRType)> {
+    bounds.iter().find_map(|bound| {
+        let actual = generics_arguments.get(bound.generics_argument)?;
+        match actual {
+            // 推論できなかった型は、具体的な型が決まるまで検査できない
+            HIRType::Infer => return None,
+            HIRType::GenericsTypeArgument(i) => {
+                let declared = own_bounds.iter().any(|own| own.generics_argument == *i && own.bound == bound.bound);
+                return if declared { None } else { Some((bound, actual.clone())) };
+            }
+            _ => {}
+        }
+        let candidates = match &bound.function {
+            Variable::Named(_, _, ResolvedItems(_, candidates)) => candidates,
+            Variable::Unnamed(_) => unreachable!("制約の関数は常に名前で参照する"),
+        };
+        let satisfied = candidates.iter().any(|(_, _, function_type)| match function_type {
+            HIRType::Function { arguments, .. } if arguments.len() == bound.argument_count => {
+                let mut generics_arguments = Vec::new();
+                arguments.iter().all(|formal| is_a_with_generics(actual, formal, &mut generics_arguments))
+            }
+            _ => false,
+        });
+        if satisfied {
+            None
+        } else {
+            Some((bound, actual.clone()))
+        }
+    })
+}
+
+// 構造体のメンバーやタプルの要素の和型を外側へ展開する(#{a: A | B}を#{a: A} | #{a: B}にする)
+// 組み合わせが多すぎる場合は展開しない
+const MAX_DISTRIBUTED_TYPES: usize = 64;
+
+fn distribute_or(ty: &HIRType) -> Vec<HIRType> {
+    fn product(alternatives: Vec<Vec<HIRType>>) -> Option<Vec<Vec<HIRType>>> {
+        let mut result = vec![Vec::new()];
+        for alternative in alternatives {
+            if result.len() * alternative.len() > MAX_DISTRIBUTED_TYPES {
+                return None;
+            }
+            result = result.into_iter().flat_map(|items| alternative.iter().map(move |ty| items.iter().cloned().chain([ty.clone()]).collect())).collect();
+        }
+        Some(result)
+    }
+    match ty {
+        HIRType::Or(items) => items.iter().flat_map(distribute_or).collect(),
+        HIRType::Tuple(items) => match product(items.iter().map(distribute_or).collect()) {
+            Some(items) => items.into_iter().map(HIRType::Tuple).collect(),
+            None => vec![ty.clone()],
+        },
+        HIRType::AnonymousStruct(members) => match product(members.values().map(distribute_or).collect()) {
+            Some(items) => items.into_iter().map(|items| HIRType::AnonymousStruct(members.keys().cloned().zip(items).collect())).collect(),
+            None => vec![ty.clone()],
+        },
+        ty => vec![ty.clone()],
+    }
+}
+
+fn exclude_type(ty: &HIRType, excluded: &HIRType) -> HIRType {
+    match ty {
+        ty @ HIRType::Recursive { .. } if !ty.is_a(excluded) => exclude_type(&ty.unfold_recursive(), excluded),
+        ty if ty.is_a(excluded) => HIRType::Unreachable,
+        ty => {
+            // メンバーの和型の一部だけに一致するパターンもあるので、展開してから除く
+            let items = distribute_or(ty);
+            if !items.iter().any(|item| item.is_a(excluded)) {
+                return ty.clone();
+            }
+            let items = items.into_iter().filter(|item| !item.is_a(excluded)).collect::<Vec<_>>();
+            if items.is_empty() {
+                HIRType::Unreachable
+            } else {
+                HIRType::Or(items).into_normalized()
+            }
+        }
+    }
+}
+
+// 条件を満たす候補が一つだけならそれに絞り込む(一つも満たさなければ元の候補を全て報告する)
+fn narrow_candidates(position: &Range<CharacterPosition>, paths: &mut SearchPaths, candidates: &mut Vec<(Vec<String>, Uuid, HIRType)>, predicate: impl Fn(&HIRType) -> bool) -> DecisionTypeResult<()> {
+    let matched: Vec<_> = candidates.iter().filter(|(_, _, ty)| predicate(ty)).cloned().collect();
+    match matched.len() {
+        1 => {
+            *candidates = matched;
+            Ok(())
+        }
+        0 => Err(DecisionTypeError::NamedReferenceIsNotUnique { position: position.clone(), found: ResolvedItems(mem::take(paths), mem::take(candidates)) }),
+        _ => Err(DecisionTypeError::NamedReferenceIsNotUnique { position: position.clone(), found: ResolvedItems(mem::take(paths), matched) }),
+    }
+}
+
+pub fn decision_type(mut statements: Vec<HIRStatement<ResolvedItems>>, argument_types: Vec<HIRType>, return_type: HIRType, struct_definitions: &HashMap<Vec<String>, HIRStructDefinition>, generics_bounds: &HashMap<Uuid, Vec<HIRGenericsBound<ResolvedItems>>>, own_generics_bounds: &[HIRGenericsBound<ResolvedItems>]) -> DecisionTypeResult<(Vec<HIRStatement<(Uuid, HIRType)>>, Vec<HIRType>)> {
     #[derive(Debug)]
     enum Reachable {
         Reachable,
         Unreachable,
     }
-    fn decision_type_inner(statements: &mut [HIRStatement<ResolvedItems>], types: &mut Vec<HIRType>, return_type: &HIRType) -> DecisionTypeResult<Reachable> {
-        fn decision_type_one(stmt: &mut HIRStatement<ResolvedItems>, types: &mut Vec<HIRType>, return_type: &HIRType) -> DecisionTypeResult<Reachable> {
+    fn decision_type_inner(statements: &mut [HIRStatement<ResolvedItems>], types: &mut Vec<HIRType>, return_type: &HIRType, yield_type: Option<&HIRType>, struct_definitions: &HashMap<Vec<String>, HIRStructDefinition>, generics_bounds: &HashMap<Uuid, Vec<HIRGenericsBound<ResolvedItems>>>, own_generics_bounds: &[HIRGenericsBound<ResolvedItems>]) -> DecisionTypeResult<Reachable> {
+        fn decision_type_one(stmt: &mut HIRStatement<ResolvedItems>, types: &mut Vec<HIRType>, return_type: &HIRType, yield_type: Option<&HIRType>, struct_definitions: &HashMap<Vec<String>, HIRStructDefinition>, generics_bounds: &HashMap<Uuid, Vec<HIRGenericsBound<ResolvedItems>>>, own_generics_bounds: &[HIRGenericsBound<ResolvedItems>]) -> DecisionTypeResult<Reachable> {
             match stmt {
-                //TODO:loop式を導入するときはもうちょっと考えないとだめ
-                HIRStatement::Binding { variable_id, variable_type, expression } => {
+                HIRStatement::Binding { position: binding_position, variable_id, variable_type, expression } => {
                     if types.len() <= *variable_id {
                         types.resize_with(*variable_id + 1, || HIRType::Infer);
                     }
+                    check_generics_argument_count(variable_type, struct_definitions)?;
                     let (result_type, reachable) = match expression {
                         HIRExpression::Immediate(value) => match value {
                             Immediate::Number(_) => {
@@ -132,12 +330,46 @@ pub fn decision_type(mut statements: Vec<HIRStatement<ResolvedItems>>, argument_
                                 (type_string, Reachable::Reachable)
                             }
                         },
-                        HIRExpression::CallFunction { function, arguments: actual_arguments } => {
+                        HIRExpression::CallFunction { function: Variable::Unnamed(function), arguments: actual_arguments, .. } => match &types[*function] {
+                            HIRType::Function { arguments: formal_arguments, result } if formal_arguments.len() == actual_arguments.len() => {
+                                for (formal, actual) in formal_arguments.iter().zip(actual_arguments.iter_mut()) {
+                                    match actual {
+                                        Variable::Named(position, _, ResolvedItems(paths, candidates)) => {
+                                            narrow_candidates(position, paths, candidates, |ty| ty.is_a(formal))?;
+                                        }
+                                        Variable::Unnamed(id) => {
+                                            if !types[*id].is_a(formal) {
+                                                return Err(DecisionTypeError::TypeMismatched { expected: formal.clone(), actual: types[*id].clone() });
+                                            }
+                                        }
+                                    }
+                                }
+                                (result.deref().clone(), Reachable::Reachable)
+                            }
+                            function_type => {
+                                return Err(DecisionTypeError::TypeMismatched {
+                                    expected: HIRType::Function { arguments: vec![HIRType::Infer; actual_arguments.len()], result: Box::new(HIRType::Infer) },
+                                    actual: function_type.clone(),
+                                });
+                            }
+                        },
+                        HIRExpression::CallFunction { function, arguments: actual_arguments, .. } => {
                             let (position, generics_arguments, ResolvedItems(paths, function)) = match function {
                                 Variable::Named(position, generics_arguments, candidates) => (position.clone(), generics_arguments.as_slice(), candidates),
-                                Variable::Unnamed(_) => {
-                                    return Err(DecisionTypeError::UnImplementedFeature("Call function referenced by variable"));
+                                Variable::Unnamed(_) => unreachable!("変数経由の呼び出しは直前のアームで処理している"),
+                            };
+                            // 型は合うが型引数の制約を満たさなかった候補
+                            let mut unsatisfied = None;
+                            let mut check_bounds = |path: &Vec<String>, id: &Uuid, generics_arguments: &[HIRType]| match generics_bounds.get(id).and_then(|bounds| unsatisfied_generics_bound(bounds, generics_arguments, own_generics_bounds)) {
+                                Some((bound, actual)) => {
+                                    let required = match &bound.function {
+                                        Variable::Named(_, _, ResolvedItems(SearchPaths(paths), _)) => paths.first().and_then(|path| path.last()).cloned().unwrap_or_default(),
+                                        Variable::Unnamed(_) => String::new(),
+                                    };
+                                    unsatisfied.get_or_insert_with(|| DecisionTypeError::UnsatisfiedGenericsBound { position: position.clone(), function: path.clone(), bound: bound.bound.clone(), required, actual });
+                                    false
                                 }
+                                None => true,
                             };
                             if generics_arguments.is_empty() {
                                 take_mut::take(function, |function| {
@@ -150,7 +382,8 @@ pub fn decision_type(mut statements: Vec<HIRStatement<ResolvedItems>>, argument_
                                                     if actual_arguments.iter().zip(formal_arguments).all(|(actual, formal)| match actual {
                                                         Variable::Named(_, _, ResolvedItems(_, candidates)) => candidates.iter().any(|(_, _, ty)| is_a_with_generics(ty, formal, &mut generics_arguments) /*ty.is_a(formal)*/),
                                                         Variable::Unnamed(id) => is_a_with_generics(&types[*id], formal, &mut generics_arguments), /*types[*id].is_a(formal)*/
-                                                    }) {
+                                                    }) && check_bounds(&path, &id, &generics_arguments)
+                                                    {
                                                         Some((path, id, ty.apply_generics_type_argument(&generics_arguments)?))
                                                     } else {
                                                         None
@@ -176,7 +409,8 @@ pub fn decision_type(mut statements: Vec<HIRStatement<ResolvedItems>>, argument_
                                                 if actual_arguments.iter().zip(&formal_arguments).all(|(actual, formal)| match actual {
                                                     Variable::Named(_, _, ResolvedItems(_, candidates)) => candidates.iter().any(|(_, _, ty)| ty.is_a(formal)),
                                                     Variable::Unnamed(id) => types[*id].is_a(formal),
-                                                }) {
+                                                }) && check_bounds(&path, &id, generics_arguments)
+                                                {
                                                     Some((path, id, HIRType::Function { arguments: formal_arguments, result: Box::new(result) }))
                                                 } else {
                                                     None
@@ -192,47 +426,32 @@ pub fn decision_type(mut statements: Vec<HIRStatement<ResolvedItems>>, argument_
                                 if let HIRType::Function { arguments, result } = function_type {
                                     for (formal, actual) in arguments.iter().zip(actual_arguments) {
                                         if let Variable::Named(position, _, ResolvedItems(paths, candidates)) = actual {
-                                            candidates.retain(|(_, _, ty)| ty.is_a(formal));
-                                            if candidates.len() != 1 {
-                                                return Err(DecisionTypeError::NamedReferenceIsNotUnique {
-                                                    position: position.clone(),
-                                                    found: ResolvedItems(mem::take(paths), mem::take(candidates)),
-                                                });
-                                            }
+                                            narrow_candidates(position, paths, candidates, |ty| ty.is_a(formal))?;
                                         }
                                     }
                                     (result.deref().clone(), Reachable::Reachable)
                                 } else {
                                     unreachable!("retainでチェックしてるのであんりーちゃぶる")
                                 }
+                            } else if let (true, Some(unsatisfied)) = (function.is_empty(), unsatisfied) {
+                                return Err(unsatisfied);
                             } else {
                                 return Err(DecisionTypeError::NamedReferenceIsNotUnique { position, found: ResolvedItems(mem::take(paths), mem::take(function)) });
                             }
                         }
                         HIRExpression::AccessVariable(variable) => match variable {
                             Variable::Named(position, _, ResolvedItems(paths, candidates)) => {
-                                if let [(_, _, ty)] = candidates.as_slice() {
-                                    (ty.clone(), Reachable::Reachable)
-                                } else {
-                                    return Err(DecisionTypeError::NamedReferenceIsNotUnique {
-                                        position: position.clone(),
-                                        found: ResolvedItems(mem::take(paths), mem::take(candidates)),
-                                    });
-                                }
+                                narrow_candidates(position, paths, candidates, |ty| ty.is_a(variable_type))?;
+                                let ty = &candidates[0].2;
+                                (ty.clone(), Reachable::Reachable)
                             }
                             Variable::Unnamed(id) => (types[*id].clone(), Reachable::Reachable),
                         },
                         HIRExpression::AccessTupleItem { variable, index } => match variable {
                             Variable::Named(position, _, ResolvedItems(paths, candidates)) => {
-                                candidates.retain(|(_, _, ty)| ty.has_tuple_item(*index));
-                                if let [(_, _, ty)] = candidates.as_slice() {
-                                    (ty.tuple_item_type(*index).unwrap().into_owned(), Reachable::Reachable)
-                                } else {
-                                    return Err(DecisionTypeError::NamedReferenceIsNotUnique {
-                                        position: position.clone(),
-                                        found: ResolvedItems(mem::take(paths), mem::take(candidates)),
-                                    });
-                                }
+                                narrow_candidates(position, paths, candidates, |ty| ty.has_tuple_item(*index))?;
+                                let ty = &candidates[0].2;
+                                (ty.tuple_item_type(*index).unwrap().into_owned(), Reachable::Reachable)
                             }
                             Variable::Unnamed(id) => {
                                 if let Some(ty) = types[*id].tuple_item_type(*index) {
@@ -247,19 +466,13 @@ pub fn decision_type(mut statements: Vec<HIRStatement<ResolvedItems>>, argument_
                         },
                         HIRExpression::AccessMember { variable, member } => match variable {
                             Variable::Named(position, _, ResolvedItems(paths, candidates)) => {
-                                candidates.retain(|(_, _, ty)| ty.has_member(member));
-                                if let [(_, _, ty)] = candidates.as_slice() {
-                                    (ty.member_type(member).unwrap().into_owned(), Reachable::Reachable)
-                                } else {
-                                    return Err(DecisionTypeError::NamedReferenceIsNotUnique {
-                                        position: position.clone(),
-                                        found: ResolvedItems(mem::take(paths), mem::take(candidates)),
-                                    });
-                                }
+                                narrow_candidates(position, paths, candidates, |ty| member_type(ty, member, struct_definitions).is_some())?;
+                                let ty = &candidates[0].2;
+                                (member_type(ty, member, struct_definitions).unwrap(), Reachable::Reachable)
                             }
                             Variable::Unnamed(id) => {
-                                if let Some(ty) = types[*id].member_type(member) {
-                                    (ty.into_owned(), Reachable::Reachable)
+                                if let Some(ty) = member_type(&types[*id], member, struct_definitions) {
+                                    (ty, Reachable::Reachable)
                                 } else {
                                     return Err(DecisionTypeError::TypeMismatched {
                                         expected: HIRType::AnonymousStruct([(member.clone(), HIRType::Infer)].into_iter().collect()),
@@ -276,13 +489,7 @@ pub fn decision_type(mut statements: Vec<HIRStatement<ResolvedItems>>, argument_
                             let bool_type = HIRType::Named { path: vec!["Bool".to_string()], generics_arguments: Vec::new() };
                             match condition {
                                 Variable::Named(position, _, ResolvedItems(paths, candidates)) => {
-                                    candidates.retain(|(_, _, ty)| ty.is_a(&bool_type));
-                                    if candidates.len() != 1 {
-                                        return Err(DecisionTypeError::NamedReferenceIsNotUnique {
-                                            position: position.clone(),
-                                            found: ResolvedItems(mem::take(paths), mem::take(candidates)),
-                                        });
-                                    }
+                                    narrow_candidates(position, paths, candidates, |ty| ty.is_a(&bool_type))?;
                                 }
                                 Variable::Unnamed(id) => {
                                     if !types[*id].is_a(&bool_type) {
@@ -291,7 +498,7 @@ pub fn decision_type(mut statements: Vec<HIRStatement<ResolvedItems>>, argument_
                                 }
                             }
                             let mut unreachable = true;
-                            let then_type = match decision_type_inner(then_statements, types, return_type)? {
+                            let then_type = match decision_type_inner(then_statements, types, return_type, yield_type, struct_definitions, generics_bounds, own_generics_bounds)? {
                                 Reachable::Unreachable => HIRType::Unreachable,
                                 Reachable::Reachable => {
                                     unreachable = false;
@@ -310,7 +517,7 @@ pub fn decision_type(mut statements: Vec<HIRStatement<ResolvedItems>>, argument_
                                     }
                                 }
                             };
-                            let other_type = match decision_type_inner(other_statements, types, return_type)? {
+                            let other_type = match decision_type_inner(other_statements, types, return_type, yield_type, struct_definitions, generics_bounds, own_generics_bounds)? {
                                 Reachable::Unreachable => HIRType::Unreachable,
                                 Reachable::Reachable => {
                                     unreachable = false;
@@ -339,21 +546,46 @@ pub fn decision_type(mut statements: Vec<HIRStatement<ResolvedItems>>, argument_
                             condition,
                             then: (then_statements, then_expr),
                             other: (other_statements, other_expr),
+                            kind,
                         } => {
+                            fill_omitted_generics_arguments(pattern_type, struct_definitions);
                             let (possibility_assign, is_a, mut condition_type) = match condition {
                                 Variable::Named(position, _, ResolvedItems(paths, candidates)) => {
-                                    candidates.retain(|(_, _, ty)| ty.is_a(pattern_type));
-                                    if candidates.len() != 1 {
-                                        return Err(DecisionTypeError::NamedReferenceIsNotUnique {
-                                            position: position.clone(),
-                                            found: ResolvedItems(mem::take(paths), mem::take(candidates)),
-                                        });
-                                    }
+                                    narrow_candidates(position, paths, candidates, |ty| ty.is_a(pattern_type))?;
                                     (true, true, candidates[0].2.clone())
                                 }
                                 Variable::Unnamed(id) => (types[*id].possibility_assignable_to(pattern_type), types[*id].is_a(pattern_type), pattern_type.intersect_to(&types[*id])),
                             };
                             assert!(!is_a || possibility_assign); // is_a=trueなのにpossibility_assign=falseは無いよ
+                            // ?の対象がエラーになり得ない場合はmatchの腕のエラーではなく専用のエラーにする
+                            if *kind == IfLetKind::Try && !possibility_assign {
+                                let actual = match condition {
+                                    Variable::Named(_, _, ResolvedItems(_, candidates)) => candidates[0].2.clone(),
+                                    Variable::Unnamed(id) => types[*id].clone(),
+                                };
+                                return Err(DecisionTypeError::InfallibleTry { position: binding_position.clone(), actual });
+                            }
+                            // matchの腕の場合は、後続の腕からは既に一致した型を除いて考える
+                            let remaining_type = if *kind != IfLetKind::IfLet {
+                                let current_type = match condition {
+                                    Variable::Named(_, _, ResolvedItems(_, candidates)) => candidates[0].2.clone(),
+                                    Variable::Unnamed(id) => types[*id].clone(),
+                                };
+                                if current_type == HIRType::Unreachable || !possibility_assign {
+                                    return Err(DecisionTypeError::UnreachableMatchArm { pattern: pattern_type.clone(), condition: current_type });
+                                }
+                                let remaining_type = exclude_type(&current_type, pattern_type);
+                                match other_statements.as_slice() {
+                                    [HIRStatement::Unreachable] if remaining_type != HIRType::Unreachable => return Err(DecisionTypeError::NonExhaustiveMatch { remaining: remaining_type }),
+                                    [HIRStatement::Binding { expression: HIRExpression::IfLet { pattern_type: next_pattern, .. }, .. }] if remaining_type == HIRType::Unreachable => {
+                                        return Err(DecisionTypeError::UnreachableMatchArm { pattern: next_pattern.clone(), condition: remaining_type });
+                                    }
+                                    _ => {}
+                                }
+                                Some(remaining_type)
+                            } else {
+                                None
+                            };
                             if is_a {
                                 *other_statements = vec![HIRStatement::Unreachable];
                             }
@@ -364,7 +596,7 @@ pub fn decision_type(mut statements: Vec<HIRStatement<ResolvedItems>>, argument_
                             types[*condition_binding] = condition_type.clone();
                             *pattern_type = condition_type;
                             let mut unreachable = true;
-                            let then_type = match decision_type_inner(then_statements, types, return_type)? {
+                            let then_type = match decision_type_inner(then_statements, types, return_type, yield_type, struct_definitions, generics_bounds, own_generics_bounds)? {
                                 Reachable::Unreachable => HIRType::Unreachable,
                                 Reachable::Reachable => {
                                     unreachable = false;
@@ -383,7 +615,11 @@ pub fn decision_type(mut statements: Vec<HIRStatement<ResolvedItems>>, argument_
                                     }
                                 }
                             };
-                            let other_type = match decision_type_inner(other_statements, types, return_type)? {
+                            let narrowed = match (remaining_type, &*condition) {
+                                (Some(remaining_type), Variable::Unnamed(id)) => Some((*id, mem::replace(&mut types[*id], remaining_type))),
+                                _ => None,
+                            };
+                            let other_type = match decision_type_inner(other_statements, types, return_type, yield_type, struct_definitions, generics_bounds, own_generics_bounds)? {
                                 Reachable::Unreachable => HIRType::Unreachable,
                                 Reachable::Reachable => {
                                     unreachable = false;
@@ -402,19 +638,30 @@ pub fn decision_type(mut statements: Vec<HIRStatement<ResolvedItems>>, argument_
                                     }
                                 }
                             };
+                            if let Some((id, ty)) = narrowed {
+                                types[id] = ty;
+                            }
                             let mut result_type = HIRType::Or(vec![then_type, other_type]);
                             result_type.normalize();
                             (result_type, if unreachable { Reachable::Unreachable } else { Reachable::Reachable })
                         }
-                        HIRExpression::Loop(inner) => {
-                            decision_type_inner(inner, types, return_type)?;
-                            (HIRType::Tuple(Vec::new()), Reachable::Reachable)
+                        HIRExpression::Loop(label, inner) => {
+                            decision_type_inner(inner, types, return_type, yield_type, struct_definitions, generics_bounds, own_generics_bounds)?;
+                            let mut break_types = Vec::new();
+                            collect_break_types(inner, label.as_deref(), true, types, &mut break_types);
+                            if break_types.is_empty() {
+                                (HIRType::Unreachable, Reachable::Unreachable)
+                            } else {
+                                let mut result_type = HIRType::Or(break_types);
+                                result_type.normalize();
+                                (result_type, Reachable::Reachable)
+                            }
                         }
-                        HIRExpression::Assign { variable, value } => {
+                        HIRExpression::Assign { variable: variable_access, value } => {
                             let value_type = match value {
                                 Variable::Named(position, _, ResolvedItems(paths, candidates)) => {
                                     if let [(_, _, ty)] = candidates.as_slice() {
-                                        ty
+                                        ty.clone()
                                     } else {
                                         return Err(DecisionTypeError::NamedReferenceIsNotUnique {
                                             position: position.clone(),
@@ -422,59 +669,103 @@ pub fn decision_type(mut statements: Vec<HIRStatement<ResolvedItems>>, argument_
                                         });
                                     }
                                 }
-                                Variable::Unnamed(id) => &types[*id],
+                                Variable::Unnamed(id) => types[*id].clone(),
                             };
-                            match variable {
-                                ReferenceAccess::Variable(variable) => {
-                                    if let Variable::Unnamed(variable) = variable {
-                                        if value_type.is_a(&types[*variable]) {
-                                            (types[*variable].clone(), Reachable::Reachable)
+                            let (ReferenceAccess::Variable(variable) | ReferenceAccess::TupleItem(variable, _) | ReferenceAccess::Member(variable, _)) = &mut *variable_access;
+                            let variable_type = match variable {
+                                Variable::Named(position, _, ResolvedItems(paths, candidates)) => {
+                                    if let [(_, _, ty)] = candidates.as_slice() {
+                                        ty.clone()
+                                    } else {
+                                        return Err(DecisionTypeError::NamedReferenceIsNotUnique {
+                                            position: position.clone(),
+                                            found: ResolvedItems(mem::take(paths), mem::take(candidates)),
+                                        });
+                                    }
+                                }
+                                Variable::Unnamed(id) => types[*id].clone(),
+                            };
+                            match variable_access {
+                                ReferenceAccess::Variable(_) => {
+                                    if value_type.is_a(&variable_type) {
+                                        (variable_type, Reachable::Reachable)
+                                    } else {
+                                        return Err(DecisionTypeError::TypeMismatched { expected: variable_type, actual: value_type });
+                                    }
+                                }
+                                ReferenceAccess::TupleItem(_, index) => {
+                                    if let Some(ty) = variable_type.tuple_item_type(*index) {
+                                        if value_type.is_a(&ty) {
+                                            (ty.into_owned(), Reachable::Reachable)
                                         } else {
-                                            return Err(DecisionTypeError::TypeMismatched { expected: types[*variable].clone(), actual: value_type.clone() });
+                                            return Err(DecisionTypeError::TypeMismatched { expected: ty.into_owned(), actual: value_type });
                                         }
                                     } else {
-                                        return Err(DecisionTypeError::UnImplementedFeature("assign to un-local variable"));
+                                        return Err(DecisionTypeError::TypeMismatched {
+                                            expected: HIRType::Tuple([HIRType::Infer].into_iter().cycle().take(*index).chain([value_type]).collect()),
+                                            actual: variable_type,
+                                        });
                                     }
                                 }
-                                ReferenceAccess::TupleItem(variable, index) => {
-                                    if let Variable::Unnamed(variable) = variable {
-                                        let variable_type = &types[*variable];
-                                        if let Some(ty) = variable_type.tuple_item_type(*index) {
-                                            if value_type.is_a(&ty) {
-                                                (ty.into_owned(), Reachable::Reachable)
-                                            } else {
-                                                return Err(DecisionTypeError::TypeMismatched { expected: ty.into_owned(), actual: value_type.clone() });
-                                            }
+                                ReferenceAccess::Member(_, member) => {
+                                    if let Some(ty) = member_type(&variable_type, member, struct_definitions) {
+                                        if value_type.is_a(&ty) {
+                                            (ty, Reachable::Reachable)
                                         } else {
-                                            return Err(DecisionTypeError::TypeMismatched {
-                                                expected: HIRType::Tuple([HIRType::Infer].into_iter().cycle().take(*index).chain([value_type.clone()]).collect()),
-                                                actual: variable_type.clone(),
-                                            });
+                                            return Err(DecisionTypeError::TypeMismatched { expected: ty, actual: value_type });
                                         }
                                     } else {
-                                        return Err(DecisionTypeError::UnImplementedFeature("assign to un-local variable"));
+                                        return Err(DecisionTypeError::TypeMismatched {
+                                            expected: HIRType::AnonymousStruct([(member.clone(), value_type)].into_iter().collect()),
+                                            actual: variable_type,
+                                        });
                                     }
                                 }
-                                ReferenceAccess::Member(variable, member) => {
-                                    if let Variable::Unnamed(variable) = variable {
-                                        let variable_type = &types[*variable];
-                                        if let Some(ty) = variable_type.member_type(member) {
-                                            if value_type.is_a(&ty) {
-                                                (ty.into_owned(), Reachable::Reachable)
-                                            } else {
-                                                return Err(DecisionTypeError::TypeMismatched { expected: ty.into_owned(), actual: value_type.clone() });
-                                            }
+                            }
+                        }
+                        HIRExpression::ConstructClosure { captures, arguments, return_type: closure_return_type, body } => {
+                            let mut closure_types = arguments.clone();
+                            for (inner_id, variable) in captures.iter_mut() {
+                                let ty = match variable {
+                                    Variable::Named(position, _, ResolvedItems(paths, candidates)) => {
+                                        if let [(_, _, ty)] = candidates.as_slice() {
+                                            ty.clone()
                                         } else {
-                                            return Err(DecisionTypeError::TypeMismatched {
-                                                expected: HIRType::AnonymousStruct([(member.clone(), value_type.clone())].into_iter().collect()),
-                                                actual: variable_type.clone(),
+                                            return Err(DecisionTypeError::NamedReferenceIsNotUnique {
+                                                position: position.clone(),
+                                                found: ResolvedItems(mem::take(paths), mem::take(candidates)),
                                             });
                                         }
-                                    } else {
-                                        return Err(DecisionTypeError::UnImplementedFeature("assign to un-local variable"));
                                     }
+                                    Variable::Unnamed(id) => types[*id].clone(),
+                                };
+                                if closure_types.len() <= *inner_id {
+                                    closure_types.resize_with(*inner_id + 1, || HIRType::Infer);
                                 }
+                                closure_types[*inner_id] = ty;
                             }
+                            decision_type_inner(body, &mut closure_types, closure_return_type, None, struct_definitions, generics_bounds, own_generics_bounds)?;
+                            let mut returned_types = Vec::new();
+                            collect_return_types(body, &closure_types, &mut returned_types);
+                            *closure_return_type = if returned_types.is_empty() {
+                                HIRType::Unreachable
+                            } else {
+                                let mut result_type = HIRType::Or(returned_types);
+                                result_type.normalize();
+                                result_type
+                            };
+                            (HIRType::Function { arguments: arguments.clone(), result: Box::new(closure_return_type.clone()) }, Reachable::Reachable)
+                        }
+                        // ジェネレータの本体は最後まで実行されると()を返して終わる
+                        HIRExpression::ConstructGenerator { item_type, body } => {
+                            decision_type_inner(body, types, &HIRType::Tuple(Vec::new()), Some(&*item_type), struct_definitions, generics_bounds, own_generics_bounds)?;
+                            (
+                                HIRType::Named {
+                                    path: vec!["std".to_string(), "iter".to_string(), "Iterator".to_string()],
+                                    generics_arguments: vec![item_type.clone()],
+                                },
+                                Reachable::Reachable,
+                            )
                         }
                         HIRExpression::ConstructStruct(items) => {
                             let mut items_type = BTreeMap::new();
@@ -496,6 +787,41 @@ pub fn decision_type(mut statements: Vec<HIRStatement<ResolvedItems>>, argument_
                             }
                             (HIRType::AnonymousStruct(items_type), Reachable::Reachable)
                         }
+                        HIRExpression::ConstructNamedStruct { struct_type, members } => {
+                            let (path, generics_arguments) = if let HIRType::Named { path, generics_arguments } = &*struct_type { (path.clone(), generics_arguments.clone()) } else { unreachable!("名前付き構造体の型は常にNamedのはず") };
+                            let HIRStructDefinition { generics_argument_count, members: formal_members } = struct_definitions.get(&path).ok_or_else(|| DecisionTypeError::UnknownStruct { path: path.clone() })?;
+                            if !generics_arguments.is_empty() && generics_arguments.len() != *generics_argument_count {
+                                return Err(DecisionTypeError::WrongGenericsArgumentCount { struct_type: struct_type.clone(), expected: *generics_argument_count });
+                            }
+                            let mut actual_members = BTreeMap::new();
+                            for (member, variable) in members {
+                                let ty = match variable {
+                                    Variable::Named(position, _, ResolvedItems(paths, candidates)) => {
+                                        if let [(_, _, ty)] = candidates.as_slice() {
+                                            ty.clone()
+                                        } else {
+                                            return Err(DecisionTypeError::NamedReferenceIsNotUnique {
+                                                position: position.clone(),
+                                                found: ResolvedItems(mem::take(paths), mem::take(candidates)),
+                                            });
+                                        }
+                                    }
+                                    Variable::Unnamed(id) => types[*id].clone(),
+                                };
+                                actual_members.insert(member.clone(), ty);
+                            }
+                            // 型引数が省略されていればメンバの型から推論する
+                            let mut generics_arguments = generics_arguments;
+                            if actual_members.len() != formal_members.len() || !formal_members.iter().all(|(member, formal)| actual_members.get(member).map_or(false, |actual| is_a_with_generics(actual, formal, &mut generics_arguments))) {
+                                return Err(DecisionTypeError::TypeMismatched {
+                                    expected: HIRType::AnonymousStruct(formal_members.clone()),
+                                    actual: HIRType::AnonymousStruct(actual_members),
+                                });
+                            }
+                            generics_arguments.resize_with(*generics_argument_count, || HIRType::Infer);
+                            *struct_type = HIRType::Named { path, generics_arguments };
+                            (struct_type.clone(), Reachable::Reachable)
+                        }
                         HIRExpression::ConstructTuple(items) => {
                             let mut items_type = Vec::with_capacity(items.len());
                             for variable in items {
@@ -544,6 +870,12 @@ pub fn decision_type(mut statements: Vec<HIRStatement<ResolvedItems>>, argument_
                         }
                     };
                     if !result_type.is_a(variable_type) {
+                        // forから呼ばれた_nextの戻り値の形が違う
+                        if let HIRExpression::CallFunction { function: Variable::Named(position, _, _), arguments, iterator_next: true } = expression {
+                            if let [Variable::Unnamed(iterator)] = arguments.as_slice() {
+                                return Err(DecisionTypeError::InvalidIteratorNext { position: position.clone(), iterator: types[*iterator].clone(), actual: result_type });
+                            }
+                        }
                         return Err(DecisionTypeError::TypeMismatched { expected: variable_type.clone(), actual: result_type });
                     }
                     *variable_type = variable_type.intersect_to(&result_type);
@@ -551,16 +883,10 @@ pub fn decision_type(mut statements: Vec<HIRStatement<ResolvedItems>>, argument_
                     Ok(reachable)
                 }
                 HIRStatement::Unreachable => Ok(Reachable::Unreachable),
-                HIRStatement::Return(return_value) => {
+                HIRStatement::Return(_, return_value) => {
                     match return_value {
                         Variable::Named(position, _, ResolvedItems(paths, candidates)) => {
-                            candidates.retain(|(_, _, ty)| ty.is_a(return_type));
-                            if candidates.len() != 1 {
-                                return Err(DecisionTypeError::NamedReferenceIsNotUnique {
-                                    position: position.clone(),
-                                    found: ResolvedItems(mem::take(paths), mem::take(candidates)),
-                                });
-                            }
+                            narrow_candidates(position, paths, candidates, |ty| ty.is_a(return_type))?;
                         }
                         Variable::Unnamed(id) => {
                             if !types[*id].is_a(return_type) {
@@ -570,18 +896,92 @@ pub fn decision_type(mut statements: Vec<HIRStatement<ResolvedItems>>, argument_
                     }
                     Ok(Reachable::Unreachable)
                 }
+                HIRStatement::Yield(value) => {
+                    let yield_type = yield_type.ok_or(DecisionTypeError::YieldOutsideGenerator)?;
+                    match value {
+                        Variable::Named(position, _, ResolvedItems(paths, candidates)) => {
+                            narrow_candidates(position, paths, candidates, |ty| ty.is_a(yield_type))?;
+                        }
+                        Variable::Unnamed(id) => {
+                            if !types[*id].is_a(yield_type) {
+                                return Err(DecisionTypeError::TypeMismatched { expected: yield_type.clone(), actual: types[*id].clone() });
+                            }
+                        }
+                    }
+                    Ok(Reachable::Reachable)
+                }
                 HIRStatement::Continue(_) => Ok(Reachable::Unreachable),
-                HIRStatement::Break(_) => Ok(Reachable::Unreachable),
+                HIRStatement::Break(_, value) => {
+                    if let Some(Variable::Named(position, _, ResolvedItems(paths, candidates))) = value {
+                        if candidates.len() != 1 {
+                            return Err(DecisionTypeError::NamedReferenceIsNotUnique {
+                                position: position.clone(),
+                                found: ResolvedItems(mem::take(paths), mem::take(candidates)),
+                            });
+                        }
+                    }
+                    Ok(Reachable::Unreachable)
+                }
             }
         }
         for stmt in statements {
-            if let Reachable::Unreachable = decision_type_one(stmt, types, return_type)? {
+            if let Reachable::Unreachable = decision_type_one(stmt, types, return_type, yield_type, struct_definitions, generics_bounds, own_generics_bounds)? {
                 return Ok(Reachable::Unreachable);
             }
         }
         Ok(Reachable::Reachable)
     }
 
+    // クロージャの戻り値の型はreturnされる値の型の和とする(内側のクロージャの中は見ない)
+    fn collect_return_types(statements: &[HIRStatement<ResolvedItems>], types: &[HIRType], result: &mut Vec<HIRType>) {
+        for statement in statements {
+            match statement {
+                HIRStatement::Binding { expression, .. } => match expression {
+                    HIRExpression::If { then, other, .. } | HIRExpression::IfLet { then, other, .. } => {
+                        collect_return_types(&then.0, types, result);
+                        collect_return_types(&other.0, types, result);
+                    }
+                    HIRExpression::Loop(_, statements) => collect_return_types(statements, types, result),
+                    _ => {}
+                },
+                HIRStatement::Return(_, Variable::Named(_, _, ResolvedItems(_, candidates))) => result.extend(candidates.first().map(|(_, _, ty)| ty.clone())),
+                HIRStatement::Return(_, Variable::Unnamed(id)) => result.push(types[*id].clone()),
+                HIRStatement::Unreachable | HIRStatement::Yield(_) | HIRStatement::Continue(_) | HIRStatement::Break(..) => {}
+            }
+        }
+    }
+
+    // loop式の値の型はそのループを抜けるbreakの値の型の和とする(内側のクロージャの中は見ない)
+    fn collect_break_types(statements: &[HIRStatement<ResolvedItems>], label: Option<&str>, innermost: bool, types: &[HIRType], result: &mut Vec<HIRType>) {
+        for statement in statements {
+            match statement {
+                HIRStatement::Binding { expression, .. } => match expression {
+                    HIRExpression::If { then, other, .. } | HIRExpression::IfLet { then, other, .. } => {
+                        collect_break_types(&then.0, label, innermost, types, result);
+                        collect_break_types(&other.0, label, innermost, types, result);
+                    }
+                    // 内側のループではラベルの無いbreakと同じラベルのbreakは内側のループを抜ける
+                    HIRExpression::Loop(inner_label, statements) if label.is_some() && inner_label.as_deref() != label => collect_break_types(statements, label, false, types, result),
+                    _ => {}
+                },
+                HIRStatement::Break(break_label, value) => {
+                    let target = match break_label {
+                        Some(break_label) => Some(break_label.as_str()) == label,
+                        None => innermost,
+                    };
+                    if target {
+                        result.push(match value {
+                            None => HIRType::Tuple(Vec::new()),
+                            Some(Variable::Named(_, _, ResolvedItems(_, candidates))) => candidates.first().map_or(HIRType::Infer, |(_, _, ty)| ty.clone()),
+                            Some(Variable::Unnamed(id)) => types[*id].clone(),
+                        });
+                    }
+                }
+                HIRStatement::Unreachable | HIRStatement::Return(..) | HIRStatement::Yield(_) | HIRStatement::Continue(_) => {}
+            }
+        }
+    }
+
     fn into_one_candidate(statements: Vec<HIRStatement<ResolvedItems>>) -> Vec<HIRStatement<(Uuid, HIRType)>> {
         fn into_one(variable: Variable<ResolvedItems>) -> Variable<(Uuid, HIRType)> {
             match variable {
@@ -596,14 +996,16 @@ pub fn decision_type(mut statements: Vec<HIRStatement<ResolvedItems>>, argument_
         statements
             .into_iter()
             .map(|statement| match statement {
-                HIRStatement::Binding { variable_id, variable_type, expression } => HIRStatement::Binding {
+                HIRStatement::Binding { position, variable_id, variable_type, expression } => HIRStatement::Binding {
+                    position,
                     variable_id,
                     variable_type,
                     expression: match expression {
                         HIRExpression::Immediate(value) => HIRExpression::Immediate(value),
-                        HIRExpression::CallFunction { function, arguments } => HIRExpression::CallFunction {
+                        HIRExpression::CallFunction { function, arguments, iterator_next } => HIRExpression::CallFunction {
                             function: into_one(function),
                             arguments: arguments.into_iter().map(into_one).collect(),
+                            iterator_next,
                         },
                         HIRExpression::AccessVariable(variable) => HIRExpression::AccessVariable(into_one(variable)),
                         HIRExpression::AccessTupleItem { variable, index } => HIRExpression::AccessTupleItem { variable: into_one(variable), index },
@@ -623,14 +1025,16 @@ pub fn decision_type(mut statements: Vec<HIRStatement<ResolvedItems>>, argument_
                             condition,
                             then: (then_statements, then_expr),
                             other: (other_statements, other_expr),
+                            kind,
                         } => HIRExpression::IfLet {
                             condition_binding,
                             pattern_type,
                             condition: into_one(condition),
                             then: (into_one_candidate(then_statements), into_one(then_expr)),
                             other: (into_one_candidate(other_statements), into_one(other_expr)),
+                            kind,
                         },
-                        HIRExpression::Loop(stmt) => HIRExpression::Loop(into_one_candidate(stmt)),
+                        HIRExpression::Loop(label, stmt) => HIRExpression::Loop(label, into_one_candidate(stmt)),
                         HIRExpression::Assign { variable, value } => HIRExpression::Assign {
                             variable: match variable {
                                 ReferenceAccess::Variable(variable) => ReferenceAccess::Variable(into_one(variable)),
@@ -639,31 +1043,38 @@ pub fn decision_type(mut statements: Vec<HIRStatement<ResolvedItems>>, argument_
                             },
                             value: into_one(value),
                         },
+                        HIRExpression::ConstructClosure { captures, arguments, return_type, body } => HIRExpression::ConstructClosure {
+                            captures: captures.into_iter().map(|(id, variable)| (id, into_one(variable))).collect(),
+                            arguments,
+                            return_type,
+                            body: into_one_candidate(body),
+                        },
+                        HIRExpression::ConstructGenerator { item_type, body } => HIRExpression::ConstructGenerator { item_type, body: into_one_candidate(body) },
                         HIRExpression::ConstructStruct(items) => HIRExpression::ConstructStruct(items.into_iter().map(|(k, v)| (k, into_one(v))).collect()),
+                        HIRExpression::ConstructNamedStruct { struct_type, members } => HIRExpression::ConstructNamedStruct { struct_type, members: members.into_iter().map(|(k, v)| (k, into_one(v))).collect() },
                         HIRExpression::ConstructTuple(items) => HIRExpression::ConstructTuple(items.into_iter().map(into_one).collect()),
                         HIRExpression::ConstructArray(items) => HIRExpression::ConstructArray(items.into_iter().map(into_one).collect()),
                     },
                 },
                 HIRStatement::Unreachable => HIRStatement::Unreachable,
-                HIRStatement::Return(value) => HIRStatement::Return(into_one(value)),
+                HIRStatement::Return(position, value) => HIRStatement::Return(position, into_one(value)),
+                HIRStatement::Yield(value) => HIRStatement::Yield(into_one(value)),
                 HIRStatement::Continue(label) => HIRStatement::Continue(label),
-                HIRStatement::Break(label) => HIRStatement::Break(label),
+                HIRStatement::Break(label, value) => HIRStatement::Break(label, value.map(into_one)),
             })
             .collect()
     }
     let mut types = argument_types;
-    decision_type_inner(&mut statements, &mut types, &return_type)?;
-    Ok(into_one_candidate(statements))
+    decision_type_inner(&mut statements, &mut types, &return_type, None, struct_definitions, generics_bounds, own_generics_bounds)?;
+    Ok((into_one_candidate(statements), types))
 }
 
-pub fn used_functions(statements: &[HIRStatement<(Uuid, HIRType)>]) -> HashSet<Uuid> {
-    let mut result = HashSet::new();
-    fn inner(statements: &[HIRStatement<(Uuid, HIRType)>], result: &mut HashSet<Uuid>) {
-        fn add_used_variable(variable: &Variable<(Uuid, HIRType)>, result: &mut HashSet<Uuid>) {
+// 名前で参照されている関数やグローバル変数のIDと明示された型引数、型を順に渡す(IDは書き換えられる)
+pub fn for_each_used_function(statements: &mut [HIRStatement<(Uuid, HIRType)>], f: &mut impl FnMut(&mut Uuid, &[HIRType], &HIRType)) {
+    fn inner(statements: &mut [HIRStatement<(Uuid, HIRType)>], result: &mut impl FnMut(&mut Uuid, &[HIRType], &HIRType)) {
+        fn add_used_variable(variable: &mut Variable<(Uuid, HIRType)>, result: &mut impl FnMut(&mut Uuid, &[HIRType], &HIRType)) {
             match variable {
-                Variable::Named(_, _, (id, _)) => {
-                    result.insert(*id);
-                }
+                Variable::Named(_, generics_arguments, (id, ty)) => result(id, generics_arguments, ty),
                 Variable::Unnamed(_) => {}
             }
         }
@@ -671,7 +1082,7 @@ pub fn used_functions(statements: &[HIRStatement<(Uuid, HIRType)>]) -> HashSet<U
             match statement {
                 HIRStatement::Binding { expression, .. } => match expression {
                     HIRExpression::Immediate(_) => {}
-                    HIRExpression::CallFunction { function, arguments } => {
+                    HIRExpression::CallFunction { function, arguments, .. } => {
                         add_used_variable(function, result);
                         for argument in arguments {
                             add_used_variable(argument, result);
@@ -703,7 +1114,7 @@ pub fn used_functions(statements: &[HIRStatement<(Uuid, HIRType)>]) -> HashSet<U
                         inner(other_statements, result);
                         add_used_variable(other_result, result);
                     }
-                    HIRExpression::Loop(statements) => inner(statements, result),
+                    HIRExpression::Loop(_, statements) => inner(statements, result),
                     HIRExpression::Assign { variable, value } => {
                         match variable {
                             ReferenceAccess::Variable(variable) => add_used_variable(variable, result),
@@ -712,8 +1123,20 @@ pub fn used_functions(statements: &[HIRStatement<(Uuid, HIRType)>]) -> HashSet<U
                         }
                         add_used_variable(value, result);
                     }
+                    HIRExpression::ConstructClosure { captures, body, .. } => {
+                        for (_, capture) in captures {
+                            add_used_variable(capture, result);
+                        }
+                        inner(body, result);
+                    }
+                    HIRExpression::ConstructGenerator { body, .. } => inner(body, result),
                     HIRExpression::ConstructStruct(members) => {
-                        for member_value in members.values() {
+                        for member_value in members.values_mut() {
+                            add_used_variable(member_value, result);
+                        }
+                    }
+                    HIRExpression::ConstructNamedStruct { members, .. } => {
+                        for member_value in members.values_mut() {
                             add_used_variable(member_value, result);
                         }
                     }
@@ -729,12 +1152,15 @@ pub fn used_functions(statements: &[HIRStatement<(Uuid, HIRType)>]) -> HashSet<U
                     }
                 },
                 HIRStatement::Unreachable => {}
-                HIRStatement::Return(variable) => add_used_variable(variable, result),
+                HIRStatement::Return(_, variable) | HIRStatement::Yield(variable) => add_used_variable(variable, result),
                 HIRStatement::Continue(_) => {}
-                HIRStatement::Break(_) => {}
+                HIRStatement::Break(_, value) => {
+                    if let Some(value) = value {
+                        add_used_variable(value, result);
+                    }
+                }
             }
         }
     }
-    inner(statements, &mut result);
-    result
+    inner(statements, f);
 }
@@ -1,12 +1,12 @@
 use kirl_common::dec::Decimal128;
-use kirl_common::interface::{FunctionWrapper, InterchangeKirlVMValue, KirlRustFunction, KirlVMValue, KirlVMValueLock};
+use kirl_common::interface::{into_kirl_result, FunctionWrapper, InterchangeKirlVMValue, KirlRustFunction, KirlVMValue, KirlVMValueLock};
 use kirl_common::typing::{HIRType, LIRType};
 use kirl_common::{dec, get_type};
 use kirl_common_macro::kirl_function;
 use once_cell::sync::Lazy;
 use regex::Regex;
 use std::borrow::Cow;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::error::Error;
 use std::fmt::{Debug, Display, Formatter};
 use std::io;
@@ -48,8 +48,9 @@ impl From<HashMap<String, Vec<FunctionOrChildren>>> for FunctionOrChildren {
     }
 }
 
+// 登録した関数や値と、それらの型に現れるstd::以下の型の(std::を除いた)パス
 #[derive(Debug)]
-pub struct KirlStdLib(HashMap<String, Vec<FunctionOrChildren>>);
+pub struct KirlStdLib(HashMap<String, Vec<FunctionOrChildren>>, HashSet<Vec<String>>);
 
 impl<'a> KirlNameResolver for &'a FunctionOrChildren {
     fn resolve(&mut self, full_path: &[String]) -> Vec<(uuid::Uuid, HIRType)> {
@@ -66,16 +67,69 @@ impl<'a> KirlNameResolver for &'a FunctionOrChildren {
     }
 }
 
+// importしなくても見える名前と、それを探すモジュール
+const PRELUDE_NAMES: &[&str] = &["_add", "_sub", "_mul", "_div", "_rem", "_neg", "_not", "_eq", "_gt", "_and", "_or", "_xor", "true", "false", "_iterator", "_next", "_get_item", "_set_item", "to_string", "_range", "_range_inclusive"];
+const PRELUDE_MODULES: &[&[&str]] = &[&["num"], &["bool"], &["string"], &["collections", "list"], &["iter"]];
+
 impl<'a> KirlNameResolver for &'a KirlStdLib {
     fn resolve(&mut self, full_path: &[String]) -> Vec<(Uuid, HIRType)> {
         resolve_ref(&self.0, full_path)
     }
+
+    fn resolve_type(&mut self, full_path: &[String]) -> Option<Vec<String>> {
+        self.1.contains(full_path).then(|| full_path.to_vec())
+    }
+
+    fn resolve_prelude(&mut self, name: &str) -> Vec<(Vec<String>, Uuid, HIRType)> {
+        if !PRELUDE_NAMES.contains(&name) {
+            return Vec::new();
+        }
+        PRELUDE_MODULES
+            .iter()
+            .flat_map(|module| {
+                let path = module.iter().copied().chain([name]).map(String::from).collect::<Vec<_>>();
+                resolve_ref(&self.0, &path).into_iter().map(move |(id, ty)| (path.clone(), id, ty))
+            })
+            .collect()
+    }
 }
 
 fn resolve_ref(map: &HashMap<String, Vec<FunctionOrChildren>>, full_path: &[String]) -> Vec<(uuid::Uuid, HIRType)> {
     full_path.first().and_then(|key| map.get(key)).into_iter().flatten().flat_map(|mut resolver| resolver.resolve(&full_path[1..])).collect()
 }
 
+// 関数や値としては登録されていないがimportできるよう、型に現れるstd::以下の型のパスを集める
+fn collect_type_paths(ty: &HIRType, type_paths: &mut HashSet<Vec<String>>) {
+    match ty {
+        HIRType::Named { path, generics_arguments } => {
+            if let [root, path @ ..] = path.as_slice() {
+                if root == "std" {
+                    type_paths.insert(path.to_vec());
+                }
+            }
+            generics_arguments.iter().for_each(|ty| collect_type_paths(ty, type_paths));
+        }
+        HIRType::Tuple(items) | HIRType::Or(items) => items.iter().for_each(|ty| collect_type_paths(ty, type_paths)),
+        HIRType::Array(item) | HIRType::Recursive { body: item, .. } => collect_type_paths(item, type_paths),
+        HIRType::Function { arguments, result } => {
+            arguments.iter().for_each(|ty| collect_type_paths(ty, type_paths));
+            collect_type_paths(result, type_paths);
+        }
+        HIRType::AnonymousStruct(members) => members.values().for_each(|ty| collect_type_paths(ty, type_paths)),
+        HIRType::Infer | HIRType::Unreachable | HIRType::GenericsTypeArgument(_) | HIRType::RecursiveReference(_) => {}
+    }
+}
+
+fn contains_path_ref(map: &HashMap<String, Vec<FunctionOrChildren>>, full_path: &[String]) -> bool {
+    match full_path {
+        [] => true,
+        [key, rest @ ..] => map.get(key).into_iter().flatten().any(|item| match item {
+            FunctionOrChildren::Function(..) | FunctionOrChildren::StaticValue(..) => rest.is_empty(),
+            FunctionOrChildren::Children(children) => contains_path_ref(children, rest),
+        }),
+    }
+}
+
 struct KirlStdLibFunctions<'a> {
     functions: Vec<(Uuid, Arc<Mutex<dyn KirlRustFunction>>)>,
     maps: Vec<&'a HashMap<String, Vec<FunctionOrChildren>>>,
@@ -135,6 +189,20 @@ impl<'a> Iterator for KirlStdLibStaticValues<'a> {
 }
 
 impl KirlStdLib {
+    fn new(items: HashMap<String, Vec<FunctionOrChildren>>) -> Self {
+        let mut type_paths = HashSet::new();
+        let mut maps = vec![&items];
+        while let Some(map) = maps.pop() {
+            for item in map.values().flatten() {
+                match item {
+                    FunctionOrChildren::Function(_, _, ty) | FunctionOrChildren::StaticValue(_, _, ty) => collect_type_paths(ty, &mut type_paths),
+                    FunctionOrChildren::Children(children) => maps.push(children),
+                }
+            }
+        }
+        KirlStdLib(items, type_paths)
+    }
+
     pub fn functions(&self) -> impl IntoIterator<Item = (Uuid, Arc<Mutex<dyn KirlRustFunction>>)> + '_ {
         KirlStdLibFunctions { functions: Vec::new(), maps: vec![&self.0] }
     }
@@ -142,6 +210,11 @@ impl KirlStdLib {
     pub fn static_values(&self) -> impl IntoIterator<Item = (Uuid, Arc<dyn Fn() -> Arc<dyn KirlVMValueLock>>)> + '_ {
         KirlStdLibStaticValues { values: Vec::new(), maps: vec![&self.0] }
     }
+
+    // std::以下のパスがモジュール、関数、値、型のいずれかを指していればtrue
+    pub fn contains_path(&self, full_path: &[String]) -> bool {
+        self.1.contains(full_path) || contains_path_ref(&self.0, full_path)
+    }
 }
 
 macro_rules! count {
@@ -176,8 +249,99 @@ impl Display for NoneError {
 
 impl Error for NoneError {}
 
+#[derive(Debug)]
+struct IndexOutOfRange {
+    index: Decimal128,
+    length: usize,
+}
+
+impl Display for IndexOutOfRange {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Index {} is out of range for a list of length {}.", self.index.to_standard_notation_string(), self.length)
+    }
+}
+
+impl Error for IndexOutOfRange {}
+
+fn list_index(index: Decimal128, length: usize) -> Result<usize, IndexOutOfRange> {
+    usize::try_from(dec::Decimal::<15>::from(index)).ok().filter(|i| *i < length).ok_or(IndexOutOfRange { index, length })
+}
+
+#[derive(Debug)]
+struct ZeroRangeStep;
+
+impl Display for ZeroRangeStep {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Step of a range must not be zero.")
+    }
+}
+
+impl Error for ZeroRangeStep {}
+
+// a..b や a..=b で作られる範囲(stepが負なら逆向きに進む)
+#[derive(Debug, Clone, Copy)]
+struct KirlRange {
+    start: Decimal128,
+    end: Decimal128,
+    step: Decimal128,
+    inclusive: bool,
+}
+
+impl KirlRange {
+    fn new(start: Decimal128, end: Decimal128, inclusive: bool) -> Self {
+        KirlRange { start, end, step: Decimal128::from(1), inclusive }
+    }
+
+    fn before_end(&self, value: Decimal128) -> bool {
+        match (self.step.is_negative(), self.inclusive) {
+            (false, false) => value < self.end,
+            (false, true) => value <= self.end,
+            (true, false) => value > self.end,
+            (true, true) => value >= self.end,
+        }
+    }
+
+    fn contains(&self, value: Decimal128) -> bool {
+        let after_start = if self.step.is_negative() { value <= self.start } else { value >= self.start };
+        after_start && self.before_end(value) && ((value - self.start) % self.step).is_zero()
+    }
+
+    // 要素を数え上げずに、startからendまでに入るstepの数で要素の数を求める
+    fn len(&self) -> Decimal128 {
+        if !self.before_end(self.start) {
+            return Decimal128::from(0);
+        }
+        let distance = self.end - self.start;
+        let remainder = distance % self.step;
+        let whole_steps = (distance - remainder) / self.step;
+        if self.inclusive || !remainder.is_zero() {
+            whole_steps + Decimal128::from(1)
+        } else {
+            whole_steps
+        }
+    }
+
+    fn iter(self) -> impl Iterator<Item = Decimal128> {
+        std::iter::successors(Some(self.start), move |&i| Some(i + self.step)).take_while(move |&i| self.before_end(i))
+    }
+}
+
+impl KirlVMValue for KirlRange {
+    fn static_type() -> Cow<'static, LIRType>
+    where
+        Self: Sized,
+    {
+        static TYPE: Lazy<LIRType> = Lazy::new(|| get_type!((std::iter::Range)).into_normalized());
+        Cow::Borrowed(&*TYPE)
+    }
+
+    fn get_type(&self) -> Cow<LIRType> {
+        <Self as KirlVMValue>::static_type()
+    }
+}
+
 static STDLIB: Lazy<KirlStdLib> = Lazy::new(|| {
-    KirlStdLib(map! {
+    KirlStdLib::new(map! {
         io: map! {
             print: FunctionOrChildren::from_function({
                 #[kirl_function((String)->())]
@@ -199,20 +363,18 @@ static STDLIB: Lazy<KirlStdLib> = Lazy::new(|| {
             println: FunctionOrChildren::from_function(FunctionWrapper::from(|s: bool| Ok::<_, NoneError>(println!("{}", s)))),
             stdin: map! {
                 read_line: FunctionOrChildren::from_function({
-                    #[kirl_function(()->String)]
-                    fn stdin_read_line() -> Result<String, io::Error> {
+                    #[kirl_function(()->(String | #{error: String}))]
+                    fn stdin_read_line() -> Arc<dyn KirlVMValueLock> {
                         let mut result = String::new();
-                        io::stdin().read_line(&mut result)?;
-                        Ok(result)
+                        into_kirl_result(io::stdin().read_line(&mut result).map(|_| result))
                     }
                     stdin_read_line::new()
                 }),
                 read_all: FunctionOrChildren::from_function({
-                    #[kirl_function(()->String)]
-                    fn stdin_read_all() -> Result<String, io::Error> {
+                    #[kirl_function(()->(String | #{error: String}))]
+                    fn stdin_read_all() -> Arc<dyn KirlVMValueLock> {
                         let mut result = String::new();
-                        io::stdin().read_to_string(&mut result)?;
-                        Ok(result)
+                        into_kirl_result(io::stdin().read_to_string(&mut result).map(|_| result))
                     }
                     stdin_read_all::new()
                 }) ,
@@ -240,6 +402,20 @@ static STDLIB: Lazy<KirlStdLib> = Lazy::new(|| {
             to_string: FunctionOrChildren::from_function(FunctionWrapper::from(|a: Decimal128| Ok::<_, NoneError>(a.to_standard_notation_string()))),
         },
         string: map! {
+            _add: FunctionOrChildren::from_function({
+                #[kirl_function((String, String)->String)]
+                fn str_add(a: Arc<RwLock<String>>, b: Arc<RwLock<String>>) -> String {
+                    [&**a.read().unwrap(), &**b.read().unwrap()].concat()
+                }
+                str_add::new()
+            }),
+            to_string: FunctionOrChildren::from_function({
+                #[kirl_function((String)->String)]
+                fn str_to_string(s: Arc<RwLock<String>>) -> String {
+                    s.read().unwrap().clone()
+                }
+                str_to_string::new()
+            }),
             _eq: FunctionOrChildren::from_function({
                 #[kirl_function((String, String)->Bool)]
                 fn str_eq(a: Arc<RwLock<String>>, b: Arc<RwLock<String>>) -> bool {
@@ -256,18 +432,16 @@ static STDLIB: Lazy<KirlStdLib> = Lazy::new(|| {
             }),
             regex: map! {
                 is_match: FunctionOrChildren::from_function({
-                    #[kirl_function((String, String)->Bool)]
-                    fn is_match(input: Arc<RwLock<String>>, pattern: Arc<RwLock<String>>)->Result<bool, regex::Error>{
-                        let regex = Regex::new(&pattern.read().unwrap())?;
-                        Ok(regex.is_match(&input.read().unwrap()))
+                    #[kirl_function((String, String)->(Bool | #{error: String}))]
+                    fn is_match(input: Arc<RwLock<String>>, pattern: Arc<RwLock<String>>)->Arc<dyn KirlVMValueLock>{
+                        into_kirl_result(Regex::new(&pattern.read().unwrap()).map(|regex| regex.is_match(&input.read().unwrap())))
                     }
                     is_match::new()
                 }),
                 replace: FunctionOrChildren::from_function({
-                    #[kirl_function((String, String, String)->String)]
-                    fn replace(input: Arc<RwLock<String>>, pattern: Arc<RwLock<String>>, replace: Arc<RwLock<String>>)->Result<String, regex::Error>{
-                        let regex = Regex::new(&pattern.read().unwrap())?;
-                        Ok(regex.replace_all(&input.read().unwrap(), &**replace.read().unwrap()).into_owned())
+                    #[kirl_function((String, String, String)->(String | #{error: String}))]
+                    fn replace(input: Arc<RwLock<String>>, pattern: Arc<RwLock<String>>, replace: Arc<RwLock<String>>)->Arc<dyn KirlVMValueLock>{
+                        into_kirl_result(Regex::new(&pattern.read().unwrap()).map(|regex| regex.replace_all(&input.read().unwrap(), &**replace.read().unwrap()).into_owned()))
                     }
                     replace::new()
                 }),
@@ -305,32 +479,52 @@ static STDLIB: Lazy<KirlStdLib> = Lazy::new(|| {
                 },
                 insert: {
                     #[kirl_function(for<T> ([T], Number, T)->() )]
-                    fn list_insert(list: Arc<RwLock<Vec<Arc<dyn KirlVMValueLock>>>>, index: Decimal128, item: Arc<dyn KirlVMValueLock>) {
-                        list.write().unwrap().insert(usize::try_from(dec::Decimal::<15>::from(index)).unwrap(), item);
+                    fn list_insert(list: Arc<RwLock<Vec<Arc<dyn KirlVMValueLock>>>>, index: Decimal128, item: Arc<dyn KirlVMValueLock>) -> Result<(), IndexOutOfRange> {
+                        let mut list = list.write().unwrap();
+                        let length = list.len();
+                        // 末尾への挿入は許す
+                        let index = list_index(index, length + 1).map_err(|_| IndexOutOfRange { index, length })?;
+                        list.insert(index, item);
+                        Ok(())
                     }
                     FunctionOrChildren::from_function(list_insert::new())
                 },
                 remove: {
                     #[kirl_function(for<T> ([T], Number)->() )]
-                    fn list_remove(list: Arc<RwLock<Vec<Arc<dyn KirlVMValueLock>>>>, index: Decimal128) {
-                        list.write().unwrap().remove(usize::try_from(dec::Decimal::<15>::from(index)).unwrap());
+                    fn list_remove(list: Arc<RwLock<Vec<Arc<dyn KirlVMValueLock>>>>, index: Decimal128) -> Result<(), IndexOutOfRange> {
+                        let mut list = list.write().unwrap();
+                        let index = list_index(index, list.len())?;
+                        list.remove(index);
+                        Ok(())
                     }
                     FunctionOrChildren::from_function(list_remove::new())
                 },
                 _get_item: {
                     #[kirl_function(for<T> ([T], Number)->T )]
-                    fn list_get_item(list: Arc<RwLock<Vec<Arc<dyn KirlVMValueLock>>>>, index: Decimal128) -> Arc<dyn KirlVMValueLock> {
-                        Arc::clone(&list.read().unwrap()[usize::try_from(dec::Decimal::<15>::from(index)).unwrap()])
+                    fn list_get_item(list: Arc<RwLock<Vec<Arc<dyn KirlVMValueLock>>>>, index: Decimal128) -> Result<Arc<dyn KirlVMValueLock>, IndexOutOfRange> {
+                        let list = list.read().unwrap();
+                        Ok(Arc::clone(&list[list_index(index, list.len())?]))
                     }
                     FunctionOrChildren::from_function(list_get_item::new())
                 },
                 _set_item: {
                     #[kirl_function(for<T> ([T], Number, T)->())]
-                    fn list_set_item(list: Arc<RwLock<Vec<Arc<dyn KirlVMValueLock>>>>, index: Decimal128, item: Arc<dyn KirlVMValueLock>) {
-                        list.write().unwrap()[usize::try_from(dec::Decimal::<15>::from(index)).unwrap()] = item;
+                    fn list_set_item(list: Arc<RwLock<Vec<Arc<dyn KirlVMValueLock>>>>, index: Decimal128, item: Arc<dyn KirlVMValueLock>) -> Result<(), IndexOutOfRange> {
+                        let mut list = list.write().unwrap();
+                        let index = list_index(index, list.len())?;
+                        list[index] = item;
+                        Ok(())
                     }
                     FunctionOrChildren::from_function(list_set_item::new())
                 },
+                _get_item: {
+                    #[kirl_function(for<T> ([T], std::iter::Range)->[T])]
+                    fn list_slice(list: Arc<RwLock<Vec<Arc<dyn KirlVMValueLock>>>>, range: KirlRange) -> Result<Vec<Arc<dyn KirlVMValueLock>>, IndexOutOfRange> {
+                        let list = list.read().unwrap();
+                        range.iter().map(|index| list_index(index, list.len()).map(|index| Arc::clone(&list[index]))).collect()
+                    }
+                    FunctionOrChildren::from_function(list_slice::new())
+                },
                 _iterator: {
                     #[kirl_function(for<T> ([T])->std::iter::Iterator::<T>)]
                     fn list_iterator(list: Vec<Arc<dyn KirlVMValueLock>>) -> IteratorWrapper {
@@ -341,6 +535,51 @@ static STDLIB: Lazy<KirlStdLib> = Lazy::new(|| {
             },
         },
         iter: map! {
+            _range: {
+                #[kirl_function((Number, Number)->std::iter::Range)]
+                fn create_range(start: Decimal128, end: Decimal128) -> KirlRange {
+                    KirlRange::new(start, end, false)
+                }
+                FunctionOrChildren::from_function(create_range::new())
+            },
+            _range_inclusive: {
+                #[kirl_function((Number, Number)->std::iter::Range)]
+                fn create_range_inclusive(start: Decimal128, end: Decimal128) -> KirlRange {
+                    KirlRange::new(start, end, true)
+                }
+                FunctionOrChildren::from_function(create_range_inclusive::new())
+            },
+            _iterator: {
+                #[kirl_function((std::iter::Range)->std::iter::Iterator::<Number>)]
+                fn range_iterator(range: KirlRange) -> IteratorWrapper {
+                    IteratorWrapper::new(range.iter(), get_type!((std::iter::Iterator::<(Number)>)))
+                }
+                FunctionOrChildren::from_function(range_iterator::new())
+            },
+            contains: {
+                #[kirl_function((std::iter::Range, Number)->Bool)]
+                fn range_contains(range: KirlRange, value: Decimal128) -> bool {
+                    range.contains(value)
+                }
+                FunctionOrChildren::from_function(range_contains::new())
+            },
+            len: {
+                #[kirl_function((std::iter::Range)->Number)]
+                fn range_length(range: KirlRange) -> Decimal128 {
+                    range.len()
+                }
+                FunctionOrChildren::from_function(range_length::new())
+            },
+            step: {
+                #[kirl_function((std::iter::Range, Number)->std::iter::Range)]
+                fn range_step(range: KirlRange, step: Decimal128) -> Result<KirlRange, ZeroRangeStep> {
+                    if step.is_zero() {
+                        return Err(ZeroRangeStep);
+                    }
+                    Ok(KirlRange { step: range.step * step, ..range })
+                }
+                FunctionOrChildren::from_function(range_step::new())
+            },
             range: {
                 #[kirl_function((Number)->std::iter::Iterator::<Number>)]
                 fn range(stop: Decimal128) -> IteratorWrapper {
@@ -389,7 +628,7 @@ static STDLIB: Lazy<KirlStdLib> = Lazy::new(|| {
                         None => ().into_kirl_value()
                     }
                 }
-                FunctionOrChildren::from_function(iterator_into_iterator::new())
+                FunctionOrChildren::from_function(IteratorNext(iterator_into_iterator::new()))
             },
         },
     })
@@ -399,6 +638,30 @@ pub fn get_stdlib() -> &'static KirlStdLib {
     &*STDLIB
 }
 
+// fn*で作られたIteratorの_nextはVMが本体を再開して処理する
+struct IteratorNext<F>(F);
+
+impl<F: KirlRustFunction> KirlRustFunction for IteratorNext<F> {
+    fn static_type() -> Cow<'static, HIRType>
+    where
+        Self: Sized,
+    {
+        F::static_type()
+    }
+
+    fn argument_count(&self) -> usize {
+        self.0.argument_count()
+    }
+
+    fn call(&mut self, args: Vec<Arc<dyn KirlVMValueLock>>) -> Result<Arc<dyn KirlVMValueLock>, Box<dyn Error>> {
+        self.0.call(args)
+    }
+
+    fn resumes_generator(&self) -> bool {
+        true
+    }
+}
+
 struct IteratorWrapper {
     iter: Box<dyn Iterator<Item = Arc<dyn KirlVMValueLock>> + Send + Sync>,
     iter_type: LIRType,
@@ -2,6 +2,7 @@ use std::collections::HashMap;
 use std::convert::TryFrom;
 
 use std::fmt::Debug;
+use std::path::PathBuf;
 
 use std::sync::{Arc, Mutex, RwLock};
 
@@ -11,7 +12,7 @@ use kirl_common::typing::LIRType;
 use kirl_common::interface::{KirlRustFunction, KirlVMValueLock};
 use uuid::Uuid;
 
-use crate::lir::{LIRInstruction, LIRStatement};
+use crate::lir::{LIRInstruction, LIRStatement, SourcePosition};
 
 #[derive(Debug, Clone, Copy)]
 pub struct KirlByteCode(KirlByteCodeOpcode, [u8; 3]);
@@ -19,22 +20,32 @@ pub struct KirlByteCode(KirlByteCodeOpcode, [u8; 3]);
 #[derive(Debug, Clone, Copy)]
 pub enum KirlByteCodeOpcode {
     LoadStaticValue,
+    LoadKirlFunction,
+    LoadRustFunction,
     Load,
     Store,
+    LoadGlobal,
+    StoreGlobal,
     JumpIfTrue,
     JumpIfHasType,
     Jump,
     CallKirlFunction,
     CallRustFunction,
+    CallIndirect,
     Return,
     Nop,
+    Pop,
     AccessTupleItem,
     AccessMember,
     AssignTupleItem,
     AssignMember,
     ConstructStruct,
+    ConstructNamedStruct,
     ConstructTuple,
     ConstructArray,
+    ConstructClosure,
+    ConstructGenerator,
+    Yield,
     PushAdditionalOperand,
 }
 
@@ -71,6 +82,12 @@ impl KirlByteCode {
     }
 }
 
+#[derive(Debug, Clone, PartialEq)]
+pub struct KirlFunctionDebugInfo {
+    pub name: String,
+    pub file_path: PathBuf,
+}
+
 pub(crate) type StaticValueGenerator = Arc<dyn Fn() -> Arc<dyn KirlVMValueLock>>;
 
 pub struct KirlVMExecutable {
@@ -81,22 +98,30 @@ pub struct KirlVMExecutable {
     pub(crate) function_pointers: Vec<usize>,
     pub(crate) member_names: Vec<String>,
     pub(crate) types: Vec<LIRType>,
+    pub(crate) global_count: usize,
+    pub(crate) source_positions: Vec<Option<SourcePosition>>,
+    pub(crate) function_debug_infos: Vec<(usize, KirlFunctionDebugInfo)>,
 }
 
 impl KirlVMExecutable {
-    pub fn new(functions: impl IntoIterator<Item = (Uuid, Vec<LIRStatement>)>, static_value_generators: impl IntoIterator<Item = (Uuid, Arc<dyn Fn() -> Arc<dyn KirlVMValueLock>>)>, rust_functions: impl IntoIterator<Item = (Uuid, Arc<Mutex<dyn KirlRustFunction>>)>, main_function: Uuid) -> Self {
+    pub fn new(functions: impl IntoIterator<Item = (Uuid, KirlFunctionDebugInfo, Vec<LIRStatement>)>, static_value_generators: impl IntoIterator<Item = (Uuid, Arc<dyn Fn() -> Arc<dyn KirlVMValueLock>>)>, rust_functions: impl IntoIterator<Item = (Uuid, Arc<Mutex<dyn KirlRustFunction>>)>, main_function: Uuid) -> Self {
         let (mut static_value_generators, static_value_index): (Vec<_>, HashMap<_, _>) = static_value_generators.into_iter().enumerate().map(|(i, (id, generator))| (generator, (id, u32::try_from(i).unwrap()))).unzip();
         let (rust_functions, rust_function_index): (Vec<_>, HashMap<_, _>) = rust_functions.into_iter().enumerate().map(|(i, (id, function))| (function, (id, u32::try_from(i).unwrap()))).unzip();
         let mut bytecodes = Vec::new();
         let mut function_pointers = HashMap::new();
         let mut member_name_map = HashMap::new();
         let mut type_map = HashMap::new();
+        let mut global_map = HashMap::new();
         let mut function_references = HashMap::new();
-        for (function_id, function_body) in functions {
+        let mut source_positions = Vec::new();
+        let mut function_debug_infos = Vec::new();
+        for (function_id, debug_info, function_body) in functions {
             function_pointers.insert(function_id, bytecodes.len());
-            let (bytecode, function_reference) = lir_to_bytecode(function_body, &mut member_name_map, &mut type_map, &mut static_value_generators, &static_value_index, &rust_function_index);
+            function_debug_infos.push((bytecodes.len(), debug_info));
+            let (bytecode, function_reference, positions) = lir_to_bytecode(function_body, &mut member_name_map, &mut type_map, &mut global_map, &mut static_value_generators, &static_value_index, &rust_function_index);
             function_references.extend(function_reference.into_iter().map(|(index, id)| (index + bytecodes.len(), id)));
             bytecodes.extend(bytecode);
+            source_positions.extend(positions);
         }
         let (function_pointers, function_pointer_reference): (Vec<_>, HashMap<_, _>) = function_pointers.into_iter().enumerate().map(|(i, (id, pointer))| (pointer, (id, u32::try_from(i).unwrap()))).unzip();
         for (index, function) in function_references {
@@ -118,16 +143,29 @@ impl KirlVMExecutable {
             function_pointers,
             member_names,
             types,
+            global_count: global_map.len(),
+            source_positions,
+            function_debug_infos,
         }
     }
+
+    pub(crate) fn function_debug_info(&self, program_counter: usize) -> &KirlFunctionDebugInfo {
+        let index = self.function_debug_infos.partition_point(|(begin, _)| *begin <= program_counter);
+        &self.function_debug_infos[index - 1].1
+    }
+
+    pub(crate) fn source_position(&self, program_counter: usize) -> Option<SourcePosition> {
+        self.source_positions.get(program_counter).copied().flatten()
+    }
 }
 
-fn lir_to_bytecode(lir: impl IntoIterator<Item = LIRStatement>, member_name_map: &mut HashMap<String, u32>, type_map: &mut HashMap<LIRType, u32>, static_value_generators: &mut Vec<StaticValueGenerator>, static_value_index: &HashMap<Uuid, u32>, rust_function_index: &HashMap<Uuid, u32>) -> (impl IntoIterator<Item = KirlByteCode>, impl IntoIterator<Item = (usize, Uuid)>) {
+fn lir_to_bytecode(lir: impl IntoIterator<Item = LIRStatement>, member_name_map: &mut HashMap<String, u32>, type_map: &mut HashMap<LIRType, u32>, global_map: &mut HashMap<Uuid, u32>, static_value_generators: &mut Vec<StaticValueGenerator>, static_value_index: &HashMap<Uuid, u32>, rust_function_index: &HashMap<Uuid, u32>) -> (impl IntoIterator<Item = KirlByteCode>, impl IntoIterator<Item = (usize, Uuid)>, impl IntoIterator<Item = Option<SourcePosition>>) {
     let mut result = Vec::new();
+    let mut source_positions = Vec::new();
     let mut label_position_map = HashMap::new();
     let mut position_label_map = HashMap::new();
     let mut function_pointer_map = HashMap::new();
-    for LIRStatement { label, instruction } in lir {
+    for LIRStatement { label, instruction, position: source_position } in lir {
         if instruction == LIRInstruction::Nop && label.is_none() {
             continue;
         }
@@ -147,12 +185,40 @@ fn lir_to_bytecode(lir: impl IntoIterator<Item = LIRStatement>, member_name_map:
                 static_value_generators.push(Arc::new(move || Arc::clone(&value)));
             }
             LIRInstruction::LoadNamedValue(id) => result.push(KirlByteCode::new(KirlByteCodeOpcode::LoadStaticValue, *static_value_index.get(&id).expect("TODO:"))),
+            LIRInstruction::LoadFunction(id, ty) => {
+                if let Some(index) = static_value_index.get(&id) {
+                    result.push(KirlByteCode::new(KirlByteCodeOpcode::LoadStaticValue, *index));
+                } else {
+                    let type_map_len = type_map.len() as u32;
+                    let type_index = *type_map.entry(ty.into_normalized()).or_insert(type_map_len);
+                    result.push(KirlByteCode::new(KirlByteCodeOpcode::PushAdditionalOperand, type_index));
+                    match rust_function_index.get(&id) {
+                        None => {
+                            function_pointer_map.insert(result.len(), id);
+                            result.push(KirlByteCode::without_operand(KirlByteCodeOpcode::LoadKirlFunction));
+                        }
+                        Some(index) => {
+                            result.push(KirlByteCode::new(KirlByteCodeOpcode::LoadRustFunction, *index));
+                        }
+                    }
+                }
+            }
             LIRInstruction::Load(index) => {
                 result.push(KirlByteCode::new(KirlByteCodeOpcode::Load, index as u32));
             }
             LIRInstruction::Store(index) => {
                 result.push(KirlByteCode::new(KirlByteCodeOpcode::Store, index as u32));
             }
+            LIRInstruction::LoadGlobal(id) => {
+                let global_map_len = global_map.len() as u32;
+                let index = *global_map.entry(id).or_insert(global_map_len);
+                result.push(KirlByteCode::new(KirlByteCodeOpcode::LoadGlobal, index));
+            }
+            LIRInstruction::StoreGlobal(id) => {
+                let global_map_len = global_map.len() as u32;
+                let index = *global_map.entry(id).or_insert(global_map_len);
+                result.push(KirlByteCode::new(KirlByteCodeOpcode::StoreGlobal, index));
+            }
             LIRInstruction::JumpIfTrue(label) => {
                 position_label_map.insert(result.len(), label);
                 result.push(KirlByteCode::without_operand(KirlByteCodeOpcode::JumpIfTrue));
@@ -177,12 +243,18 @@ fn lir_to_bytecode(lir: impl IntoIterator<Item = LIRStatement>, member_name_map:
                     result.push(KirlByteCode::new(KirlByteCodeOpcode::CallRustFunction, *index));
                 }
             },
+            LIRInstruction::CallIndirect => {
+                result.push(KirlByteCode::without_operand(KirlByteCodeOpcode::CallIndirect));
+            }
             LIRInstruction::Return => {
                 result.push(KirlByteCode::without_operand(KirlByteCodeOpcode::Return));
             }
             LIRInstruction::Nop => {
                 result.push(KirlByteCode::without_operand(KirlByteCodeOpcode::Nop));
             }
+            LIRInstruction::Pop => {
+                result.push(KirlByteCode::without_operand(KirlByteCodeOpcode::Pop));
+            }
             LIRInstruction::AccessTupleItem(index) => {
                 result.push(KirlByteCode::new(KirlByteCodeOpcode::AccessTupleItem, index as u32));
             }
@@ -202,13 +274,39 @@ fn lir_to_bytecode(lir: impl IntoIterator<Item = LIRStatement>, member_name_map:
             LIRInstruction::ConstructStruct(len) => {
                 result.push(KirlByteCode::new(KirlByteCodeOpcode::ConstructStruct, u32::try_from(len).unwrap()));
             }
+            LIRInstruction::ConstructNamedStruct(ty, len) => {
+                let type_map_len = type_map.len() as u32;
+                let type_index = *type_map.entry(ty.into_normalized()).or_insert(type_map_len);
+                result.push(KirlByteCode::new(KirlByteCodeOpcode::PushAdditionalOperand, type_index));
+                result.push(KirlByteCode::new(KirlByteCodeOpcode::ConstructNamedStruct, u32::try_from(len).unwrap()));
+            }
             LIRInstruction::ConstructTuple(len) => {
                 result.push(KirlByteCode::new(KirlByteCodeOpcode::ConstructTuple, u32::try_from(len).unwrap()));
             }
             LIRInstruction::ConstructArray(len) => {
                 result.push(KirlByteCode::new(KirlByteCodeOpcode::ConstructArray, u32::try_from(len).unwrap()));
             }
+            LIRInstruction::ConstructClosure(ty, capture_count, label) => {
+                let type_map_len = type_map.len() as u32;
+                let type_index = *type_map.entry(ty.into_normalized()).or_insert(type_map_len);
+                result.push(KirlByteCode::new(KirlByteCodeOpcode::PushAdditionalOperand, u32::try_from(capture_count).unwrap()));
+                result.push(KirlByteCode::new(KirlByteCodeOpcode::PushAdditionalOperand, type_index));
+                position_label_map.insert(result.len(), label);
+                result.push(KirlByteCode::without_operand(KirlByteCodeOpcode::ConstructClosure));
+            }
+            LIRInstruction::ConstructGenerator(ty, frame_size, label) => {
+                let type_map_len = type_map.len() as u32;
+                let type_index = *type_map.entry(ty.into_normalized()).or_insert(type_map_len);
+                result.push(KirlByteCode::new(KirlByteCodeOpcode::PushAdditionalOperand, u32::try_from(frame_size).unwrap()));
+                result.push(KirlByteCode::new(KirlByteCodeOpcode::PushAdditionalOperand, type_index));
+                position_label_map.insert(result.len(), label);
+                result.push(KirlByteCode::without_operand(KirlByteCodeOpcode::ConstructGenerator));
+            }
+            LIRInstruction::Yield => {
+                result.push(KirlByteCode::without_operand(KirlByteCodeOpcode::Yield));
+            }
         }
+        source_positions.resize(result.len(), source_position);
     }
     for (position, label) in position_label_map {
         let target_position = label_position_map[&label] as isize;
@@ -216,5 +314,5 @@ fn lir_to_bytecode(lir: impl IntoIterator<Item = LIRStatement>, member_name_map:
         let opcode = result[position].0;
         result[position] = KirlByteCode::new_signed(opcode, diff);
     }
-    (result, function_pointer_map)
+    (result, function_pointer_map, source_positions)
 }
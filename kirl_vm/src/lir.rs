@@ -1,3 +1,4 @@
+use std::collections::{HashMap, HashSet};
 use std::convert::TryInto;
 use std::error::Error;
 use std::fmt::{Display, Formatter};
@@ -23,15 +24,22 @@ impl From<LIRStatementList> for Vec<LIRStatement> {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SourcePosition {
+    pub line: usize,
+    pub column: usize,
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct LIRStatement {
     pub(crate) label: Option<String>,
     pub(crate) instruction: LIRInstruction,
+    pub(crate) position: Option<SourcePosition>,
 }
 
 impl From<LIRInstruction> for LIRStatement {
     fn from(instruction: LIRInstruction) -> Self {
-        LIRStatement { label: None, instruction }
+        LIRStatement { label: None, instruction, position: None }
     }
 }
 
@@ -40,21 +48,30 @@ pub enum LIRInstruction {
     LoadImmediateString(String),
     LoadImmediateNumber(Decimal128),
     LoadNamedValue(Uuid),
+    LoadFunction(Uuid, LIRType),
     Load(usize),
     Store(usize),
+    LoadGlobal(Uuid),
+    StoreGlobal(Uuid),
     JumpIfTrue(String),
     JumpIfHasType(LIRType, String),
     Jump(String),
     CallFunction(Uuid),
+    CallIndirect,
     Return,
     Nop,
+    Pop,
     AccessMember(String),
     AccessTupleItem(usize),
     AssignMember(String),
     AssignTupleItem(usize),
     ConstructStruct(usize),
+    ConstructNamedStruct(LIRType, usize),
     ConstructTuple(usize),
     ConstructArray(usize),
+    ConstructClosure(LIRType, usize, String),
+    ConstructGenerator(LIRType, usize, String),
+    Yield,
 }
 
 #[derive(Debug)]
@@ -62,6 +79,8 @@ pub enum LIRStatementListConvertError {
     TypeConvertError(LIRTypeConvertError),
     UnexpectedBreak,
     UnexpectedContinue,
+    UnexpectedYield,
+    AssignToConstant,
 }
 
 impl Display for LIRStatementListConvertError {
@@ -70,6 +89,8 @@ impl Display for LIRStatementListConvertError {
             LIRStatementListConvertError::TypeConvertError(e) => e.fmt(f),
             LIRStatementListConvertError::UnexpectedBreak => write!(f, "Unexpected break."),
             LIRStatementListConvertError::UnexpectedContinue => write!(f, "Unexpected continue."),
+            LIRStatementListConvertError::UnexpectedYield => write!(f, "Unexpected yield."),
+            LIRStatementListConvertError::AssignToConstant => write!(f, "Cannot assign to a function."),
         }
     }
 }
@@ -80,6 +101,8 @@ impl Error for LIRStatementListConvertError {
             LIRStatementListConvertError::TypeConvertError(e) => Some(e),
             LIRStatementListConvertError::UnexpectedBreak => None,
             LIRStatementListConvertError::UnexpectedContinue => None,
+            LIRStatementListConvertError::UnexpectedYield => None,
+            LIRStatementListConvertError::AssignToConstant => None,
         }
     }
 }
@@ -90,41 +113,90 @@ impl From<LIRTypeConvertError> for LIRStatementListConvertError {
     }
 }
 
-pub fn hir_to_lir(statements: Vec<HIRStatement<(Uuid, HIRType)>>, argument_count: usize) -> Result<LIRStatementList, LIRStatementListConvertError> {
-    fn convert_list(statements: impl IntoIterator<Item = HIRStatement<(Uuid, HIRType)>>, result: &mut Vec<LIRStatement>, sequence: &mut usize, loop_labels: &mut Vec<String>) -> Result<(), LIRStatementListConvertError> {
-        fn convert(statement: HIRStatement<(Uuid, HIRType)>, result: &mut Vec<LIRStatement>, sequence: &mut usize, loop_labels: &mut Vec<String>) -> Result<(), LIRStatementListConvertError> {
-            fn push_variable(variable: Variable<(Uuid, HIRType)>, result: &mut Vec<LIRStatement>) {
+// 実行時には構造体の型引数を区別しないので消しておく
+fn erase_generics_arguments(ty: HIRType) -> HIRType {
+    match ty {
+        HIRType::Named { path, .. } => HIRType::Named { path, generics_arguments: Vec::new() },
+        HIRType::Tuple(items) => HIRType::Tuple(items.into_iter().map(erase_generics_arguments).collect()),
+        HIRType::Array(item) => HIRType::Array(Box::new(erase_generics_arguments(*item))),
+        HIRType::Function { arguments, result } => HIRType::Function { arguments: arguments.into_iter().map(erase_generics_arguments).collect(), result: Box::new(erase_generics_arguments(*result)) },
+        HIRType::AnonymousStruct(members) => HIRType::AnonymousStruct(members.into_iter().map(|(k, v)| (k, erase_generics_arguments(v))).collect()),
+        HIRType::Or(items) => HIRType::Or(items.into_iter().map(erase_generics_arguments).collect()),
+        HIRType::Recursive { path, body } => HIRType::Recursive { path, body: Box::new(erase_generics_arguments(*body)) },
+        ty => ty,
+    }
+}
+
+// ラベルが無ければ最も内側のループ、あればそのラベルを持つ最も内側のループを指す
+fn find_loop_label<'a>(loop_labels: &'a [(Option<String>, String)], label: Option<&str>) -> Option<&'a str> {
+    match label {
+        Some(label) => loop_labels.iter().rev().find(|(loop_label, _)| loop_label.as_deref() == Some(label)),
+        None => loop_labels.last(),
+    }
+    .map(|(_, label)| label.as_str())
+}
+
+struct Globals<'a> {
+    ids: &'a HashSet<Uuid>,
+    top_level_variables: &'a HashMap<usize, Uuid>,
+    // ジェネレータの本体の中ならyieldできる
+    generator: bool,
+}
+
+impl<'a> Globals<'a> {
+    fn load(&self, id: usize) -> LIRInstruction {
+        self.top_level_variables.get(&id).map_or(LIRInstruction::Load(id), |id| LIRInstruction::LoadGlobal(*id))
+    }
+
+    fn store(&self, id: usize) -> LIRInstruction {
+        self.top_level_variables.get(&id).map_or(LIRInstruction::Store(id), |id| LIRInstruction::StoreGlobal(*id))
+    }
+}
+
+// `global_variables`は参照されうる全てのグローバル変数、`top_level_variables`はこの関数内の変数のうちグローバル変数として扱うもの
+pub fn hir_to_lir(statements: Vec<HIRStatement<(Uuid, HIRType)>>, argument_count: usize, global_variables: &HashSet<Uuid>, top_level_variables: &HashMap<usize, Uuid>) -> Result<LIRStatementList, LIRStatementListConvertError> {
+    fn convert_list(statements: impl IntoIterator<Item = HIRStatement<(Uuid, HIRType)>>, result: &mut Vec<LIRStatement>, sequence: &mut usize, loop_labels: &mut Vec<(Option<String>, String)>, globals: &Globals) -> Result<(), LIRStatementListConvertError> {
+        fn convert(statement: HIRStatement<(Uuid, HIRType)>, result: &mut Vec<LIRStatement>, sequence: &mut usize, loop_labels: &mut Vec<(Option<String>, String)>, globals: &Globals) -> Result<(), LIRStatementListConvertError> {
+            let push_variable = |variable: Variable<(Uuid, HIRType)>, result: &mut Vec<LIRStatement>| -> Result<(), LIRStatementListConvertError> {
                 match variable {
+                    Variable::Named(_, _, (id, _)) if globals.ids.contains(&id) => result.push(LIRInstruction::LoadGlobal(id).into()),
+                    Variable::Named(_, _, (id, ty @ HIRType::Function { .. })) => result.push(LIRInstruction::LoadFunction(id, ty.try_into()?).into()),
                     Variable::Named(_, _, (id, _)) => result.push(LIRInstruction::LoadNamedValue(id).into()),
-                    Variable::Unnamed(id) => result.push(LIRInstruction::Load(id).into()),
+                    Variable::Unnamed(id) => result.push(globals.load(id).into()),
                 }
-            }
+                Ok(())
+            };
             match statement {
-                HIRStatement::Binding { variable_id, expression, .. } => {
+                HIRStatement::Binding { position, variable_id, expression, .. } => {
+                    let begin = result.len();
+                    // 引数の分解のように元のコードを持たない文は範囲が空になっている
+                    let position = (position.start != position.end).then(|| SourcePosition { line: position.start.line, column: position.start.column });
                     match expression {
                         HIRExpression::Immediate(value) => match value {
                             Immediate::Number(value) => result.push(LIRInstruction::LoadImmediateNumber(value).into()),
                             Immediate::String(value) => result.push(LIRInstruction::LoadImmediateString(value).into()),
                         },
-                        HIRExpression::CallFunction { function, arguments } => {
-                            let function = match function {
-                                Variable::Named(_, _, (id, _)) => id,
-                                Variable::Unnamed(_) => todo!(),
-                            };
+                        HIRExpression::CallFunction { function, arguments, .. } => {
                             for variable in arguments.into_iter().rev() {
-                                push_variable(variable, result);
+                                push_variable(variable, result)?;
+                            }
+                            match function {
+                                Variable::Named(_, _, (id, _)) => result.push(LIRInstruction::CallFunction(id).into()),
+                                Variable::Unnamed(id) => {
+                                    result.push(globals.load(id).into());
+                                    result.push(LIRInstruction::CallIndirect.into());
+                                }
                             }
-                            result.push(LIRInstruction::CallFunction(function).into());
                         }
                         HIRExpression::AccessVariable(variable) => {
-                            push_variable(variable, result);
+                            push_variable(variable, result)?;
                         }
                         HIRExpression::AccessTupleItem { variable, index } => {
-                            push_variable(variable, result);
+                            push_variable(variable, result)?;
                             result.push(LIRInstruction::AccessTupleItem(index).into());
                         }
                         HIRExpression::AccessMember { variable, member } => {
-                            push_variable(variable, result);
+                            push_variable(variable, result)?;
                             result.push(LIRInstruction::AccessMember(member).into());
                         }
                         HIRExpression::If {
@@ -132,18 +204,18 @@ pub fn hir_to_lir(statements: Vec<HIRStatement<(Uuid, HIRType)>>, argument_count
                             then: (then_statements, then_result),
                             other: (other_statements, other_result),
                         } => {
-                            push_variable(condition, result);
+                            push_variable(condition, result)?;
                             let then_label = format!("$if_then_{}", *sequence);
                             let end_label = format!("$if_end_{}", *sequence);
                             *sequence += 1;
                             result.push(LIRInstruction::JumpIfTrue(then_label.clone()).into());
-                            convert_list(other_statements, result, sequence, loop_labels)?;
-                            push_variable(other_result, result);
+                            convert_list(other_statements, result, sequence, loop_labels, globals)?;
+                            push_variable(other_result, result)?;
                             result.push(LIRInstruction::Jump(end_label.clone()).into());
-                            result.push(LIRStatement { label: Some(then_label), instruction: LIRInstruction::Nop });
-                            convert_list(then_statements, result, sequence, loop_labels)?;
-                            push_variable(then_result, result);
-                            result.push(LIRStatement { label: Some(end_label), instruction: LIRInstruction::Nop });
+                            result.push(LIRStatement { label: Some(then_label), instruction: LIRInstruction::Nop, position: None });
+                            convert_list(then_statements, result, sequence, loop_labels, globals)?;
+                            push_variable(then_result, result)?;
+                            result.push(LIRStatement { label: Some(end_label), instruction: LIRInstruction::Nop, position: None });
                         }
                         HIRExpression::IfLet {
                             condition_binding,
@@ -151,96 +223,175 @@ pub fn hir_to_lir(statements: Vec<HIRStatement<(Uuid, HIRType)>>, argument_count
                             condition,
                             then: (then_statements, then_result),
                             other: (other_statements, other_result),
+                            ..
                         } => {
-                            push_variable(condition, result);
+                            push_variable(condition, result)?;
                             let then_label = format!("$if_then_{}", *sequence);
                             let end_label = format!("$if_end_{}", *sequence);
                             *sequence += 1;
-                            result.push(LIRInstruction::JumpIfHasType(pattern_type.try_into()?, then_label.clone()).into());
-                            convert_list(other_statements, result, sequence, loop_labels)?;
-                            push_variable(other_result, result);
+                            result.push(LIRInstruction::JumpIfHasType(erase_generics_arguments(pattern_type).try_into()?, then_label.clone()).into());
+                            convert_list(other_statements, result, sequence, loop_labels, globals)?;
+                            push_variable(other_result, result)?;
                             result.push(LIRInstruction::Jump(end_label.clone()).into());
                             result.push(LIRStatement {
                                 label: Some(then_label),
-                                instruction: LIRInstruction::Store(condition_binding),
+                                instruction: globals.store(condition_binding),
+                                position: None,
                             });
-                            convert_list(then_statements, result, sequence, loop_labels)?;
-                            push_variable(then_result, result);
-                            result.push(LIRStatement { label: Some(end_label), instruction: LIRInstruction::Nop });
+                            convert_list(then_statements, result, sequence, loop_labels, globals)?;
+                            push_variable(then_result, result)?;
+                            result.push(LIRStatement { label: Some(end_label), instruction: LIRInstruction::Nop, position: None });
                         }
-                        HIRExpression::Loop(inner) => {
-                            let label = format!("$loop_anonymous_{}", *sequence);
+                        HIRExpression::Loop(loop_label, inner) => {
+                            let label = format!("$loop_{}", *sequence);
                             let label_begin = format!("{}_begin", label);
                             let label_end = format!("{}_end", label);
-                            loop_labels.push(label);
+                            loop_labels.push((loop_label, label));
                             *sequence += 1;
-                            result.push(LIRStatement { label: Some(label_begin.clone()), instruction: LIRInstruction::Nop });
-                            convert_list(inner, result, sequence, loop_labels)?;
+                            result.push(LIRStatement { label: Some(label_begin.clone()), instruction: LIRInstruction::Nop, position: None });
+                            convert_list(inner, result, sequence, loop_labels, globals)?;
+                            loop_labels.pop();
                             result.push(LIRInstruction::Jump(label_begin).into());
-                            result.push(LIRStatement { label: Some(label_end), instruction: LIRInstruction::Nop });
+                            result.push(LIRStatement { label: Some(label_end), instruction: LIRInstruction::Nop, position: None });
                         }
                         HIRExpression::Assign { variable, value } => {
-                            push_variable(value.clone(), result);
+                            push_variable(value.clone(), result)?;
                             match variable {
                                 ReferenceAccess::Variable(dest) => {
-                                    push_variable(value, result);
+                                    push_variable(value, result)?;
                                     match dest {
-                                        Variable::Named(_, _, _) => todo!(),
-                                        Variable::Unnamed(dest) => result.push(LIRInstruction::Store(dest).into()),
+                                        Variable::Named(_, _, (id, _)) if globals.ids.contains(&id) => result.push(LIRInstruction::StoreGlobal(id).into()),
+                                        Variable::Named(_, _, _) => return Err(LIRStatementListConvertError::AssignToConstant),
+                                        Variable::Unnamed(dest) => result.push(globals.store(dest).into()),
                                     }
                                 }
                                 ReferenceAccess::TupleItem(dest_variable, dest_index) => {
-                                    push_variable(dest_variable, result);
-                                    push_variable(value, result);
+                                    push_variable(dest_variable, result)?;
+                                    push_variable(value, result)?;
                                     result.push(LIRInstruction::AssignTupleItem(dest_index).into());
                                 }
                                 ReferenceAccess::Member(dest_variable, dest_member) => {
-                                    push_variable(dest_variable, result);
-                                    push_variable(value, result);
+                                    push_variable(dest_variable, result)?;
+                                    push_variable(value, result)?;
                                     result.push(LIRInstruction::AssignMember(dest_member).into());
                                 }
                             }
                         }
+                        HIRExpression::ConstructClosure { captures, arguments, return_type, body } => {
+                            let label = format!("$closure_{}", *sequence);
+                            let label_begin = format!("{}_begin", label);
+                            let label_end = format!("{}_end", label);
+                            *sequence += 1;
+                            let argument_count = arguments.len();
+                            let closure_type = HIRType::Function { arguments, result: Box::new(return_type) }.try_into()?;
+                            let (capture_ids, capture_variables): (Vec<_>, Vec<_>) = captures.into_iter().unzip();
+                            for variable in capture_variables.into_iter().rev() {
+                                push_variable(variable, result)?;
+                            }
+                            result.push(LIRInstruction::Jump(label_end.clone()).into());
+                            result.push(LIRStatement { label: Some(label_begin.clone()), instruction: LIRInstruction::Nop, position: None });
+                            for &id in &capture_ids {
+                                result.push(LIRInstruction::Store(id).into());
+                            }
+                            for i in 0..argument_count {
+                                result.push(LIRInstruction::Store(i).into());
+                            }
+                            convert_list(body, result, sequence, &mut Vec::new(), &Globals { ids: globals.ids, top_level_variables: &HashMap::new(), generator: false })?;
+                            result.push(LIRInstruction::ConstructTuple(0).into());
+                            result.push(LIRInstruction::Return.into());
+                            result.push(LIRStatement {
+                                label: Some(label_end),
+                                instruction: LIRInstruction::ConstructClosure(closure_type, capture_ids.len(), label_begin),
+                                position: None,
+                            });
+                        }
+                        // 本体は呼び出し元の関数の変数をそのまま使うので、引数の格納は要らない
+                        HIRExpression::ConstructGenerator { item_type, body } => {
+                            let label = format!("$generator_{}", *sequence);
+                            let label_begin = format!("{}_begin", label);
+                            let label_end = format!("{}_end", label);
+                            *sequence += 1;
+                            let generator_type = HIRType::Named {
+                                path: vec!["std".to_string(), "iter".to_string(), "Iterator".to_string()],
+                                generics_arguments: vec![item_type],
+                            }
+                            .try_into()?;
+                            result.push(LIRInstruction::Jump(label_end.clone()).into());
+                            result.push(LIRStatement { label: Some(label_begin.clone()), instruction: LIRInstruction::Nop, position: None });
+                            convert_list(body, result, sequence, &mut Vec::new(), &Globals { generator: true, ..*globals })?;
+                            result.push(LIRInstruction::ConstructTuple(0).into());
+                            result.push(LIRInstruction::Return.into());
+                            result.push(LIRStatement {
+                                label: Some(label_end),
+                                instruction: LIRInstruction::ConstructGenerator(generator_type, 0, label_begin),
+                                position: None,
+                            });
+                        }
                         HIRExpression::ConstructStruct(members) => {
                             let len = members.len();
                             for (member, value) in members {
-                                push_variable(value, result);
+                                push_variable(value, result)?;
                                 result.push(LIRInstruction::LoadImmediateString(member).into());
                             }
                             result.push(LIRInstruction::ConstructStruct(len).into());
                         }
+                        HIRExpression::ConstructNamedStruct { struct_type, members } => {
+                            let len = members.len();
+                            for (member, value) in members {
+                                push_variable(value, result)?;
+                                result.push(LIRInstruction::LoadImmediateString(member).into());
+                            }
+                            result.push(LIRInstruction::ConstructNamedStruct(erase_generics_arguments(struct_type).try_into()?, len).into());
+                        }
                         HIRExpression::ConstructTuple(items) => {
                             let len = items.len();
                             for item in items.into_iter().rev() {
-                                push_variable(item, result);
+                                push_variable(item, result)?;
                             }
                             result.push(LIRInstruction::ConstructTuple(len).into());
                         }
                         HIRExpression::ConstructArray(items) => {
                             let len = items.len();
                             for item in items.into_iter().rev() {
-                                push_variable(item, result);
+                                push_variable(item, result)?;
                             }
                             result.push(LIRInstruction::ConstructArray(len).into());
                         }
                     }
-                    result.push(LIRInstruction::Store(variable_id).into());
+                    result.push(globals.store(variable_id).into());
+                    for statement in &mut result[begin..] {
+                        if statement.position.is_none() {
+                            statement.position = position;
+                        }
+                    }
                 }
                 HIRStatement::Unreachable => {}
-                HIRStatement::Return(value) => {
-                    push_variable(value, result);
-                    result.push(LIRInstruction::Return.into());
+                HIRStatement::Return(position, value) => {
+                    push_variable(value, result)?;
+                    let position = (position.start != position.end).then(|| SourcePosition { line: position.start.line, column: position.start.column });
+                    result.push(LIRStatement { label: None, instruction: LIRInstruction::Return, position });
+                }
+                HIRStatement::Yield(value) => {
+                    if !globals.generator {
+                        return Err(LIRStatementListConvertError::UnexpectedYield);
+                    }
+                    push_variable(value, result)?;
+                    result.push(LIRInstruction::Yield.into());
                 }
                 HIRStatement::Continue(label) => {
-                    if let Some(label) = label.as_ref().or_else(|| loop_labels.last()) {
+                    if let Some(label) = find_loop_label(loop_labels, label.as_deref()) {
                         result.push(LIRInstruction::Jump(format!("{}_begin", label)).into());
                     } else {
                         return Err(LIRStatementListConvertError::UnexpectedContinue);
                     }
                 }
-                HIRStatement::Break(label) => {
-                    result.push(LIRInstruction::ConstructTuple(0).into()); //TODO:loop式とかで値を持ってbreakするときに変える
-                    if let Some(label) = label.as_ref().or_else(|| loop_labels.last()) {
+                HIRStatement::Break(label, value) => {
+                    if let Some(value) = value {
+                        push_variable(value, result)?;
+                    } else {
+                        result.push(LIRInstruction::ConstructTuple(0).into());
+                    }
+                    if let Some(label) = find_loop_label(loop_labels, label.as_deref()) {
                         result.push(LIRInstruction::Jump(format!("{}_end", label)).into());
                     } else {
                         return Err(LIRStatementListConvertError::UnexpectedBreak);
@@ -250,7 +401,7 @@ pub fn hir_to_lir(statements: Vec<HIRStatement<(Uuid, HIRType)>>, argument_count
             Ok(())
         }
         for statement in statements {
-            convert(statement, result, sequence, loop_labels)?;
+            convert(statement, result, sequence, loop_labels, globals)?;
         }
         Ok(())
     }
@@ -259,8 +410,22 @@ pub fn hir_to_lir(statements: Vec<HIRStatement<(Uuid, HIRType)>>, argument_count
         result.push(LIRInstruction::Store(i).into());
     }
     let mut loop_labels = Vec::new();
-    convert_list(statements, &mut result, &mut 0, &mut loop_labels)?;
+    convert_list(statements, &mut result, &mut 0, &mut loop_labels, &Globals { ids: global_variables, top_level_variables, generator: false })?;
     result.push(LIRInstruction::ConstructTuple(0).into());
     result.push(LIRInstruction::Return.into());
+    // ジェネレータが保存するのはこの関数の変数だけ(呼び出し先のフレームは含めない)
+    let frame_size = result
+        .iter()
+        .filter_map(|statement| match statement.instruction {
+            LIRInstruction::Load(id) | LIRInstruction::Store(id) => Some(id + 1),
+            _ => None,
+        })
+        .max()
+        .unwrap_or(0);
+    for statement in &mut result {
+        if let LIRInstruction::ConstructGenerator(_, size, _) = &mut statement.instruction {
+            *size = frame_size;
+        }
+    }
     Ok(result.into())
 }
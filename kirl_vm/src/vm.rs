@@ -1,161 +1,560 @@
-use kirl_common::interface::{InterchangeKirlVMValue, KirlVMValueLock};
+use kirl_common::interface::{InterchangeKirlVMValue, KirlArgumentError, KirlRustFunction, KirlVMValue, KirlVMValueLock};
+use kirl_common::typing::LIRType;
+use once_cell::sync::Lazy;
+use std::borrow::Cow;
 use std::collections::HashMap;
-use std::sync::{Arc, RwLock};
-
-use crate::bytecode::{KirlByteCodeOpcode, KirlVMExecutable};
-
-fn unwrap<T: Clone>(ptr: Arc<RwLock<T>>) -> T {
-    Arc::try_unwrap(ptr).map(|rw| rw.into_inner().expect("")).unwrap_or_else(|ptr| ptr.read().expect("").clone())
-}
-
-pub fn exec(
-    KirlVMExecutable {
-        bytecodes,
-        entry_point,
-        static_value_generators,
-        rust_functions,
-        function_pointers,
-        member_names,
-        types,
-    }: &KirlVMExecutable,
-) {
-    let mut local_stack = Vec::new();
-    let mut global_stack: Vec<Arc<dyn KirlVMValueLock>> = Vec::new();
-    let mut global_stack_offset = 0usize;
-    let mut global_stack_offset_stack = Vec::new();
-    let mut program_counter_stack = Vec::new();
-    let mut program_counter = *entry_point;
-    let mut additional_operand = Vec::new();
-    loop {
-        let instruction = bytecodes[program_counter];
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::iter;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+use crate::bytecode::{KirlByteCode, KirlByteCodeOpcode, KirlFunctionDebugInfo, KirlVMExecutable};
+use crate::lir::SourcePosition;
+
+fn unwrap<T: Clone>(ptr: Arc<RwLock<T>>) -> KirlRuntimeResult<T> {
+    match Arc::try_unwrap(ptr) {
+        Ok(rw) => rw.into_inner().map_err(|_| KirlRuntimeErrorKind::PoisonedValue),
+        Err(ptr) => Ok(read(&ptr)?.clone()),
+    }
+}
+
+#[derive(Debug)]
+enum FunctionBody {
+    Kirl { function_pointer: usize, captures: Vec<Arc<dyn KirlVMValueLock>> },
+    Rust(usize),
+}
+
+#[derive(Debug)]
+struct FunctionValue {
+    body: FunctionBody,
+    function_type: LIRType,
+}
+
+impl KirlVMValue for FunctionValue {
+    fn static_type() -> Cow<'static, LIRType>
+    where
+        Self: Sized,
+    {
+        static TYPE: Lazy<LIRType> = Lazy::new(|| LIRType::Function { arguments: Vec::new(), result: Box::new(LIRType::Unreachable) }.into_normalized());
+        Cow::Borrowed(&TYPE)
+    }
+
+    fn get_type(&self) -> Cow<LIRType> {
+        Cow::Borrowed(&self.function_type)
+    }
+}
+
+#[derive(Debug)]
+struct StructValue {
+    struct_type: LIRType,
+    members: HashMap<String, Arc<dyn KirlVMValueLock>>,
+}
+
+impl KirlVMValue for StructValue {
+    fn static_type() -> Cow<'static, LIRType>
+    where
+        Self: Sized,
+    {
+        static TYPE: Lazy<LIRType> = Lazy::new(|| LIRType::Named { path: Vec::new(), generics_arguments: Vec::new() });
+        Cow::Borrowed(&TYPE)
+    }
+
+    fn get_type(&self) -> Cow<LIRType> {
+        Cow::Borrowed(&self.struct_type)
+    }
+}
+
+// 中断中のジェネレータの再開位置と変数(終了していればNone)
+#[derive(Debug)]
+struct GeneratorValue {
+    state: Option<(usize, Vec<Arc<dyn KirlVMValueLock>>)>,
+    // 保存する変数の数(呼び出し元の関数のフレームの大きさ)
+    frame_size: usize,
+    // 本体を実行中(stateは空だが終了はしていない)
+    running: bool,
+    generator_type: LIRType,
+}
+
+impl KirlVMValue for GeneratorValue {
+    fn static_type() -> Cow<'static, LIRType>
+    where
+        Self: Sized,
+    {
+        static TYPE: Lazy<LIRType> = Lazy::new(|| LIRType::Named { path: vec!["std".to_string(), "iter".to_string(), "Iterator".to_string()], generics_arguments: Vec::new() });
+        Cow::Borrowed(&TYPE)
+    }
+
+    fn get_type(&self) -> Cow<LIRType> {
+        Cow::Borrowed(&self.generator_type)
+    }
+}
+
+#[derive(Debug)]
+pub enum KirlRuntimeErrorKind {
+    StackUnderflow,
+    UnexpectedValueType { expected: &'static str, actual: &'static str },
+    TupleIndexOutOfRange(usize),
+    MemberNotFound(String),
+    VariableNotFound(usize),
+    GlobalVariableNotFound(usize),
+    UninitializedGlobalVariable(usize),
+    GeneratorAlreadyRunning,
+    PoisonedValue,
+    RustFunctionFailed(Box<dyn Error>),
+}
+
+impl Display for KirlRuntimeErrorKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            KirlRuntimeErrorKind::StackUnderflow => write!(f, "The value stack is empty."),
+            KirlRuntimeErrorKind::UnexpectedValueType { expected, actual } => write!(f, "Expected a value of {} but found {}.", expected, actual),
+            KirlRuntimeErrorKind::TupleIndexOutOfRange(index) => write!(f, "A tuple does not have item {}.", index),
+            KirlRuntimeErrorKind::MemberNotFound(member) => write!(f, "A struct does not have member {:?}.", member),
+            KirlRuntimeErrorKind::VariableNotFound(index) => write!(f, "Variable {} is not initialized.", index),
+            KirlRuntimeErrorKind::GlobalVariableNotFound(index) => write!(f, "Global variable {} is not found.", index),
+            KirlRuntimeErrorKind::UninitializedGlobalVariable(index) => write!(f, "Global variable {} is read before it is initialized.", index),
+            KirlRuntimeErrorKind::GeneratorAlreadyRunning => write!(f, "A generator is resumed while it is running."),
+            KirlRuntimeErrorKind::PoisonedValue => write!(f, "A value is poisoned."),
+            KirlRuntimeErrorKind::RustFunctionFailed(e) => write!(f, "A built-in function failed: {}", e),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct KirlStackFrame {
+    pub function_name: String,
+    pub file_path: PathBuf,
+    pub position: Option<SourcePosition>,
+}
+
+impl Display for KirlStackFrame {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self.position {
+            Some(SourcePosition { line, column }) => write!(f, "at {} ({}:{}:{})", self.function_name, self.file_path.display(), line + 1, column + 1),
+            None => write!(f, "at {} ({})", self.function_name, self.file_path.display()),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct KirlRuntimeError {
+    pub opcode: KirlByteCodeOpcode,
+    pub program_counter: usize,
+    // 呼び出し元のプログラムカウンタ(外側から順)
+    pub call_stack: Vec<usize>,
+    // エラー発生位置から呼び出し元へ向かう順
+    pub backtrace: Vec<KirlStackFrame>,
+    pub kind: KirlRuntimeErrorKind,
+}
+
+impl Display for KirlRuntimeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} (opcode {:?} at {})", self.kind, self.opcode, self.program_counter)?;
+        for frame in &self.backtrace {
+            write!(f, "\n    {}", frame)?;
+        }
+        Ok(())
+    }
+}
+
+impl Error for KirlRuntimeError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match &self.kind {
+            KirlRuntimeErrorKind::RustFunctionFailed(e) => Some(e.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+type KirlRuntimeResult<T> = Result<T, KirlRuntimeErrorKind>;
+
+fn pop(stack: &mut Vec<Arc<dyn KirlVMValueLock>>) -> KirlRuntimeResult<Arc<dyn KirlVMValueLock>> {
+    stack.pop().ok_or(KirlRuntimeErrorKind::StackUnderflow)
+}
+
+fn downcast<T: InterchangeKirlVMValue>(value: Arc<dyn KirlVMValueLock>) -> KirlRuntimeResult<Arc<RwLock<T>>> {
+    T::try_from_kirl_value(value).map_err(|value| KirlRuntimeErrorKind::UnexpectedValueType { expected: std::any::type_name::<T>(), actual: value.type_name() })
+}
+
+fn read<T>(value: &RwLock<T>) -> KirlRuntimeResult<RwLockReadGuard<T>> {
+    value.read().map_err(|_| KirlRuntimeErrorKind::PoisonedValue)
+}
+
+fn write<T>(value: &RwLock<T>) -> KirlRuntimeResult<RwLockWriteGuard<T>> {
+    value.write().map_err(|_| KirlRuntimeErrorKind::PoisonedValue)
+}
+
+fn call_rust_function(function: &Mutex<dyn KirlRustFunction>, local_stack: &mut Vec<Arc<dyn KirlVMValueLock>>) -> KirlRuntimeResult<()> {
+    let mut function = function.lock().map_err(|_| KirlRuntimeErrorKind::PoisonedValue)?;
+    let argument_count = function.argument_count();
+    let mut arguments = Vec::with_capacity(argument_count);
+    for _ in 0..argument_count {
+        arguments.push(pop(local_stack)?);
+    }
+    let ret = function.call(arguments).map_err(|error| match error.downcast::<KirlArgumentError>() {
+        Ok(error) => match *error {
+            KirlArgumentError::UnexpectedType { expected, actual } => KirlRuntimeErrorKind::UnexpectedValueType { expected, actual },
+            KirlArgumentError::PoisonedValue => KirlRuntimeErrorKind::PoisonedValue,
+        },
+        Err(error) => KirlRuntimeErrorKind::RustFunctionFailed(error),
+    })?;
+    local_stack.push(ret);
+    Ok(())
+}
+
+enum Control {
+    Next,
+    Jumped,
+    Exit(Arc<dyn KirlVMValueLock>),
+}
+
+struct KirlVMState {
+    local_stack: Vec<Arc<dyn KirlVMValueLock>>,
+    global_stack: Vec<Arc<dyn KirlVMValueLock>>,
+    global_stack_offset: usize,
+    global_stack_offset_stack: Vec<usize>,
+    program_counter_stack: Vec<usize>,
+    program_counter: usize,
+    additional_operand: Vec<u32>,
+    // 初期化前のグローバル変数はNone
+    globals: Vec<Option<Arc<dyn KirlVMValueLock>>>,
+    // 実行中のジェネレータと、そのフレームでのprogram_counter_stackの深さ
+    generator_stack: Vec<(usize, Arc<RwLock<GeneratorValue>>)>,
+}
+
+impl KirlVMState {
+    fn additional_operand(&mut self) -> KirlRuntimeResult<u32> {
+        self.additional_operand.pop().ok_or(KirlRuntimeErrorKind::StackUnderflow)
+    }
+
+    fn jump(&mut self, operand: i32) {
+        self.program_counter = ((self.program_counter as isize) + (operand as isize)) as usize;
+    }
+
+    fn call(&mut self, function_pointer: usize) {
+        self.program_counter_stack.push(self.program_counter);
+        self.program_counter = function_pointer;
+        self.global_stack_offset_stack.push(self.global_stack_offset);
+        self.global_stack_offset = self.global_stack.len();
+    }
+
+    fn ret(&mut self) -> KirlRuntimeResult<()> {
+        self.program_counter = self.program_counter_stack.pop().ok_or(KirlRuntimeErrorKind::StackUnderflow)?;
+        self.global_stack_offset = self.global_stack_offset_stack.pop().ok_or(KirlRuntimeErrorKind::StackUnderflow)?;
+        Ok(())
+    }
+
+    // Iteratorの_nextにジェネレータが渡されたら、関数を呼ぶ代わりに保存しておいたフレームを復元して続きを実行する
+    fn resume_generator(&mut self, function: &Mutex<dyn KirlRustFunction>) -> KirlRuntimeResult<Option<Control>> {
+        if !function.lock().map_err(|_| KirlRuntimeErrorKind::PoisonedValue)?.resumes_generator() {
+            return Ok(None);
+        }
+        let generator = match GeneratorValue::try_from_kirl_value(pop(&mut self.local_stack)?) {
+            Ok(generator) => generator,
+            Err(value) => {
+                self.local_stack.push(value);
+                return Ok(None);
+            }
+        };
+        let state = {
+            let mut generator_value = write(&generator)?;
+            if generator_value.running {
+                return Err(KirlRuntimeErrorKind::GeneratorAlreadyRunning);
+            }
+            let state = generator_value.state.take();
+            generator_value.running = state.is_some();
+            state
+        };
+        match state {
+            None => {
+                self.local_stack.push(Arc::new(RwLock::new(Vec::<Arc<dyn KirlVMValueLock>>::new().into_boxed_slice())));
+                Ok(Some(Control::Next))
+            }
+            Some((program_counter, locals)) => {
+                self.call(program_counter);
+                self.global_stack.extend(locals);
+                self.generator_stack.push((self.program_counter_stack.len(), generator));
+                Ok(Some(Control::Jumped))
+            }
+        }
+    }
+
+    fn step(
+        &mut self,
+        instruction: KirlByteCode,
+        KirlVMExecutable {
+            static_value_generators,
+            rust_functions,
+            function_pointers,
+            member_names,
+            types,
+            ..
+        }: &KirlVMExecutable,
+    ) -> KirlRuntimeResult<Control> {
         match instruction.opcode() {
             KirlByteCodeOpcode::LoadStaticValue => {
                 let operand = instruction.operand();
-                local_stack.push(static_value_generators[operand as usize]());
+                self.local_stack.push(static_value_generators[operand as usize]());
+            }
+            KirlByteCodeOpcode::LoadKirlFunction => {
+                let operand = instruction.operand();
+                let function_type = types[self.additional_operand()? as usize].clone();
+                let body = FunctionBody::Kirl { function_pointer: function_pointers[operand as usize], captures: Vec::new() };
+                self.local_stack.push(Arc::new(RwLock::new(FunctionValue { body, function_type })));
+            }
+            KirlByteCodeOpcode::LoadRustFunction => {
+                let operand = instruction.operand();
+                let function_type = types[self.additional_operand()? as usize].clone();
+                let body = FunctionBody::Rust(operand as usize);
+                self.local_stack.push(Arc::new(RwLock::new(FunctionValue { body, function_type })));
             }
             KirlByteCodeOpcode::Load => {
-                let operand = instruction.operand() as usize + global_stack_offset;
-                local_stack.push(global_stack[operand].clone());
+                let operand = instruction.operand() as usize + self.global_stack_offset;
+                let value = self.global_stack.get(operand).ok_or(KirlRuntimeErrorKind::VariableNotFound(operand - self.global_stack_offset))?;
+                self.local_stack.push(Arc::clone(value));
             }
             KirlByteCodeOpcode::Store => {
-                let operand = instruction.operand() as usize + global_stack_offset;
-                if global_stack.len() <= operand {
-                    global_stack.resize_with(operand + 1, || Arc::new(RwLock::new(Vec::new().into_boxed_slice())));
+                let operand = instruction.operand() as usize + self.global_stack_offset;
+                if self.global_stack.len() <= operand {
+                    self.global_stack.resize_with(operand + 1, || Arc::new(RwLock::new(Vec::new().into_boxed_slice())));
                 }
-                global_stack[operand] = local_stack.pop().expect("");
+                self.global_stack[operand] = pop(&mut self.local_stack)?;
+            }
+            KirlByteCodeOpcode::LoadGlobal => {
+                let operand = instruction.operand();
+                let value = self.globals.get(operand as usize).ok_or(KirlRuntimeErrorKind::GlobalVariableNotFound(operand as usize))?;
+                let value = value.as_ref().ok_or(KirlRuntimeErrorKind::UninitializedGlobalVariable(operand as usize))?;
+                self.local_stack.push(Arc::clone(value));
+            }
+            KirlByteCodeOpcode::StoreGlobal => {
+                let operand = instruction.operand();
+                let value = pop(&mut self.local_stack)?;
+                *self.globals.get_mut(operand as usize).ok_or(KirlRuntimeErrorKind::GlobalVariableNotFound(operand as usize))? = Some(value);
             }
             KirlByteCodeOpcode::JumpIfTrue => {
-                let condition = local_stack.pop().expect("");
-                let condition = bool::try_from_kirl_value(condition).expect("");
-                if *condition.read().expect("") {
-                    let operand = instruction.operand_signed();
-                    program_counter = ((program_counter as isize) + (operand as isize)) as usize;
-                    continue;
+                let condition = downcast::<bool>(pop(&mut self.local_stack)?)?;
+                let condition = *read(&condition)?;
+                if condition {
+                    self.jump(instruction.operand_signed());
+                    return Ok(Control::Jumped);
                 }
             }
             KirlByteCodeOpcode::JumpIfHasType => {
-                let ty = &types[additional_operand.pop().expect("") as usize];
-                let condition = local_stack.last().expect("");
-                if condition.get_type().is_a(ty) {
-                    let operand = instruction.operand_signed();
-                    program_counter = ((program_counter as isize) + (operand as isize)) as usize;
-                    continue;
+                let ty = &types[self.additional_operand()? as usize];
+                let condition = self.local_stack.last().ok_or(KirlRuntimeErrorKind::StackUnderflow)?;
+                if condition.get_type().is_a_ignoring_generics_arguments(ty) {
+                    self.jump(instruction.operand_signed());
+                    return Ok(Control::Jumped);
                 }
             }
             KirlByteCodeOpcode::Jump => {
-                let operand = instruction.operand_signed();
-                program_counter = ((program_counter as isize) + (operand as isize)) as usize;
-                continue;
+                self.jump(instruction.operand_signed());
+                return Ok(Control::Jumped);
             }
             KirlByteCodeOpcode::CallKirlFunction => {
                 let operand = instruction.operand();
-                program_counter_stack.push(program_counter);
-                program_counter = function_pointers[operand as usize];
-                global_stack_offset_stack.push(global_stack_offset);
-                global_stack_offset = global_stack.len();
-                continue;
+                self.call(function_pointers[operand as usize]);
+                return Ok(Control::Jumped);
+            }
+            KirlByteCodeOpcode::CallIndirect => {
+                let function = downcast::<FunctionValue>(pop(&mut self.local_stack)?)?;
+                let function = read(&function)?;
+                match &function.body {
+                    FunctionBody::Kirl { function_pointer, captures } => {
+                        for capture in captures.iter().rev() {
+                            self.local_stack.push(Arc::clone(capture));
+                        }
+                        self.call(*function_pointer);
+                        return Ok(Control::Jumped);
+                    }
+                    FunctionBody::Rust(index) => {
+                        if let Some(control) = self.resume_generator(&rust_functions[*index])? {
+                            return Ok(control);
+                        }
+                        call_rust_function(&rust_functions[*index], &mut self.local_stack)?
+                    }
+                }
             }
             KirlByteCodeOpcode::CallRustFunction => {
                 let operand = instruction.operand();
-                let mut function = rust_functions[operand as usize].lock().unwrap();
-                let argument_count = function.argument_count();
-                let mut arguments = Vec::with_capacity(argument_count);
-                for _ in 0..argument_count {
-                    arguments.push(local_stack.pop().expect(""));
+                if let Some(control) = self.resume_generator(&rust_functions[operand as usize])? {
+                    return Ok(control);
                 }
-                let ret = function.call(arguments).expect("TODO:組み込み関数が失敗した場合(ここ)の処理");
-                local_stack.push(ret);
+                call_rust_function(&rust_functions[operand as usize], &mut self.local_stack)?;
             }
             KirlByteCodeOpcode::Return => {
-                if program_counter_stack.is_empty() {
-                    return;
+                if self.program_counter_stack.is_empty() {
+                    return Ok(Control::Exit(pop(&mut self.local_stack)?));
                 }
-                program_counter = program_counter_stack.pop().expect("");
-                global_stack_offset = global_stack_offset_stack.pop().expect("");
+                // ジェネレータの本体が最後まで実行された
+                if matches!(self.generator_stack.last(), Some((depth, _)) if *depth == self.program_counter_stack.len()) {
+                    let (_, generator) = self.generator_stack.pop().unwrap();
+                    let mut generator_value = write(&generator)?;
+                    generator_value.state = None;
+                    generator_value.running = false;
+                }
+                self.ret()?;
+            }
+            KirlByteCodeOpcode::Yield => {
+                let value = pop(&mut self.local_stack)?;
+                let (_, generator) = self.generator_stack.pop().ok_or(KirlRuntimeErrorKind::StackUnderflow)?;
+                let mut generator_value = write(&generator)?;
+                let mut locals = self.global_stack.split_off(self.global_stack_offset);
+                locals.truncate(generator_value.frame_size);
+                generator_value.state = Some((self.program_counter + 1, locals));
+                generator_value.running = false;
+                self.local_stack.push(Arc::new(RwLock::new(HashMap::from([("value".to_string(), value)]))));
+                self.ret()?;
             }
             KirlByteCodeOpcode::Nop => {}
+            KirlByteCodeOpcode::Pop => {
+                pop(&mut self.local_stack)?;
+            }
             KirlByteCodeOpcode::AccessTupleItem => {
                 let operand = instruction.operand();
-                let value = local_stack.pop().expect("");
-                let value = Box::<[Arc<dyn KirlVMValueLock>]>::try_from_kirl_value(value).expect("");
-                let value = value.read().expect("");
-                let value = Arc::clone(&value[operand as usize]);
-                local_stack.push(value);
+                let value = downcast::<Box<[Arc<dyn KirlVMValueLock>]>>(pop(&mut self.local_stack)?)?;
+                let value = read(&value)?;
+                let value = Arc::clone(value.get(operand as usize).ok_or(KirlRuntimeErrorKind::TupleIndexOutOfRange(operand as usize))?);
+                self.local_stack.push(value);
             }
             KirlByteCodeOpcode::AccessMember => {
                 let operand = instruction.operand();
-                let value = local_stack.pop().expect("");
-                let value = HashMap::<String, Arc<dyn KirlVMValueLock>>::try_from_kirl_value(value).expect("");
-                let value = value.read().expect("");
-                let member = value.get(&member_names[operand as usize]).expect("");
-                local_stack.push(Arc::clone(member));
+                let member_name = &member_names[operand as usize];
+                let value = pop(&mut self.local_stack)?;
+                let member = match HashMap::<String, Arc<dyn KirlVMValueLock>>::try_from_kirl_value(value) {
+                    Ok(value) => {
+                        let value = read(&value)?;
+                        value.get(member_name).cloned()
+                    }
+                    Err(value) => {
+                        let value = downcast::<StructValue>(value)?;
+                        let value = read(&value)?;
+                        value.members.get(member_name).cloned()
+                    }
+                };
+                self.local_stack.push(member.ok_or_else(|| KirlRuntimeErrorKind::MemberNotFound(member_name.clone()))?);
             }
             KirlByteCodeOpcode::AssignTupleItem => {
                 let operand = instruction.operand();
-                let value = local_stack.pop().expect("");
-                let dest = local_stack.pop().expect("");
-                let dest = Box::<[Arc<dyn KirlVMValueLock>]>::try_from_kirl_value(dest).expect("");
-                dest.write().expect("")[operand as usize] = value;
+                let value = pop(&mut self.local_stack)?;
+                let dest = downcast::<Box<[Arc<dyn KirlVMValueLock>]>>(pop(&mut self.local_stack)?)?;
+                let mut dest = write(&dest)?;
+                *dest.get_mut(operand as usize).ok_or(KirlRuntimeErrorKind::TupleIndexOutOfRange(operand as usize))? = value;
             }
             KirlByteCodeOpcode::AssignMember => {
                 let operand = instruction.operand();
-                let value = local_stack.pop().expect("");
-                let dest = local_stack.pop().expect("");
-                let dest = HashMap::<String, Arc<dyn KirlVMValueLock>>::try_from_kirl_value(dest).expect("");
-                dest.write().expect("").insert(member_names[operand as usize].clone(), value);
+                let value = pop(&mut self.local_stack)?;
+                let dest = pop(&mut self.local_stack)?;
+                match HashMap::<String, Arc<dyn KirlVMValueLock>>::try_from_kirl_value(dest) {
+                    Ok(dest) => {
+                        write(&dest)?.insert(member_names[operand as usize].clone(), value);
+                    }
+                    Err(dest) => {
+                        let dest = downcast::<StructValue>(dest)?;
+                        write(&dest)?.members.insert(member_names[operand as usize].clone(), value);
+                    }
+                }
             }
             KirlByteCodeOpcode::ConstructStruct => {
                 let operand = instruction.operand();
                 let mut result = HashMap::with_capacity(operand as usize);
                 for _ in 0..operand {
-                    let name = local_stack.pop().expect("");
-                    let name = unwrap(String::try_from_kirl_value(name).expect(""));
-                    let value = local_stack.pop().expect("");
+                    let name = unwrap(downcast::<String>(pop(&mut self.local_stack)?)?)?;
+                    let value = pop(&mut self.local_stack)?;
                     result.insert(name, value);
                 }
-                local_stack.push(Arc::new(RwLock::new(result)));
+                self.local_stack.push(Arc::new(RwLock::new(result)));
+            }
+            KirlByteCodeOpcode::ConstructNamedStruct => {
+                let struct_type = types[self.additional_operand()? as usize].clone();
+                let operand = instruction.operand();
+                let mut members = HashMap::with_capacity(operand as usize);
+                for _ in 0..operand {
+                    let name = unwrap(downcast::<String>(pop(&mut self.local_stack)?)?)?;
+                    let value = pop(&mut self.local_stack)?;
+                    members.insert(name, value);
+                }
+                self.local_stack.push(Arc::new(RwLock::new(StructValue { struct_type, members })));
             }
             KirlByteCodeOpcode::ConstructTuple => {
                 let operand = instruction.operand();
                 let mut result = Vec::with_capacity(operand as usize);
                 for _ in 0..operand {
-                    result.push(local_stack.pop().expect(""));
+                    result.push(pop(&mut self.local_stack)?);
                 }
-                local_stack.push(Arc::new(RwLock::new(result.into_boxed_slice())));
+                self.local_stack.push(Arc::new(RwLock::new(result.into_boxed_slice())));
             }
             KirlByteCodeOpcode::ConstructArray => {
                 let operand = instruction.operand();
                 let mut result = Vec::with_capacity(operand as usize);
                 for _ in 0..operand {
-                    result.push(local_stack.pop().expect(""));
+                    result.push(pop(&mut self.local_stack)?);
                 }
-                local_stack.push(Arc::new(RwLock::new(result)));
+                self.local_stack.push(Arc::new(RwLock::new(result)));
+            }
+            KirlByteCodeOpcode::ConstructClosure => {
+                let function_type = types[self.additional_operand()? as usize].clone();
+                let capture_count = self.additional_operand()?;
+                let mut captures = Vec::with_capacity(capture_count as usize);
+                for _ in 0..capture_count {
+                    captures.push(pop(&mut self.local_stack)?);
+                }
+                let operand = instruction.operand_signed();
+                let function_pointer = ((self.program_counter as isize) + (operand as isize)) as usize;
+                self.local_stack.push(Arc::new(RwLock::new(FunctionValue { body: FunctionBody::Kirl { function_pointer, captures }, function_type })));
+            }
+            KirlByteCodeOpcode::ConstructGenerator => {
+                let generator_type = types[self.additional_operand()? as usize].clone();
+                let frame_size = self.additional_operand()? as usize;
+                let operand = instruction.operand_signed();
+                let program_counter = ((self.program_counter as isize) + (operand as isize)) as usize;
+                let locals = self.global_stack.get(self.global_stack_offset..).unwrap_or_default().iter().take(frame_size).cloned().collect();
+                self.local_stack.push(Arc::new(RwLock::new(GeneratorValue { state: Some((program_counter, locals)), frame_size, running: false, generator_type })));
             }
             KirlByteCodeOpcode::PushAdditionalOperand => {
                 let operand = instruction.operand();
-                additional_operand.push(operand);
+                self.additional_operand.push(operand);
+            }
+        }
+        Ok(Control::Next)
+    }
+}
+
+pub fn exec(executable: &KirlVMExecutable) -> Result<Arc<dyn KirlVMValueLock>, KirlRuntimeError> {
+    let mut state = KirlVMState {
+        local_stack: Vec::new(),
+        global_stack: Vec::new(),
+        global_stack_offset: 0,
+        global_stack_offset_stack: Vec::new(),
+        program_counter_stack: Vec::new(),
+        program_counter: executable.entry_point,
+        additional_operand: Vec::new(),
+        globals: (0..executable.global_count).map(|_| None).collect(),
+        generator_stack: Vec::new(),
+    };
+    loop {
+        let instruction = executable.bytecodes[state.program_counter];
+        match state.step(instruction, executable) {
+            Ok(Control::Next) => state.program_counter += 1,
+            Ok(Control::Jumped) => {}
+            Ok(Control::Exit(value)) => return Ok(value),
+            Err(kind) => {
+                let backtrace = iter::once(&state.program_counter)
+                    .chain(state.program_counter_stack.iter().rev())
+                    .map(|&program_counter| {
+                        let KirlFunctionDebugInfo { name, file_path } = executable.function_debug_info(program_counter).clone();
+                        KirlStackFrame { function_name: name, file_path, position: executable.source_position(program_counter) }
+                    })
+                    .collect();
+                return Err(KirlRuntimeError {
+                    opcode: instruction.opcode(),
+                    program_counter: state.program_counter,
+                    call_stack: state.program_counter_stack,
+                    backtrace,
+                    kind,
+                });
             }
         }
-        program_counter += 1;
     }
 }